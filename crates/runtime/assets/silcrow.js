@@ -17,6 +17,18 @@ function throwErr(msg) {
   if (DEBUG) throw new Error("[silcrow] " + msg);
 }
 
+// Echoes the instructions recorded server-side (feature `recorder`) for a response.
+function debugInstructions(recordId, headers) {
+  if (!DEBUG || !recordId) return;
+  const instructions = {};
+  headers.forEach(function (value, name) {
+    if (name.startsWith("silcrow-") && name !== "silcrow-debug-record") {
+      instructions[name.slice("silcrow-".length)] = decodeHeaderValue(value);
+    }
+  });
+  console.debug("[silcrow] instructions #" + recordId, instructions);
+}
+
 // /url-safety.js
 // ════════════════════════════════════════════════════════════
 // URL Safety — shared protocol & URL validation primitives
@@ -181,6 +193,72 @@ function safeSetHTML(el, raw) {
   el.innerHTML = doc.body.innerHTML;
 }
 
+const SWAP_POSITIONS = new Set(["beforebegin", "afterbegin", "beforeend", "afterend"]);
+
+// Places sanitized markup according to a silcrow-swap mode; unknown modes
+// fall back to replacing the children.
+function safeSwapHTML(el, raw, mode) {
+  if (!mode || mode === "innerHTML") {
+    safeSetHTML(el, raw);
+    return;
+  }
+
+  const doc = new DOMParser().parseFromString(raw == null ? "" : String(raw), "text/html");
+  sanitizeTree(doc.body);
+
+  if (mode === "outerHTML") {
+    el.outerHTML = doc.body.innerHTML;
+  } else if (SWAP_POSITIONS.has(mode)) {
+    el.insertAdjacentHTML(mode, doc.body.innerHTML);
+  } else {
+    warn("Unknown silcrow-swap mode: " + mode);
+    el.innerHTML = doc.body.innerHTML;
+  }
+}
+
+// Elements matching `preserve` (the silcrow-preserve header) survive an
+// innerHTML/outerHTML swap as the same nodes, keeping playback, open
+// <details> and typed text. New matches take an old node with the same id,
+// else the next unclaimed one without an id.
+function swapPreserving(el, raw, mode, preserve) {
+  const replaces = !mode || mode === "innerHTML" || mode === "outerHTML";
+  const scope = mode === "outerHTML" ? el.parentNode : el;
+  if (!preserve || !replaces || !scope) {
+    safeSwapHTML(el, raw, mode);
+    return;
+  }
+
+  let kept, outside;
+  try {
+    kept = Array.from(el.querySelectorAll(preserve));
+    outside = new Set(Array.from(scope.querySelectorAll(preserve)).filter(n => !el.contains(n)));
+  } catch (e) {
+    warn("Invalid silcrow-preserve selector: " + preserve);
+    safeSwapHTML(el, raw, mode);
+    return;
+  }
+  const playing = kept.filter(n => n instanceof HTMLMediaElement && !n.paused);
+  const focused = kept.find(n => n === document.activeElement || n.contains(document.activeElement))
+    ? document.activeElement
+    : null;
+
+  safeSwapHTML(el, raw, mode);
+
+  const claimed = new Set();
+  for (const fresh of scope.querySelectorAll(preserve)) {
+    if (outside.has(fresh)) continue;
+    const old = fresh.id
+      ? kept.find(n => n.id === fresh.id && !claimed.has(n))
+      : kept.find(n => !n.id && !claimed.has(n));
+    if (!old) continue;
+    claimed.add(old);
+    fresh.replaceWith(old);
+  }
+
+  playing.forEach(n => n.play().catch(() => {}));
+  if (focused && focused.isConnected) focused.focus();
+}
+
 // /toasts.js
 // ════════════════════════════════════════════════════════════
 // Toasts — notification processing
@@ -188,11 +266,25 @@ function safeSetHTML(el, raw) {
 
 let toastHandler = null;
 
+// Handlers get (message, level, options); options carries the optional
+// duration_ms, id, action ({label, href}) and key a structured toast was sent
+// with. A handler should replace the toast it is showing under the same key.
+// options.html is inline markup the server already sanitized.
+function showToast(t) {
+  const options = {};
+  if (t.html != null) options.html = t.html;
+  if (t.duration_ms != null) options.duration_ms = t.duration_ms;
+  if (t.id != null) options.id = t.id;
+  if (t.key != null) options.key = t.key;
+  if (t.action) options.action = t.action;
+  toastHandler(t.message, t.level || "info", options);
+}
+
 function processToasts(isJSON, content = null) {
   if (!toastHandler) return;
 
   if (isJSON && content && content._toasts) {
-    content._toasts.forEach(t => toastHandler(t.message, t.level));
+    content._toasts.forEach(showToast);
     delete content._toasts;
 
     if (content.data !== undefined && Object.keys(content).length === 1) {
@@ -205,7 +297,7 @@ function processToasts(isJSON, content = null) {
       try {
         const rawJSON = decodeURIComponent(match[2]);
         const toasts = JSON.parse(rawJSON);
-        toasts.forEach(t => toastHandler(t.message, t.level));
+        toasts.forEach(showToast);
       } catch (e) {
         console.error("Failed to parse toasts", e);
       }
@@ -214,6 +306,16 @@ function processToasts(isJSON, content = null) {
   }
 }
 
+// Toasts sent in a silcrow-toast header instead of the cookie or body.
+function processToastHeader(header) {
+  if (!header || !toastHandler) return;
+  try {
+    JSON.parse(header).forEach(showToast);
+  } catch (e) {
+    warn("Failed to parse silcrow-toast header: " + e.message);
+  }
+}
+
 function setToastHandler(handler) {
   toastHandler = handler;
   processToasts(false);
@@ -523,6 +625,9 @@ function buildMaps(root) {
   return {scalars: scanBindings(root), collections};
 }
 
+// Last data patched into each root: the document JSON Patch ops apply to.
+const lastPatched = new WeakMap();
+
 function patch(data, root, options = {}) {
   const element = resolveRoot(root);
 
@@ -566,6 +671,8 @@ function patch(data, root, options = {}) {
     }
   });
 
+  lastPatched.set(element, transformedData);
+
   element.dispatchEvent(new CustomEvent("silcrow:patched", {
     bubbles: true,
     detail: {paths: Array.from(instance.scalars.keys())},
@@ -578,6 +685,129 @@ function invalidate(root) {
   element.querySelectorAll('[\\:key]').forEach(el => localBindingsCache.delete(el));
 }
 
+// RFC 6902 operations against the root's last patched data. Ops apply in
+// order to a copy; any failure (including a failed "test") drops them all.
+function applyJsonPatch(ops, root) {
+  const element = resolveRoot(root);
+  if (!Array.isArray(ops)) {
+    warn("JSON Patch must be an array of operations");
+    return;
+  }
+  let doc = safeClone(lastPatched.get(element) ?? {});
+  try {
+    for (const op of ops) doc = applyPatchOp(doc, op);
+  } catch (err) {
+    warn("JSON Patch not applied: " + err.message);
+    return;
+  }
+  patch(doc, element);
+}
+
+function applyPatchOp(doc, op) {
+  const path = parsePointer(op?.path);
+  switch (op.op) {
+    case "add":
+      return pointerAdd(doc, path, safeClone(op.value));
+    case "remove":
+      return pointerRemove(doc, path);
+    case "replace":
+      pointerGet(doc, path);
+      return pointerAdd(pointerRemove(doc, path), path, safeClone(op.value));
+    case "move": {
+      const from = parsePointer(op.from);
+      const value = pointerGet(doc, from);
+      return pointerAdd(pointerRemove(doc, from), path, value);
+    }
+    case "copy":
+      return pointerAdd(doc, path, safeClone(pointerGet(doc, parsePointer(op.from))));
+    case "test":
+      if (!jsonEqual(pointerGet(doc, path), op.value)) {
+        throw new Error("test failed at " + op.path);
+      }
+      return doc;
+    default:
+      throw new Error("unknown op " + op.op);
+  }
+}
+
+function parsePointer(pointer) {
+  if (pointer === "") return [];
+  if (typeof pointer !== "string" || !pointer.startsWith("/")) {
+    throw new Error("invalid pointer " + pointer);
+  }
+  return pointer.slice(1).split("/").map(t => {
+    const token = t.replace(/~1/g, "/").replace(/~0/g, "~");
+    if (BLOCKED_KEYS.has(token)) throw new Error("blocked key " + token);
+    return token;
+  });
+}
+
+function arrayIndex(token, limit) {
+  if (!/^(0|[1-9][0-9]*)$/.test(token) || Number(token) >= limit) {
+    throw new Error("bad array index " + token);
+  }
+  return Number(token);
+}
+
+function pointerGet(doc, tokens) {
+  let current = doc;
+  for (const token of tokens) {
+    if (Array.isArray(current)) {
+      current = current[arrayIndex(token, current.length)];
+    } else if (current && typeof current === "object" &&
+      Object.prototype.hasOwnProperty.call(current, token)) {
+      current = current[token];
+    } else {
+      throw new Error("missing path /" + tokens.join("/"));
+    }
+  }
+  return current;
+}
+
+// Removing the root leaves nothing; "replace" at "" adds the new root back.
+function pointerRemove(doc, tokens) {
+  if (!tokens.length) return undefined;
+  const parent = pointerGet(doc, tokens.slice(0, -1));
+  const key = tokens[tokens.length - 1];
+  if (Array.isArray(parent)) {
+    parent.splice(arrayIndex(key, parent.length), 1);
+  } else if (parent && typeof parent === "object" &&
+    Object.prototype.hasOwnProperty.call(parent, key)) {
+    delete parent[key];
+  } else {
+    throw new Error("missing path /" + tokens.join("/"));
+  }
+  return doc;
+}
+
+function pointerAdd(doc, tokens, value) {
+  if (!tokens.length) return value;
+  const parent = pointerGet(doc, tokens.slice(0, -1));
+  const key = tokens[tokens.length - 1];
+  if (Array.isArray(parent)) {
+    const index = key === "-" ? parent.length : arrayIndex(key, parent.length + 1);
+    parent.splice(index, 0, value);
+  } else if (parent && typeof parent === "object") {
+    parent[key] = value;
+  } else {
+    throw new Error("no container at /" + tokens.slice(0, -1).join("/"));
+  }
+  return doc;
+}
+
+function jsonEqual(a, b) {
+  if (a === b) return true;
+  if (Array.isArray(a)) {
+    return Array.isArray(b) && a.length === b.length && a.every((v, i) => jsonEqual(v, b[i]));
+  }
+  if (a && b && typeof a === "object" && typeof b === "object" && !Array.isArray(b)) {
+    const keys = Object.keys(a);
+    return keys.length === Object.keys(b).length &&
+      keys.every(k => Object.prototype.hasOwnProperty.call(b, k) && jsonEqual(a[k], b[k]));
+  }
+  return false;
+}
+
 function stream(root) {
   let pending = null;
   return function(data) {
@@ -599,8 +829,38 @@ const liveConnections = new Map();      // element → state (SSE) or hub-state
 const liveConnectionsByUrl = new Map(); // url → Set<state>  (kept for resolveLiveStates compat)
 const sseHubs = new Map();              // normalized url → SseHub
 const MAX_BACKOFF = 30000;
+const WS_SUPERSEDED_CODE = 4001;          // matches the server's connection limiter
 const LIVE_HTTP_PROTOCOLS = new Set(["http:", "https:"]);
 
+// Handlers registered with Silcrow.on, by custom event name.
+const customHandlers = new Map();
+
+function onCustom(name, handler) {
+  if (typeof handler !== "function") return;
+  if (!customHandlers.has(name)) customHandlers.set(name, new Set());
+  customHandlers.get(name).add(handler);
+}
+
+function offCustom(name, handler) {
+  const handlers = customHandlers.get(name);
+  if (!handlers) return;
+  if (handler) handlers.delete(handler);
+  else handlers.clear();
+  if (handlers.size === 0) customHandlers.delete(name);
+}
+
+function runCustomHandlers(name, data, meta) {
+  const handlers = customHandlers.get(name);
+  if (!handlers) return;
+  handlers.forEach(function (handler) {
+    try {
+      handler(data, meta);
+    } catch (err) {
+      warn("Handler for custom event " + name + " failed: " + err.message);
+    }
+  });
+}
+
 function isLikelyLiveUrl(value) {
   return (
     typeof value === "string" &&
@@ -640,6 +900,21 @@ function resolveLiveTarget(selector, fallback) {
   return document.querySelector(selector) || null;
 }
 
+// push_history from SSE or WS: same-origin URLs only, and a pushState that
+// throws (bad URL, rate-limited by the browser) must not break the stream.
+function pushLiveHistory(rawUrl, protocol) {
+  try {
+    const parsed = new URL(String(rawUrl).trim(), location.origin);
+    if (parsed.origin !== location.origin) {
+      warn("Rejected cross-origin " + protocol + " push_history URL: " + parsed.href);
+      return;
+    }
+    history.pushState({silcrow: true, url: parsed.href}, "", parsed.href);
+  } catch (err) {
+    warn("Failed to push " + protocol + " history: " + err.message);
+  }
+}
+
 function applyLivePatchPayload(payload, fallbackTarget) {
   if (
     payload &&
@@ -860,6 +1135,39 @@ function connectSseHub(hub) {
     }
   });
 
+  es.addEventListener("json_patch", function (e) {
+    try {
+      const payload = JSON.parse(e.data);
+      let target = payload?.target ? document.querySelector(payload.target) : null;
+      if (!target && hub.subscribers.size > 0) {
+        target = hub.subscribers.values().next().value;
+      }
+      if (target) applyJsonPatch(payload?.ops, target);
+    } catch (err) {
+      warn("Failed to parse SSE json_patch event: " + err.message);
+    }
+  });
+
+  es.addEventListener("patches", function (e) {
+    try {
+      const payload = JSON.parse(e.data);
+      if (!Array.isArray(payload)) return;
+      // Resolve every target before touching the DOM so one patch cannot
+      // move or remove the element a later one addresses.
+      const resolved = payload.map(function (entry) {
+        return {
+          target: entry && entry.target ? document.querySelector(entry.target) : null,
+          data: entry ? entry.data : undefined,
+        };
+      });
+      resolved.forEach(function (entry) {
+        if (entry.target && entry.data !== undefined) patch(entry.data, entry.target);
+      });
+    } catch (err) {
+      warn("Failed to parse SSE patches event: " + err.message);
+    }
+  });
+
   es.addEventListener("html", function (e) {
     try {
       const payload = JSON.parse(e.data);
@@ -891,15 +1199,77 @@ function connectSseHub(hub) {
   es.addEventListener("custom", function (e) {
     try {
       const payload = JSON.parse(e.data);
-      document.dispatchEvent(new CustomEvent("silcrow:sse:" + (payload.event || "custom"), {
+      const name = payload.event || "custom";
+      // A dispatch hint from the server: fire on that element instead of document.
+      const target = payload.target ? document.querySelector(payload.target) : null;
+      (target || document).dispatchEvent(new CustomEvent("silcrow:sse:" + name, {
         bubbles: true,
         detail: {url: hub.url, data: payload.data},
       }));
+      runCustomHandlers(name, payload.data, {url: hub.url, protocol: "sse", target});
     } catch (err) {
       warn("Failed to parse SSE custom event: " + err.message);
     }
   });
 
+  es.addEventListener("toast", function (e) {
+    try {
+      const payload = JSON.parse(e.data);
+      if (toastHandler && payload.message) {
+        showToast(payload);
+      }
+    } catch (err) {
+      warn("Failed to parse SSE toast event: " + err.message);
+    }
+  });
+
+  es.addEventListener("trigger", function (e) {
+    try {
+      const payload = JSON.parse(e.data);
+      if (payload.event) {
+        document.dispatchEvent(
+          new CustomEvent(payload.event, {bubbles: true, detail: payload.data})
+        );
+      }
+    } catch (err) {
+      warn("Failed to parse SSE trigger event: " + err.message);
+    }
+  });
+
+  es.addEventListener("push_history", function (e) {
+    if (e.data) {
+      pushLiveHistory(e.data, "SSE");
+    }
+  });
+
+  es.addEventListener("heartbeat", function (e) {
+    try {
+      const payload = JSON.parse(e.data);
+      document.dispatchEvent(new CustomEvent("silcrow:sse:heartbeat", {
+        bubbles: true,
+        detail: {url: hub.url, time: payload.time, status: payload.status},
+      }));
+    } catch (err) {
+      warn("Failed to parse SSE heartbeat event: " + err.message);
+    }
+  });
+
+  es.addEventListener("close", function () {
+    // The server asked us to stop: no reconnect until Silcrow.reconnect.
+    hub.paused = true;
+    es.close();
+    hub.es = null;
+    for (const state of liveConnections.values()) {
+      if (state.hub === hub) state.paused = true;
+    }
+    hub.subscribers.forEach(function (el) {
+      document.dispatchEvent(new CustomEvent("silcrow:live:close", {
+        bubbles: true,
+        detail: {root: el, url: hub.url, protocol: "sse"},
+      }));
+    });
+  });
+
   es.onerror = function () {
     es.close();
     hub.es = null;
@@ -1062,6 +1432,7 @@ function createWsHub(url) {
     backoff: 1000,
     paused: false,
     reconnectTimer: null,
+    resumeToken: null,
   };
 }
 
@@ -1091,7 +1462,15 @@ function connectWsHub(hub) {
   if (hub.paused) return;
   if (hub.socket && hub.socket.readyState <= WebSocket.OPEN) return; // already connected/connecting
 
-  const socket = new WebSocket(hub.url);
+  let socketUrl = hub.url;
+  if (hub.resumeToken) {
+    const resumeUrl = new URL(hub.url, location.href);
+    resumeUrl.searchParams.set("resume", hub.resumeToken);
+    socketUrl = resumeUrl.href;
+  }
+  // Only JSON is decoded here; asking for it keeps the server from
+  // choosing a binary format such as silcrow.msgpack.
+  const socket = new WebSocket(socketUrl, ["silcrow.json"]);
   hub.socket = socket;
 
   socket.onopen = function () {
@@ -1112,7 +1491,7 @@ function connectWsHub(hub) {
     dispatchWsMessage(hub, e.data);
   };
 
-  socket.onclose = function () {
+  socket.onclose = function (e) {
     hub.socket = null;
     if (hub.paused) return;
     if (hub.subscribers.size === 0) {
@@ -1120,6 +1499,22 @@ function connectWsHub(hub) {
       return;
     }
 
+    if (e.code === WS_SUPERSEDED_CODE) {
+      // A newer tab took this connection's slot: no reconnect until
+      // Silcrow.reconnect, or the two would keep displacing each other.
+      hub.paused = true;
+      for (const state of liveConnections.values()) {
+        if (state.hub === hub) state.paused = true;
+      }
+      hub.subscribers.forEach(function (el) {
+        document.dispatchEvent(new CustomEvent("silcrow:live:close", {
+          bubbles: true,
+          detail: {root: el, url: hub.url, protocol: "ws"},
+        }));
+      });
+      return;
+    }
+
     const reconnectIn = hub.backoff;
 
     document.dispatchEvent(
@@ -1148,8 +1543,26 @@ function connectWsHub(hub) {
 }
 
 function dispatchWsMessage(hub, rawData) {
+  if (typeof rawData !== "string") {
+    warn("Ignoring binary WS frame; silcrow.js only decodes JSON");
+    return;
+  }
+  let parsed;
+  try {
+    parsed = JSON.parse(rawData);
+  } catch (err) {
+    warn("Failed to parse WS message: " + err.message);
+    return;
+  }
+  // A batch frame carries several events, applied in order
+  const events = Array.isArray(parsed) ? parsed : [parsed];
+  for (const msg of events) {
+    dispatchWsEvent(hub, msg);
+  }
+}
+
+function dispatchWsEvent(hub, msg) {
   try {
-    const msg = JSON.parse(rawData);
     const type = msg && msg.type;
 
     let targets;
@@ -1166,6 +1579,10 @@ function dispatchWsMessage(hub, rawData) {
           patch(msg.data, el);
         }
       }
+    } else if (type === "json_patch") {
+      for (const el of targets) {
+        applyJsonPatch(msg.ops, el);
+      }
     } else if (type === "html") {
       for (const el of targets) {
         safeSetHTML(el, msg.markup == null ? "" : String(msg.markup));
@@ -1180,18 +1597,43 @@ function dispatchWsMessage(hub, rawData) {
         navigate(msg.path.trim(), {trigger: "ws"});
       }
     } else if (type === "custom") {
+      // Session token from a resumable hub, sent back on reconnect
+      if (msg.event === "silcrow:resume" && msg.data && msg.data.token) {
+        hub.resumeToken = msg.data.token;
+      }
       // Custom event dispatched once on document
+      const name = msg.event || "message";
       document.dispatchEvent(
-        new CustomEvent("silcrow:ws:" + (msg.event || "message"), {
+        new CustomEvent("silcrow:ws:" + name, {
           bubbles: true,
-          detail: {url: hub.url, data: msg.data},
+          detail: {url: hub.url, channel: msg.channel, data: msg.data},
         })
       );
+      runCustomHandlers(name, msg.data, {
+        url: hub.url,
+        protocol: "ws",
+        channel: msg.channel,
+        target: null,
+      });
+    } else if (type === "toast") {
+      if (toastHandler && msg.message) {
+        showToast(msg);
+      }
+    } else if (type === "trigger") {
+      if (msg.event) {
+        document.dispatchEvent(
+          new CustomEvent(msg.event, {bubbles: true, detail: msg.data})
+        );
+      }
+    } else if (type === "push_history") {
+      if (msg.url) {
+        pushLiveHistory(msg.url, "WS");
+      }
     } else {
       warn("Unknown WS event type: " + type);
     }
   } catch (err) {
-    warn("Failed to parse WS message: " + err.message);
+    warn("Failed to apply WS event: " + err.message);
   }
 }
 
@@ -1298,6 +1740,8 @@ const MAX_CACHE = 50;
 const abortMap = new WeakMap();
 let routeHandler = null;
 let errorHandler = null;
+// Asks the user about a silcrow-confirm; may return a boolean or a promise.
+let confirmHandler = (message) => window.confirm(message);
 const responseCache = new Map();
 const preloadInflight = new Map();
 
@@ -1409,21 +1853,140 @@ function bustCacheOnMutation() {
 }
 
 // ── Side-Effect Header Processing ──────────────────────────
+// Client functions the server can call by name through silcrow-hook. Only
+// registered hooks run; the header never carries code.
+const hooks = new Map();
+
+function registerHook(name, fn) {
+  if (typeof fn === "function") hooks.set(name, fn);
+  else hooks.delete(name);
+}
+
+function runHooks(header, target) {
+  let calls;
+  try {
+    calls = typeof header === "string" ? JSON.parse(header) : header;
+  } catch (e) {
+    warn("Failed to parse silcrow-hook header: " + e.message);
+    return;
+  }
+  for (const call of Array.isArray(calls) ? calls : [calls]) {
+    const fn = call && hooks.get(call.name);
+    if (!fn) {
+      warn("No hook registered for " + (call && call.name));
+      continue;
+    }
+    try {
+      fn(call.args, {target: target || null});
+    } catch (err) {
+      warn("Hook " + call.name + " failed: " + err.message);
+    }
+  }
+}
+
+// silcrow-meta: {name: content}, updating or adding <meta name> in <head>.
+function applyMeta(header) {
+  let tags;
+  try {
+    tags = JSON.parse(header);
+  } catch (e) {
+    warn("Failed to parse silcrow-meta header: " + e.message);
+    return;
+  }
+  if (!tags || typeof tags !== "object") return;
+  for (const [name, content] of Object.entries(tags)) {
+    let tag = Array.from(document.head.querySelectorAll("meta[name]"))
+      .find(m => m.getAttribute("name") === name);
+    if (!tag) {
+      tag = document.createElement("meta");
+      tag.setAttribute("name", name);
+      document.head.appendChild(tag);
+    }
+    tag.setAttribute("content", String(content));
+  }
+}
+
+// silcrow-poll: milliseconds between fresh GETs of `url` into `target`, or
+// "stop". One poll per target; a new interval replaces the old one, and a
+// target that leaves the page stops polling.
+const pollTimers = new Map(); // element → interval id
+
+function stopPoll(target) {
+  const timer = pollTimers.get(target);
+  if (timer === undefined) return;
+  clearInterval(timer);
+  pollTimers.delete(target);
+}
+
+function schedulePoll(header, url, target) {
+  const el = target || document.body;
+  stopPoll(el);
+  if (header.trim() === "stop") return;
+  const interval = Number(header);
+  if (!Number.isFinite(interval) || interval <= 0) {
+    warn("Invalid silcrow-poll interval: " + header);
+    return;
+  }
+  const timer = setInterval(() => {
+    if (!el.isConnected) {
+      stopPoll(el);
+      return;
+    }
+    navigate(url, {target: el, skipHistory: true, trigger: "poll", fresh: true});
+  }, interval);
+  pollTimers.set(el, timer);
+}
+
+function stopAllPolls() {
+  pollTimers.forEach(timer => clearInterval(timer));
+  pollTimers.clear();
+}
+
+// silcrow-confirm: ask first, then send the follow-up request. Deferred a
+// tick so the swap that came with it is painted before a native dialog.
+function runConfirm(header, target) {
+  let request;
+  try {
+    request = JSON.parse(header);
+  } catch (e) {
+    warn("Failed to parse silcrow-confirm header: " + e.message);
+    return;
+  }
+  if (!request || typeof request.path !== "string") return;
+  const method = HTTP_METHODS.includes(request.method) ? request.method : "POST";
+  setTimeout(async () => {
+    try {
+      if (!(await confirmHandler(String(request.message ?? "")))) return;
+    } catch (err) {
+      warn("Confirm handler failed: " + err.message);
+      return;
+    }
+    navigate(request.path, {method, target: target || null, trigger: "confirm"});
+  }, 0);
+}
+
 function processSideEffectHeaders(sideEffects, primaryTarget) {
   if (!sideEffects) return;
 
-  // Order: patch → invalidate → navigate → sse
+  // Order: patch → invalidate → remove → title/meta → hook → poll →
+  // confirm → navigate → sse
   if (sideEffects.patch) {
     try {
-      const payload = JSON.parse(sideEffects.patch);
-      if (
-        payload &&
-        typeof payload === "object" &&
-        payload.target &&
-        Object.prototype.hasOwnProperty.call(payload, "data")
-      ) {
+      // A string from the silcrow-patch header, or the already-parsed list
+      // carried in the body. Entries carry either `data` or JSON Patch `ops`.
+      const parsed = typeof sideEffects.patch === "string"
+        ? JSON.parse(sideEffects.patch)
+        : sideEffects.patch;
+      const payloads = Array.isArray(parsed) ? parsed : [parsed];
+      for (const payload of payloads) {
+        if (!payload || typeof payload !== "object" || !payload.target) continue;
         const el = document.querySelector(payload.target);
-        if (el) patch(payload.data, el);
+        if (!el) continue;
+        if (Array.isArray(payload.ops)) {
+          applyJsonPatch(payload.ops, el);
+        } else if (Object.prototype.hasOwnProperty.call(payload, "data")) {
+          patch(payload.data, el);
+        }
       }
     } catch (e) {
       warn("Failed to process silcrow-patch header: " + e.message);
@@ -1435,6 +1998,23 @@ function processSideEffectHeaders(sideEffects, primaryTarget) {
     if (el) invalidate(el);
   }
 
+  if (sideEffects.remove) {
+    try {
+      document.querySelectorAll(sideEffects.remove).forEach(el => el.remove());
+    } catch (e) {
+      warn("Invalid silcrow-remove selector: " + sideEffects.remove);
+    }
+  }
+
+  if (sideEffects.title != null) document.title = sideEffects.title;
+  if (sideEffects.meta) applyMeta(sideEffects.meta);
+
+  if (sideEffects.hook) runHooks(sideEffects.hook, primaryTarget);
+
+  if (sideEffects.poll) schedulePoll(sideEffects.poll, sideEffects.pollUrl, primaryTarget);
+
+  if (sideEffects.confirm) runConfirm(sideEffects.confirm, primaryTarget);
+
   if (sideEffects.navigate) {
     navigate(sideEffects.navigate, {trigger: "header"});
   }
@@ -1457,11 +2037,27 @@ function processSideEffectHeaders(sideEffects, primaryTarget) {
 }
 
 // ── Fetch Request Construction ─────────────────────────────
-function buildFetchOptions(method, body, wantsHTML, signal) {
+// silcrow-target carries the selector of the element being updated, or
+// "true" when there is none to name (e.g. the whole body).
+function targetHeaderValue(targetEl, targetSelector) {
+  if (targetSelector) return encodeHeaderValue(targetSelector);
+  if (targetEl && targetEl !== document.body && targetEl.id) {
+    return encodeHeaderValue("#" + CSS.escape(targetEl.id));
+  }
+  return "true";
+}
+
+// Sent as `silcrow-version` so the server can avoid instructions this
+// bundle predates. Bump when adding a response instruction.
+const PROTOCOL_VERSION = 1;
+
+function buildFetchOptions(method, body, wantsHTML, signal, target = "true") {
   const opts = {
     method,
     headers: {
-      "silcrow-target": "true",
+      "silcrow-target": target,
+      "silcrow-version": String(PROTOCOL_VERSION),
+      "silcrow-current-url": encodeHeaderValue(location.href),
       "Accept": wantsHTML ? "text/html" : "application/json",
     },
     signal,
@@ -1483,23 +2079,71 @@ function buildFetchOptions(method, body, wantsHTML, signal) {
 }
 
 // ── Response Header Processing ─────────────────────────────
+// Values that are not plain ASCII arrive percent-encoded behind a utf-8''
+// marker (RFC 8187 style).
+// Request-side counterpart: fetch rejects header values outside Latin-1.
+function encodeHeaderValue(value) {
+  return /^[\x20-\x7e]*$/.test(value) ? value : "utf-8''" + encodeURIComponent(value);
+}
+
+function decodeHeaderValue(value) {
+  if (value == null || !value.startsWith("utf-8''")) return value;
+  try {
+    return decodeURIComponent(value.slice(7));
+  } catch (e) {
+    warn("Malformed encoded header value: " + value);
+    return value;
+  }
+}
+
+function readHeader(headers, name) {
+  return decodeHeaderValue(headers.get(name));
+}
+
+// silcrow-retarget is one selector, or a JSON array of them to fan the same
+// content out to several elements.
+function parseRetarget(header) {
+  if (!header) return [];
+  if (header.startsWith("[")) {
+    try {
+      const selectors = JSON.parse(header);
+      if (Array.isArray(selectors)) return selectors.filter(s => typeof s === "string" && s);
+    } catch (e) {
+      // Not JSON: an attribute selector such as [data-slot="main"]
+    }
+  }
+  return [header];
+}
+
 function processResponseHeaders(response, fullUrl) {
   const result = {
     redirected: response.redirected,
     finalUrl: response.url || fullUrl,
     pushUrl: null,
-    retargetSelector: null,
+    retargetSelectors: [],
+    swapMode: null,
+    preserve: null,
     sideEffects: {
-      patch: response.headers.get("silcrow-patch"),
-      invalidate: response.headers.get("silcrow-invalidate"),
-      navigate: response.headers.get("silcrow-navigate"),
-      sse: response.headers.get("silcrow-sse"),
-      ws: response.headers.get("silcrow-ws"),
+      patch: readHeader(response.headers, "silcrow-patch"),
+      invalidate: readHeader(response.headers, "silcrow-invalidate"),
+      navigate: readHeader(response.headers, "silcrow-navigate"),
+      sse: readHeader(response.headers, "silcrow-sse"),
+      ws: readHeader(response.headers, "silcrow-ws"),
+      remove: readHeader(response.headers, "silcrow-remove"),
+      title: readHeader(response.headers, "silcrow-title"),
+      meta: readHeader(response.headers, "silcrow-meta"),
+      hook: readHeader(response.headers, "silcrow-hook"),
+      poll: readHeader(response.headers, "silcrow-poll"),
+      pollUrl: fullUrl,
+      confirm: readHeader(response.headers, "silcrow-confirm"),
+      patchBody: response.headers.has("silcrow-patch-body"),
     },
   };
 
+  debugInstructions(response.headers.get("silcrow-debug-record"), response.headers);
+
   // Fire trigger events
-  const triggerHeader = response.headers.get("silcrow-trigger");
+  const triggerHeader = readHeader(response.headers, "silcrow-trigger");
   if (triggerHeader) {
     try {
       const triggers = JSON.parse(triggerHeader);
@@ -1511,11 +2155,15 @@ function processResponseHeaders(response, fullUrl) {
     }
   }
 
+  processToastHeader(readHeader(response.headers, "silcrow-toast"));
+
   // Retarget
-  result.retargetSelector = response.headers.get("silcrow-retarget");
+  result.retargetSelectors = parseRetarget(readHeader(response.headers, "silcrow-retarget"));
+  result.swapMode = readHeader(response.headers, "silcrow-swap");
+  result.preserve = readHeader(response.headers, "silcrow-preserve");
 
   // Push URL override
-  result.pushUrl = response.headers.get("silcrow-push");
+  result.pushUrl = readHeader(response.headers, "silcrow-push");
   if (result.pushUrl) {
     result.finalUrl = new URL(result.pushUrl, location.origin).href;
     result.redirected = true;
@@ -1524,21 +2172,100 @@ function processResponseHeaders(response, fullUrl) {
   return result;
 }
 
+// ── Out-of-Band Fragments ──────────────────────────────────
+// <template s-oob="selector"> elements in an HTML response update other
+// parts of the page after the main swap. With withPatches (the response
+// carried silcrow-patch-body), <template s-patch> holds the JSON patch list
+// that was too large for the silcrow-patch header.
+function extractBodySidecars(html, withPatches) {
+  let patches = null;
+  if (!html.includes("s-oob") && !(withPatches && html.includes("s-patch"))) {
+    return {html, fragments: [], patches};
+  }
+
+  const trimmed = html.trimStart();
+  const isDocument = trimmed.startsWith("<!") || trimmed.startsWith("<html");
+  let root;
+  let doc = null;
+  if (isDocument) {
+    doc = new DOMParser().parseFromString(html, "text/html");
+    root = doc;
+  } else {
+    const tpl = document.createElement("template");
+    tpl.innerHTML = html;
+    root = tpl.content;
+  }
+
+  const fragments = [];
+  for (const tpl of Array.from(root.querySelectorAll("template[s-oob]"))) {
+    fragments.push({selector: tpl.getAttribute("s-oob"), markup: tpl.innerHTML});
+    tpl.remove();
+  }
+  const patchTpl = withPatches ? root.querySelector("template[s-patch]") : null;
+  if (patchTpl) {
+    try {
+      patches = JSON.parse(patchTpl.content.textContent);
+    } catch (e) {
+      warn("Failed to parse body patches: " + e.message);
+    }
+    patchTpl.remove();
+  }
+  if (fragments.length === 0 && !patchTpl) return {html, fragments, patches};
+
+  if (doc) {
+    return {html: "<!DOCTYPE html>" + doc.documentElement.outerHTML, fragments, patches};
+  }
+  const container = document.createElement("div");
+  container.appendChild(root);
+  return {html: container.innerHTML, fragments, patches};
+}
+
+function applyOobFragments(fragments) {
+  for (const fragment of fragments) {
+    const el = fragment.selector ? document.querySelector(fragment.selector) : null;
+    if (el) {
+      safeSetHTML(el, fragment.markup);
+    } else {
+      warn("No element matches out-of-band target: " + fragment.selector);
+    }
+  }
+}
+
 // ── Swap Content Preparation ───────────────────────────────
-function prepareSwapContent(text, contentType, targetSelector) {
+function prepareSwapContent(text, contentType, targetSelector, patchBody = false) {
+  // application/json-patch+json also contains "application/json".
+  const isJsonPatch = contentType.includes("application/json-patch+json");
   const isJSON = contentType.includes("application/json");
   let swapContent;
+  let oob = [];
+  let patches = null;
 
-  if (isJSON) {
+  if (isJsonPatch) {
+    swapContent = JSON.parse(text);
+  } else if (isJSON) {
     swapContent = JSON.parse(text);
+    if (swapContent && typeof swapContent === "object" && "_patches" in swapContent) {
+      if (patchBody) patches = swapContent._patches;
+      delete swapContent._patches;
+      if (
+        !swapContent._toasts &&
+        swapContent.data !== undefined &&
+        Object.keys(swapContent).length === 1
+      ) {
+        swapContent = swapContent.data;
+      }
+    }
     processToasts(true, swapContent);
   } else {
+    const extracted = extractBodySidecars(text, patchBody);
+    oob = extracted.fragments;
+    patches = extracted.patches;
     const isFullPage = !targetSelector;
-    swapContent = extractHTML(text, targetSelector, isFullPage);
+    swapContent = extractHTML(extracted.html, targetSelector, isFullPage);
     processToasts(false);
   }
 
-  return {swapContent, isJSON};
+  return {swapContent, isJSON, isJsonPatch, oob, patches};
 }
 
 // ── Post-Swap Finalization ─────────────────────────────────
@@ -1581,6 +2308,7 @@ async function navigate(url, options = {}) {
     trigger = "click",
     skipHistory = false,
     sourceEl = null,
+    fresh = false,
   } = options;
 
   const fullUrl = new URL(url, location.origin).href;
@@ -1610,10 +2338,13 @@ async function navigate(url, options = {}) {
   showLoading(targetEl);
 
   try {
-    let cached = method === "GET" ? cacheGet(fullUrl) : null;
+    let cached = method === "GET" && !fresh ? cacheGet(fullUrl) : null;
 
     let text, contentType, redirected = false, finalUrl = fullUrl, pushUrl = null;
     let sideEffects = null;
+    let swapMode = null;
+    let preserve = null;
+    let extraTargets = [];
 
     const wantsHTML = sourceEl?.hasAttribute("s-html");
     if (cached) {
@@ -1622,10 +2353,20 @@ async function navigate(url, options = {}) {
       text = cached.text;
       contentType = cached.contentType;
     } else {
-      const fetchOpts = buildFetchOptions(method, body, wantsHTML, controller.signal);
+      const fetchOpts = buildFetchOptions(
+        method, body, wantsHTML, controller.signal, targetHeaderValue(targetEl, targetSelector)
+      );
+      // Link clicks and form submits are boosted navigations; popstate
+      // re-fetches restore a history entry.
+      if (trigger === "click" || trigger === "submit") {
+        fetchOpts.headers["silcrow-boosted"] = "true";
+      } else if (trigger === "popstate") {
+        fetchOpts.headers["silcrow-history-restore"] = "true";
+      }
       const response = await fetch(fullUrl, fetchOpts);
 
-      if (!response.ok) {
+      // A 422 carries the validation errors to show, so it swaps like a success.
+      if (!response.ok && response.status !== 422) {
         throw new Error(`HTTP ${response.status}: ${response.statusText}`);
       }
 
@@ -1634,18 +2375,32 @@ async function navigate(url, options = {}) {
       finalUrl = headerResult.finalUrl;
       pushUrl = headerResult.pushUrl;
       sideEffects = headerResult.sideEffects;
+      swapMode = headerResult.swapMode;
+      preserve = headerResult.preserve;
 
       // Apply retarget
-      if (headerResult.retargetSelector) {
-        const newTarget = document.querySelector(headerResult.retargetSelector);
-        if (newTarget) targetEl = newTarget;
+      const retargets = headerResult.retargetSelectors
+        .map(selector => document.querySelector(selector))
+        .filter(Boolean);
+      if (retargets.length) {
+        targetEl = retargets[0];
+        extraTargets = retargets.slice(1);
       }
 
       text = await response.text();
       contentType = response.headers.get("Content-Type") || "";
 
+      // 204 No Content / bodiless 202 Accepted: nothing to swap, but the
+      // side-effect headers and toasts still apply.
+      if (response.status === 204 || (response.status === 202 && !text)) {
+        if (method !== "GET") bustCacheOnMutation();
+        processToasts(false);
+        processSideEffectHeaders(sideEffects, targetEl);
+        return;
+      }
+
       const cacheControl = response.headers.get("silcrow-cache");
-      if (method === "GET" && !redirected && cacheControl !== "no-cache") {
+      if (method === "GET" && response.ok && !redirected && cacheControl !== "no-cache") {
         cacheSet(fullUrl, {text, contentType, ts: Date.now()});
       }
 
@@ -1677,16 +2432,25 @@ async function navigate(url, options = {}) {
     }
 
     // Prepare and execute swap
-    const {swapContent, isJSON} = prepareSwapContent(text, contentType, targetSelector);
+    const {swapContent, isJSON, isJsonPatch, oob, patches} = prepareSwapContent(
+      text, contentType, targetSelector, Boolean(sideEffects?.patchBody)
+    );
+    if (patches) sideEffects.patch = patches;
 
     let swapExecuted = false;
     const proceed = () => {
       if (swapExecuted) return;
       swapExecuted = true;
-      if (isJSON) {
+      if (isJsonPatch) {
+        applyJsonPatch(swapContent, targetEl);
+        extraTargets.forEach(el => applyJsonPatch(swapContent, el));
+      } else if (isJSON) {
         patch(swapContent, targetEl);
+        extraTargets.forEach(el => patch(swapContent, el));
       } else {
-        safeSetHTML(targetEl, swapContent);
+        swapPreserving(targetEl, swapContent, swapMode, preserve);
+        extraTargets.forEach(el => swapPreserving(el, swapContent, swapMode, preserve));
+        applyOobFragments(oob);
       }
     };
 
@@ -1837,7 +2601,12 @@ function onMouseEnter(e) {
   const controller = new AbortController();
   const wantsHTML = el.hasAttribute("s-html");
   const promise = fetch(fullUrl, {
-    headers: {"silcrow-target": "true", "Accept": wantsHTML ? "text/html" : "application/json"},
+    headers: {
+      "silcrow-target": "true",
+      "silcrow-version": String(PROTOCOL_VERSION),
+      "silcrow-current-url": encodeHeaderValue(location.href),
+      "Accept": wantsHTML ? "text/html" : "application/json",
+    },
     signal: controller.signal,
   })
     .then((r) => {
@@ -1983,6 +2752,7 @@ function destroy() {
 
   responseCache.clear();
   preloadInflight.clear();
+  stopAllPolls();
   destroyAllLive();
 }
 
@@ -1991,6 +2761,7 @@ window.Silcrow = {
   patch,         // Handles middleware, toasts, and s-for blocks
   invalidate,    // Clears cached maps for a root
   stream,        // Batched updates for high-frequency data
+  jsonPatch: applyJsonPatch, // RFC 6902 ops against the last patched data
 
   // --- Navigation (Unified ":" Placeholders) ---
   go(path, options = {}) {
@@ -2014,6 +2785,10 @@ window.Silcrow = {
   revert: revertOptimistic,
   onToast: (handler) => {setToastHandler(handler); return window.Silcrow;},
 
+  // --- Custom live events ---
+  on: (name, handler) => {onCustom(name, handler); return window.Silcrow;},
+  off: (name, handler) => {offCustom(name, handler); return window.Silcrow;},
+
   // --- Extensibility ---
   use(fn) {
     if (middlewareLocked) {
@@ -2026,6 +2801,8 @@ window.Silcrow = {
 
   onRoute: (h) => {routeHandler = h; return window.Silcrow;},
   onError: (h) => {errorHandler = h; return window.Silcrow;},
+  hook: (name, fn) => {registerHook(name, fn); return window.Silcrow;},
+  onConfirm: (h) => {confirmHandler = h; return window.Silcrow;},
 
   destroy,
 };
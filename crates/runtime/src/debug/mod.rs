@@ -0,0 +1,4 @@
+// src/debug/mod.rs
+mod recorder;
+
+pub use recorder::{INSTRUCTIONS_PATH, InstructionRecorder, RecordedInstruction, RecordedResponse};
@@ -0,0 +1,176 @@
+// ./src/debug/recorder.rs
+//
+// Dev-only layer that captures every silcrow instruction a response carried
+// (patches, triggers, retargets, toasts, …) into a bounded ring buffer.
+
+use crate::response::headers::decode_value;
+use axum::Router;
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, HeaderValue, header};
+use axum::middleware::{Next, from_fn_with_state};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Where the recorded instructions are served as JSON.
+pub const INSTRUCTIONS_PATH: &str = "/_silcrow/debug/instructions";
+
+/// Response header carrying the record id, echoed by the client debug module.
+const RECORD_HEADER: &str = "silcrow-debug-record";
+
+const TOAST_COOKIE_PREFIX: &str = "silcrow_toasts=";
+
+/// One instruction as it crossed the wire.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecordedInstruction {
+    /// Instruction name without the `silcrow-` prefix (`patch`, `trigger`, `toast`, …).
+    pub kind: String,
+    pub value: String,
+}
+
+/// All instructions carried by a single response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecordedResponse {
+    pub id: u64,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub instructions: Vec<RecordedInstruction>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    capacity: usize,
+    next_id: AtomicU64,
+    records: Mutex<VecDeque<RecordedResponse>>,
+}
+
+/// Ring buffer of recorded responses, shared between the layer and the endpoint.
+#[derive(Debug, Clone)]
+pub struct InstructionRecorder {
+    inner: Arc<Inner>,
+}
+
+impl InstructionRecorder {
+    /// Keeps the most recent `capacity` responses that carried instructions.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                capacity: capacity.max(1),
+                next_id: AtomicU64::new(1),
+                records: Mutex::new(VecDeque::new()),
+            }),
+        }
+    }
+
+    /// Recorded responses, oldest first.
+    pub fn snapshot(&self) -> Vec<RecordedResponse> {
+        self.inner
+            .records
+            .lock()
+            .map(|records| records.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Drops every recorded response.
+    pub fn clear(&self) {
+        if let Ok(mut records) = self.inner.records.lock() {
+            records.clear();
+        }
+    }
+
+    /// Installs the recording layer and mounts [`INSTRUCTIONS_PATH`].
+    ///
+    /// A no-op in release builds so the recorder can never ship by accident.
+    pub fn attach<S>(self, router: Router<S>) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        if !cfg!(debug_assertions) {
+            return router;
+        }
+        let endpoint = self.clone();
+        router
+            .route(
+                INSTRUCTIONS_PATH,
+                get(move || async move { axum::Json(endpoint.snapshot()) }),
+            )
+            .layer(from_fn_with_state(self, record_instructions))
+    }
+
+    fn push(
+        &self,
+        method: String,
+        path: String,
+        status: u16,
+        instructions: Vec<RecordedInstruction>,
+    ) -> u64 {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut records) = self.inner.records.lock() {
+            if records.len() >= self.inner.capacity {
+                records.pop_front();
+            }
+            records.push_back(RecordedResponse {
+                id,
+                method,
+                path,
+                status,
+                instructions,
+            });
+        }
+        id
+    }
+}
+
+impl Default for InstructionRecorder {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+/// Pure extraction of silcrow instructions from response headers.
+fn collect_instructions(headers: &HeaderMap) -> Vec<RecordedInstruction> {
+    let silcrow = headers.iter().filter_map(|(name, value)| {
+        let kind = name.as_str().strip_prefix("silcrow-")?;
+        (name.as_str() != RECORD_HEADER).then(|| RecordedInstruction {
+            kind: kind.to_owned(),
+            value: decode_value(value.to_str().unwrap_or_default()).into_owned(),
+        })
+    });
+    let toasts = headers
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok()?.strip_prefix(TOAST_COOKIE_PREFIX))
+        .filter_map(|rest| rest.split(';').next())
+        .filter(|encoded| !encoded.is_empty())
+        .map(|encoded| RecordedInstruction {
+            kind: "toast".to_owned(),
+            value: urlencoding::decode(encoded)
+                .map(|decoded| decoded.into_owned())
+                .unwrap_or_else(|_| encoded.to_owned()),
+        });
+    silcrow.chain(toasts).collect()
+}
+
+async fn record_instructions(
+    State(recorder): State<InstructionRecorder>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_owned();
+    let mut response = next.run(request).await.into_response();
+
+    let instructions = collect_instructions(response.headers());
+    if instructions.is_empty() {
+        return response;
+    }
+    let id = recorder.push(method, path, response.status().as_u16(), instructions);
+    if let Ok(value) = HeaderValue::from_str(&id.to_string()) {
+        response.headers_mut().insert(RECORD_HEADER, value);
+    }
+    response
+}
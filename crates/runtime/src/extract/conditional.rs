@@ -0,0 +1,85 @@
+// ./src/extract/conditional.rs
+//
+// Conditional GETs: the entity tags a client already holds, so a handler can
+// answer `304 Not Modified` instead of re-rendering an unchanged fragment.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{HeaderMap, header::IF_NONE_MATCH, request::Parts},
+};
+use std::convert::Infallible;
+
+/// The `If-None-Match` entity tags, unquoted and with any `W/` prefix
+/// dropped (the header is compared weakly). `*` is kept as is. Empty when the
+/// header is absent.
+///
+/// ```ignore
+/// async fn cart(inm: IfNoneMatch) -> Response {
+///     let etag = cart_version();
+///     if inm.matches(&etag) {
+///         return not_modified(&etag).into_response();
+///     }
+///     html(render_cart()).with_etag(&etag).into_response()
+/// }
+/// ```
+///
+/// [`SilcrowRequest::fresh`](crate::SilcrowRequest::fresh) does the same
+/// check for handlers that already take a `SilcrowRequest`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IfNoneMatch(pub Vec<String>);
+
+impl IfNoneMatch {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let tags = headers
+            .get_all(IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| opaque_tag(tag.trim()).to_owned())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        Self(tags)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether the client's copy of `etag` is current. `etag` may be given
+    /// bare (`v42`) or quoted (`"v42"`, `W/"v42"`).
+    pub fn matches(&self, etag: &str) -> bool {
+        let etag = opaque_tag(etag);
+        self.0.iter().any(|tag| tag == "*" || tag == etag)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IfNoneMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// `W/"v42"` → `v42`.
+pub(crate) fn opaque_tag(tag: &str) -> &str {
+    let tag = tag.strip_prefix("W/").unwrap_or(tag);
+    tag.strip_prefix('"')
+        .and_then(|tag| tag.strip_suffix('"'))
+        .unwrap_or(tag)
+}
+
+/// `etag` as an `ETag` header value: bare tags are quoted, quoted and weak
+/// ones are left alone.
+pub(crate) fn quoted_etag(etag: &str) -> String {
+    if etag.starts_with('"') || etag.starts_with("W/\"") {
+        etag.to_owned()
+    } else {
+        format!("\"{etag}\"")
+    }
+}
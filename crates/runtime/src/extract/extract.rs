@@ -1,6 +1,9 @@
 // ./crates/pilcrow/src/extract.rs
 
-use crate::response::headers::SilcrowTarget;
+use crate::extract::conditional::{IfNoneMatch, opaque_tag};
+use crate::response::headers::{
+    SilcrowBoosted, SilcrowCurrentUrl, SilcrowHistoryRestore, SilcrowTarget, SilcrowVersion,
+};
 use axum::{
     async_trait,
     extract::FromRequestParts,
@@ -11,20 +14,153 @@ use headers::HeaderMapExt;
 // ════════════════════════════════════════════════════════════
 // 1. The Unified Mode Enum
 // ════════════════════════════════════════════════════════════
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RequestMode {
     Html,
     Json,
 }
 
+impl RequestMode {
+    /// Parses a `?format=` value (`html` or `json`, case-insensitive).
+    pub fn from_format(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "html" => Some(Self::Html),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// [`RequestMode`] with HTML split by whether the page layout is wanted, as
+/// returned by [`SilcrowRequest::render_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    Json,
+    /// HTML for silcrow.js to swap into a target: no layout.
+    Fragment,
+    /// HTML for a full page load: wrap it in the layout.
+    Document,
+}
+
+impl RenderMode {
+    /// Either of the HTML modes.
+    pub fn is_html(self) -> bool {
+        self != Self::Json
+    }
+}
+
+/// Name of the query parameter that forces a representation (`?format=json`).
+///
+/// Defaults to `format`. Override per router with
+/// `.layer(Extension(FormatParam("_format")))`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatParam(pub &'static str);
+
+impl Default for FormatParam {
+    fn default() -> Self {
+        Self("format")
+    }
+}
+
+/// Finds `name=html|json` in a raw query string.
+fn format_override(query: Option<&str>, name: &str) -> Option<RequestMode> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .and_then(|(_, value)| RequestMode::from_format(value))
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Client Protocol Versions
+// ════════════════════════════════════════════════════════════
+
+/// The protocol version this build's silcrow.js sends in `silcrow-version`.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Response instructions a silcrow.js bundle may be too old to understand.
+/// During a rolling deploy, pages loaded before the upgrade keep running the
+/// old bundle; check [`SilcrowRequest::supports`] before relying on these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// `silcrow-swap` modes other than the default inner swap.
+    Swap,
+    /// JSON Patch bodies and `silcrow-patch` ops.
+    JsonPatch,
+    /// `silcrow-hook` calls into registered client hooks.
+    Hooks,
+    /// `silcrow-confirm` prompts.
+    Confirm,
+    /// `silcrow-poll` re-fetching.
+    Polling,
+    /// `silcrow-remove`.
+    RemoveTarget,
+    /// `silcrow-title` and `silcrow-meta`.
+    TitleAndMeta,
+    /// `silcrow-preserve` during swaps.
+    Preserve,
+    /// Toasts in the `silcrow-toast` header.
+    ToastHeader,
+}
+
+impl Feature {
+    /// The first protocol version that understands this feature.
+    pub fn since(self) -> u32 {
+        match self {
+            Self::Swap
+            | Self::JsonPatch
+            | Self::Hooks
+            | Self::Confirm
+            | Self::Polling
+            | Self::RemoveTarget
+            | Self::TitleAndMeta
+            | Self::Preserve
+            | Self::ToastHeader => 1,
+        }
+    }
+}
+
 // ════════════════════════════════════════════════════════════
-// 2. The Extractor Struct
+// 3. The Extractor Struct
 // ════════════════════════════════════════════════════════════
-#[derive(Debug, Clone)]
+
+/// What the client asked for, parsed from its headers.
+///
+/// Tests and examples build one with [`browser`](Self::browser),
+/// [`silcrow_html`](Self::silcrow_html) or [`api_json`](Self::api_json), or
+/// start from `default()` — a request that states no preference — and chain
+/// the setters:
+///
+/// ```ignore
+/// let req = SilcrowRequest::silcrow_html("#cart").boosted().if_none_match("v3");
+/// ```
+#[derive(Debug, Clone, Default)]
 pub struct SilcrowRequest {
     pub is_silcrow: bool,
     pub accepts_html: bool,
     pub accepts_json: bool,
+    /// Representation forced via the [`FormatParam`] query parameter, if any.
+    pub format_override: Option<RequestMode>,
+    /// Selector of the element the client will update (`#cart`, or its
+    /// `s-target`), when it has one to name.
+    pub target: Option<String>,
+    /// The page the user is on when silcrow.js sends the request — not the
+    /// URL being fetched. For relative redirects and active-nav state.
+    pub current_url: Option<String>,
+    /// Sent by a link click or form submit silcrow.js took over, as opposed
+    /// to an API call, poll or live-triggered request.
+    pub is_boosted: bool,
+    /// Sent to restore a history entry (back/forward) that wasn't cached.
+    /// Skip one-shot side effects — view counters, "mark as read" — here.
+    pub is_history_restore: bool,
+    /// The `If-None-Match` tags sent with the request; see [`fresh`](Self::fresh).
+    pub if_none_match: IfNoneMatch,
+    /// The `silcrow-version` silcrow.js sent; `None` from bundles that
+    /// predate it and from non-silcrow clients. See [`supports`](Self::supports).
+    pub client_version: Option<u32>,
+    /// The client sent `TE: trailers`, so trailer fields declared on a
+    /// streamed response reach it.
+    pub accepts_trailers: bool,
 }
 
 #[async_trait]
@@ -34,9 +170,53 @@ where
 {
     type Rejection = (StatusCode, &'static str);
 
+    /// Parsed once per request: the first extraction stores the result in
+    /// the request extensions and later ones — other extractors, middleware
+    /// — get that copy back.
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        // Did silcrow.js send this request?
-        let is_silcrow = parts.headers.typed_get::<SilcrowTarget>().is_some();
+        if let Some(cached) = parts.extensions.get::<SilcrowRequest>() {
+            return Ok(cached.clone());
+        }
+        let request = SilcrowRequest::parse(parts);
+        parts.extensions.insert(request.clone());
+        Ok(request)
+    }
+}
+
+impl SilcrowRequest {
+    pub(crate) fn parse(parts: &Parts) -> Self {
+        // Did silcrow.js send this request, and for which element? A bare
+        // "true" means no particular one.
+        let target_header = parts.headers.typed_get::<SilcrowTarget>();
+        let is_silcrow = target_header.is_some();
+        let target = target_header
+            .map(|header| header.0)
+            .filter(|selector| !selector.is_empty() && selector != "true");
+
+        let current_url = parts
+            .headers
+            .typed_get::<SilcrowCurrentUrl>()
+            .map(|header| header.0);
+
+        let is_boosted = parts.headers.typed_get::<SilcrowBoosted>().is_some();
+        let is_history_restore = parts.headers.typed_get::<SilcrowHistoryRestore>().is_some();
+
+        let if_none_match = IfNoneMatch::from_headers(&parts.headers);
+        let client_version = parts
+            .headers
+            .typed_get::<SilcrowVersion>()
+            .and_then(|header| header.0.trim().parse().ok());
+
+        let accepts_trailers = parts
+            .headers
+            .get_all(axum::http::header::TE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|coding| {
+                let coding = coding.split(';').next().unwrap_or("").trim();
+                coding.eq_ignore_ascii_case("trailers")
+            });
 
         // What data format does the client want?
         let accept_header = parts
@@ -69,17 +249,35 @@ where
         let accepts_html = max_html_q > 0.0 && max_html_q >= max_json_q;
         let accepts_json = max_json_q > 0.0;
 
-        Ok(SilcrowRequest {
+        let param = parts
+            .extensions
+            .get::<FormatParam>()
+            .copied()
+            .unwrap_or_default();
+        let format_override = format_override(parts.uri.query(), param.0);
+
+        SilcrowRequest {
             is_silcrow,
             accepts_html,
             accepts_json,
-        })
+            format_override,
+            target,
+            current_url,
+            is_boosted,
+            is_history_restore,
+            if_none_match,
+            client_version,
+            accepts_trailers,
+        }
     }
-}
 
-impl SilcrowRequest {
-    /// Determines the exact format the handler should return based on headers.
+    /// Determines the exact format the handler should return.
+    ///
+    /// An explicit `?format=` override wins; otherwise the headers decide.
     pub fn preferred_mode(&self) -> RequestMode {
+        if let Some(mode) = self.format_override {
+            return mode;
+        }
         match (self.is_silcrow, self.accepts_html, self.accepts_json) {
             (true, true, _) => RequestMode::Html,
             (true, false, true) => RequestMode::Json,
@@ -87,4 +285,137 @@ impl SilcrowRequest {
             _ => RequestMode::Json,
         }
     }
+
+    /// [`preferred_mode`](Self::preferred_mode), with HTML split into
+    /// [`RenderMode::Fragment`] for silcrow.js swaps and
+    /// [`RenderMode::Document`] for full page loads, so layout wrapping
+    /// can be decided in one match:
+    ///
+    /// ```ignore
+    /// match req.render_mode() {
+    ///     RenderMode::Json => json(&orders).into_response(),
+    ///     RenderMode::Fragment => html(list(&orders)).into_response(),
+    ///     RenderMode::Document => html(layout(list(&orders))).into_response(),
+    /// }
+    /// ```
+    pub fn render_mode(&self) -> RenderMode {
+        match self.preferred_mode() {
+            RequestMode::Json => RenderMode::Json,
+            RequestMode::Html if self.is_silcrow => RenderMode::Fragment,
+            RequestMode::Html => RenderMode::Document,
+        }
+    }
+
+    /// silcrow.js is swapping a named element (`#cart`), not the whole page.
+    pub fn is_partial(&self) -> bool {
+        self.is_silcrow && self.target.is_some()
+    }
+
+    /// Render HTML without the layout: [`RenderMode::Fragment`].
+    pub fn wants_fragment(&self) -> bool {
+        self.render_mode() == RenderMode::Fragment
+    }
+
+    /// Render a full HTML page, layout included: [`RenderMode::Document`].
+    pub fn wants_document(&self) -> bool {
+        self.render_mode() == RenderMode::Document
+    }
+
+    /// Whether the client's silcrow.js understands `feature`, so the handler
+    /// can fall back for pages still running an older bundle:
+    ///
+    /// ```ignore
+    /// let response = html(row(&item)).retarget("#items");
+    /// if req.supports(Feature::Swap) {
+    ///     response.swap(SwapMode::BeforeEnd)
+    /// } else {
+    ///     html(list(&items)).retarget("#items")
+    /// }
+    /// ```
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.client_version
+            .is_some_and(|version| version >= feature.since())
+    }
+
+    /// A full page load from a browser: HTML preferred, no silcrow.js.
+    pub fn browser() -> Self {
+        Self {
+            accepts_html: true,
+            accepts_json: true,
+            ..Self::default()
+        }
+    }
+
+    /// A silcrow.js request for HTML to swap into `target` (`"true"` for no
+    /// particular element), from a current bundle.
+    pub fn silcrow_html(target: &str) -> Self {
+        Self::default().silcrow(target).accept(RequestMode::Html)
+    }
+
+    /// An API client asking for JSON.
+    pub fn api_json() -> Self {
+        Self::default().accept(RequestMode::Json)
+    }
+
+    /// Marks the request as sent by a current silcrow.js for `target`
+    /// (`"true"` for no particular element).
+    pub fn silcrow(mut self, target: &str) -> Self {
+        self.is_silcrow = true;
+        self.target = Some(target.to_owned()).filter(|t| !t.is_empty() && t != "true");
+        self.client_version = Some(PROTOCOL_VERSION);
+        self
+    }
+
+    /// Sets the Accept state to exactly `mode`: JSON, or any HTML mode.
+    pub fn accept(mut self, mode: RequestMode) -> Self {
+        self.accepts_json = mode == RequestMode::Json;
+        self.accepts_html = !self.accepts_json;
+        self
+    }
+
+    /// As if `?format=` had forced `mode`.
+    pub fn format(mut self, mode: RequestMode) -> Self {
+        self.format_override = Some(mode);
+        self
+    }
+
+    pub fn current_url(mut self, url: impl Into<String>) -> Self {
+        self.current_url = Some(url.into());
+        self
+    }
+
+    pub fn boosted(mut self) -> Self {
+        self.is_boosted = true;
+        self
+    }
+
+    pub fn history_restore(mut self) -> Self {
+        self.is_history_restore = true;
+        self
+    }
+
+    /// As if the client had sent `TE: trailers`.
+    pub fn trailers(mut self) -> Self {
+        self.accepts_trailers = true;
+        self
+    }
+
+    /// Adds `etag` to the `If-None-Match` tags.
+    pub fn if_none_match(mut self, etag: &str) -> Self {
+        self.if_none_match.0.push(opaque_tag(etag).to_owned());
+        self
+    }
+
+    /// Overrides the `silcrow-version`; `None` for a bundle that predates it.
+    pub fn client_version(mut self, version: Option<u32>) -> Self {
+        self.client_version = version;
+        self
+    }
+
+    /// Whether the client already holds `etag` (bare or quoted), so the
+    /// handler can answer [`not_modified`](crate::not_modified) instead of
+    /// rendering.
+    pub fn fresh(&self, etag: &str) -> bool {
+        self.if_none_match.matches(etag)
+    }
 }
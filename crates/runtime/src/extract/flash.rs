@@ -0,0 +1,193 @@
+// ./src/extract/flash.rs
+//
+// Flash data: a value set on a redirect and read by the request that follows
+// it, e.g. the form values a failed submission should re-render. It travels
+// in a short-lived cookie, like toasts, so nothing is stored server-side.
+// FlashLayer clears the cookie after any request that read it.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{HeaderMap, HeaderValue, Request, Response, header::SET_COOKIE, request::Parts},
+    response::{IntoResponseParts, ResponseParts},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use cookie::time::Duration;
+use futures_util::future::BoxFuture;
+use serde::{Serialize, de::DeserializeOwned};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+pub(crate) const FLASH_COOKIE: &str = "silcrow_flash";
+
+/// How long a flash survives if the next request never reads it.
+const FLASH_MAX_AGE: Duration = Duration::seconds(5);
+
+/// Builds the cookie carrying `data`, or `None` if it does not serialize.
+///
+/// Browsers silently drop cookies over about 4KB, and with them the flash, so
+/// keep `data` small: the form values and errors, not a whole page.
+pub(crate) fn flash_cookie(data: &impl Serialize) -> Option<Cookie<'static>> {
+    let json = match serde_json::to_string(data) {
+        Ok(json) => json,
+        Err(e) => {
+            tracing::warn!(error = %e, "flash data failed to serialize; not sent");
+            return None;
+        }
+    };
+    Some(
+        Cookie::build((FLASH_COOKIE, urlencoding::encode(&json).into_owned()))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .max_age(FLASH_MAX_AGE)
+            .build(),
+    )
+}
+
+/// The flash set by the previous response, if there was one and it
+/// deserializes as `T`.
+///
+/// ```ignore
+/// async fn create(form: Form<NewUser>) -> NavigateResponse {
+///     navigate("/users/new").with_flash(&form.0)
+/// }
+///
+/// async fn new_user(Flash(previous): Flash<NewUser>) -> (Flash<NewUser>, HtmlResponse) {
+///     (Flash(None), html(render_form(previous.as_ref())))
+/// }
+/// ```
+///
+/// The cookie expires a few seconds after it is set. Under a [`FlashLayer`]
+/// it is cleared as soon as a handler has read it, so a reload does not show
+/// it again; without the layer, return a `Flash` from the handler to clear it.
+///
+/// Browsers drop cookies over about 4KB without an error, so an oversized
+/// flash simply never arrives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flash<T>(pub Option<T>);
+
+impl<T> Flash<T> {
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for Flash<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let jar = CookieJar::from_headers(&parts.headers);
+        let Some(cookie) = jar.get(FLASH_COOKIE) else {
+            return Ok(Flash(None));
+        };
+        if let Some(FlashRead(read)) = parts.extensions.get() {
+            read.store(true, Ordering::Relaxed);
+        }
+        let data = urlencoding::decode(cookie.value())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok());
+        if data.is_none() {
+            tracing::debug!("flash cookie did not decode as the requested type");
+        }
+        Ok(Flash(data))
+    }
+}
+
+/// Clears the flash cookie.
+impl<T> IntoResponseParts for Flash<T> {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        append_removal(res.headers_mut());
+        Ok(res)
+    }
+}
+
+fn append_removal(headers: &mut HeaderMap) {
+    let mut removal = Cookie::build((FLASH_COOKIE, "")).path("/").build();
+    removal.make_removal();
+    if let Ok(value) = HeaderValue::from_str(&removal.to_string()) {
+        headers.append(SET_COOKIE, value);
+    }
+}
+
+/// Whether the response already sets (or clears) the flash cookie itself.
+fn sets_flash(headers: &HeaderMap) -> bool {
+    let prefix = format!("{FLASH_COOKIE}=");
+    headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .any(|value| value.as_bytes().starts_with(prefix.as_bytes()))
+}
+
+/// Set by the [`Flash`] extractor when the request carried a flash cookie.
+#[derive(Clone)]
+struct FlashRead(Arc<AtomicBool>);
+
+/// Clears the flash cookie on every response whose handler extracted a
+/// [`Flash`], unless the response sets a new flash itself.
+///
+/// ```ignore
+/// let app = Router::new()
+///     .route("/users/new", get(new_user).post(create))
+///     .layer(FlashLayer::new());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlashLayer;
+
+impl FlashLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for FlashLayer {
+    type Service = FlashService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FlashService { inner }
+    }
+}
+
+/// The service [`FlashLayer`] wraps each route in.
+#[derive(Debug, Clone)]
+pub struct FlashService<S> {
+    inner: S,
+}
+
+impl<S, B, ResBody> Service<Request<B>> for FlashService<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let read = Arc::new(AtomicBool::new(false));
+        request.extensions_mut().insert(FlashRead(read.clone()));
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let mut response = response.await?;
+            if read.load(Ordering::Relaxed) && !sets_flash(response.headers()) {
+                append_removal(response.headers_mut());
+            }
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,148 @@
+// ./src/extract/form.rs
+//
+// Form submissions that keep what the user typed when they fail to parse, so
+// the handler can re-render the form with inline errors instead of the
+// request being rejected before it runs.
+
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{Method, StatusCode, header::CONTENT_TYPE},
+};
+use serde::de::DeserializeOwned;
+
+/// One problem with a submitted field. `field` is the path serde reported
+/// (`email`, `address.zip`), or empty when the error is about the whole form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// A submission that did not deserialize: the raw fields, in the order sent,
+/// and what was wrong with them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InvalidForm {
+    pub values: Vec<(String, String)>,
+    pub errors: Vec<FieldError>,
+}
+
+impl InvalidForm {
+    /// What was submitted for `field`, to put back in its input.
+    pub fn value(&self, field: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The error for `field`, to show next to its input.
+    pub fn error(&self, field: &str) -> Option<&str> {
+        self.errors
+            .iter()
+            .find(|error| error.field == field)
+            .map(|error| error.message.as_str())
+    }
+}
+
+/// A URL-encoded form (the query string for GET) as `T`, or — when it does
+/// not deserialize — the submitted values and errors, for re-rendering:
+///
+/// ```ignore
+/// async fn create(form: SilcrowForm<NewUser>) -> HtmlResponse {
+///     match form {
+///         SilcrowForm::Valid(user) => save(user),
+///         SilcrowForm::Invalid(invalid) => fragment(signup_form(&invalid), "#signup"),
+///     }
+/// }
+/// ```
+///
+/// Only a body that is not a form, or that cannot be read, is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SilcrowForm<T> {
+    Valid(T),
+    Invalid(InvalidForm),
+}
+
+impl<T> SilcrowForm<T> {
+    pub fn into_result(self) -> Result<T, InvalidForm> {
+        match self {
+            Self::Valid(data) => Ok(data),
+            Self::Invalid(invalid) => Err(invalid),
+        }
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for SilcrowForm<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if req.method() == Method::GET || req.method() == Method::HEAD {
+            let query = req.uri().query().unwrap_or_default().to_owned();
+            return Ok(parse(query.as_bytes()));
+        }
+
+        let is_form = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"));
+        if !is_form {
+            return Err((
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Expected an application/x-www-form-urlencoded body",
+            ));
+        }
+
+        let body = Bytes::from_request(req, state).await.map_err(|rejection| {
+            match rejection.status() {
+                StatusCode::PAYLOAD_TOO_LARGE => (rejection.status(), "Form body too large"),
+                status => (status, "Failed to read form body"),
+            }
+        })?;
+        Ok(parse(&body))
+    }
+}
+
+pub(crate) fn parse<T: DeserializeOwned>(body: &[u8]) -> SilcrowForm<T> {
+    let deserializer = serde_urlencoded::Deserializer::new(form_urlencoded::parse(body));
+    match serde_path_to_error::deserialize(deserializer) {
+        Ok(data) => SilcrowForm::Valid(data),
+        Err(error) => SilcrowForm::Invalid(InvalidForm {
+            values: form_urlencoded::parse(body).into_owned().collect(),
+            errors: vec![field_error(&error)],
+        }),
+    }
+}
+
+/// serde reports a missing field against its parent, naming the field only
+/// in the message; attribute it to the field itself.
+fn field_error(error: &serde_path_to_error::Error<serde_urlencoded::de::Error>) -> FieldError {
+    let message = error.inner().to_string();
+    let path = error.path().to_string();
+    let parent = if path == "." { String::new() } else { path };
+    match missing_field(&message) {
+        Some(missing) if parent.is_empty() => FieldError {
+            field: missing.to_owned(),
+            message: "is required".to_owned(),
+        },
+        Some(missing) => FieldError {
+            field: format!("{parent}.{missing}"),
+            message: "is required".to_owned(),
+        },
+        None => FieldError {
+            field: parent,
+            message,
+        },
+    }
+}
+
+fn missing_field(message: &str) -> Option<&str> {
+    message.strip_prefix("missing field `")?.strip_suffix('`')
+}
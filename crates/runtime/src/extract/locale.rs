@@ -0,0 +1,156 @@
+// ./src/extract/locale.rs
+//
+// The languages a request prefers: the `silcrow_locale` cookie the app set
+// when the user picked one, then the browser's Accept-Language list.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{HeaderMap, header::ACCEPT_LANGUAGE, request::Parts},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use cookie::time::Duration;
+use std::convert::Infallible;
+
+pub(crate) const LOCALE_COOKIE: &str = "silcrow_locale";
+
+/// How long a chosen locale is remembered.
+const LOCALE_MAX_AGE: Duration = Duration::days(365);
+
+/// Builds the override cookie for `tag`, or `None` if it is not a plausible
+/// language tag.
+pub(crate) fn locale_cookie(tag: &str) -> Option<Cookie<'static>> {
+    let Some(tag) = normalize(tag) else {
+        tracing::warn!(tag, "locale is not a language tag; cookie not set");
+        return None;
+    };
+    Some(
+        Cookie::build((LOCALE_COOKIE, tag))
+            .path("/")
+            .same_site(SameSite::Lax)
+            .max_age(LOCALE_MAX_AGE)
+            .build(),
+    )
+}
+
+/// Preferred language tags, most preferred first: the locale chosen with
+/// [`ResponseExt::with_locale`](crate::response::ResponseExt::with_locale),
+/// if any, then `Accept-Language` by quality. Empty when the client states
+/// no preference.
+///
+/// ```ignore
+/// async fn page(locale: Locale) -> HtmlResponse {
+///     let lang = locale.negotiate(&["en", "de", "fr-CA"]).unwrap_or("en");
+///     html(render(lang))
+/// }
+///
+/// async fn set_language(Form(pick): Form<Pick>) -> NavigateResponse {
+///     navigate("/").with_locale(&pick.lang)
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Locale {
+    pub languages: Vec<String>,
+    /// Whether the first entry came from the `silcrow_locale` cookie.
+    pub from_cookie: bool,
+}
+
+impl Locale {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let chosen = CookieJar::from_headers(headers)
+            .get(LOCALE_COOKIE)
+            .and_then(|cookie| normalize(cookie.value()));
+
+        let mut ranked: Vec<(String, f32)> = headers
+            .get_all(ACCEPT_LANGUAGE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|entry| {
+                let mut params = entry.split(';');
+                let tag = normalize(params.next()?.trim())?;
+                let q = params
+                    .find_map(|param| param.trim().strip_prefix("q=")?.parse().ok())
+                    .unwrap_or(1.0);
+                (q > 0.0).then_some((tag, q))
+            })
+            .collect();
+        // Stable, so equal qualities keep the order the client sent.
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let from_cookie = chosen.is_some();
+        let mut languages: Vec<String> = chosen.into_iter().collect();
+        for (tag, _) in ranked {
+            if !languages.iter().any(|seen| seen.eq_ignore_ascii_case(&tag)) {
+                languages.push(tag);
+            }
+        }
+        Self {
+            languages,
+            from_cookie,
+        }
+    }
+
+    /// The most preferred tag, e.g. `en-GB`.
+    pub fn primary(&self) -> Option<&str> {
+        self.languages.first().map(String::as_str)
+    }
+
+    /// The language subtag of [`primary`](Self::primary), e.g. `en`.
+    pub fn language(&self) -> Option<&str> {
+        self.primary()?.split('-').next()
+    }
+
+    /// The region subtag of [`primary`](Self::primary), e.g. `GB`; `None`
+    /// for a bare language.
+    pub fn region(&self) -> Option<&str> {
+        self.primary()?
+            .split('-')
+            .skip(1)
+            .find(|subtag| subtag.len() == 2 || subtag.bytes().all(|b| b.is_ascii_digit()))
+    }
+
+    /// The first of `supported` the client accepts, by preference: an exact
+    /// tag match (`de-AT`), else a language match (`de-AT` → `de`, or `de` →
+    /// `de-DE`). Comparison ignores case.
+    pub fn negotiate<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        self.languages.iter().find_map(|wanted| {
+            let language = wanted.split('-').next().unwrap_or(wanted);
+            supported
+                .iter()
+                .find(|tag| tag.eq_ignore_ascii_case(wanted))
+                .or_else(|| {
+                    supported.iter().find(|tag| {
+                        tag.split('-')
+                            .next()
+                            .is_some_and(|other| other.eq_ignore_ascii_case(language))
+                    })
+                })
+                .copied()
+        })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Locale
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// `en_us` → `en-us`; `None` for `*` and anything that is not letters,
+/// digits and hyphens.
+fn normalize(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace('_', "-");
+    let plausible = !tag.is_empty()
+        && tag.len() <= 35
+        && tag
+            .split('-')
+            .all(|subtag| !subtag.is_empty() && subtag.bytes().all(|b| b.is_ascii_alphanumeric()));
+    plausible.then_some(tag)
+}
@@ -1 +1,10 @@
+pub mod conditional;
+#[allow(clippy::module_inception)]
 pub mod extract;
+pub mod flash;
+pub mod form;
+pub mod locale;
+pub mod payload;
+pub mod toasts;
+#[cfg(feature = "validation")]
+pub mod validated;
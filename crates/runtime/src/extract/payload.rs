@@ -0,0 +1,216 @@
+// ./src/extract/payload.rs
+//
+// One body extractor for dual-mode handlers: browsers post forms, API
+// clients post JSON, and both land in the same `T`.
+
+use crate::extract::extract::{RequestMode, SilcrowRequest};
+use crate::extract::form::{self, FieldError, SilcrowForm};
+use crate::response::error::PilcrowError;
+use crate::response::response::{ResponseExt, ToastLevel};
+use axum::{
+    async_trait,
+    body::{Body, Bytes},
+    extract::{FromRequest, FromRequestParts, Multipart, Request},
+    http::{Method, StatusCode, header::CONTENT_TYPE},
+};
+use http_body_util::Limited;
+use serde::de::DeserializeOwned;
+
+/// A request body as `T`, read according to its Content-Type:
+/// `application/json`, `application/x-www-form-urlencoded` or
+/// `multipart/form-data` (text fields only; file parts are skipped). GET and
+/// HEAD requests read the query string instead.
+///
+/// ```ignore
+/// async fn create(req: SilcrowRequest, Payload(order): Payload<NewOrder>) -> Response {
+///     let order = save(order);
+///     match req.preferred_mode() {
+///         RequestMode::Json => json(&order).into_response(),
+///         _ => navigate(format!("/orders/{}", order.id)).into_response(),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Payload<T>(pub T);
+
+impl<T> Payload<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for Payload<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match deserialize_body(req, state).await {
+            Ok(data) => Ok(Payload(data)),
+            Err(BodyError::Rejected(status, message)) => Err((status, message)),
+            Err(BodyError::Invalid(errors)) => {
+                tracing::debug!(?errors, "request body did not deserialize");
+                Err((
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "Request body did not match the expected fields",
+                ))
+            }
+        }
+    }
+}
+
+/// [`Payload`] capped at `LIMIT` bytes (2 MiB by default), whose rejections
+/// answer in the request's mode: a [`PilcrowError`] fragment plus an error
+/// toast for silcrow.js, a JSON error for API clients — instead of axum's
+/// plain-text 413.
+///
+/// ```ignore
+/// async fn upload(LimitedPayload(note): LimitedPayload<Note, 65_536>) -> HtmlResponse { /* … */ }
+/// ```
+///
+/// The router's `DefaultBodyLimit` still applies, so a `LIMIT` above it
+/// needs that layer raised as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitedPayload<T, const LIMIT: usize = 2_097_152>(pub T);
+
+impl<T, const LIMIT: usize> LimitedPayload<T, LIMIT> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<S, T, const LIMIT: usize> FromRequest<S> for LimitedPayload<T, LIMIT>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = PilcrowError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let (mut parts, body) = req.into_parts();
+        let silcrow = SilcrowRequest::from_request_parts(&mut parts, state)
+            .await
+            .map_err(|(status, message)| PilcrowError::new(status, message))?;
+        let body = Body::new(Limited::new(body, LIMIT));
+
+        let error = match deserialize_body(Request::from_parts(parts, body), state).await {
+            Ok(data) => return Ok(LimitedPayload(data)),
+            Err(BodyError::Rejected(status, message)) => PilcrowError::new(status, message),
+            Err(BodyError::Invalid(errors)) => {
+                let detail = errors
+                    .iter()
+                    .map(|error| format!("{}: {}", error.field, error.message))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                PilcrowError::new(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "Request body did not match the expected fields",
+                )
+                .with_detail(detail)
+            }
+        };
+        let error = error.for_request(&silcrow);
+        if silcrow.is_silcrow && silcrow.preferred_mode() == RequestMode::Html {
+            let message = error.message.clone();
+            return Err(error.with_toast(message, ToastLevel::Error));
+        }
+        Err(error)
+    }
+}
+
+/// Why [`deserialize_body`] produced no value.
+pub(crate) enum BodyError {
+    /// The body could not be read, or is not a supported type.
+    Rejected(StatusCode, &'static str),
+    /// The body was read but did not deserialize; the fields that failed.
+    Invalid(Vec<FieldError>),
+}
+
+pub(crate) async fn deserialize_body<T, S>(req: Request, state: &S) -> Result<T, BodyError>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    if req.method() == Method::GET || req.method() == Method::HEAD {
+        let query = req.uri().query().unwrap_or_default().to_owned();
+        return from_form(query.as_bytes());
+    }
+
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_owned();
+
+    if content_type.starts_with("application/json") {
+        let body = read_bytes(req, state).await?;
+        let deserializer = &mut serde_json::Deserializer::from_slice(&body);
+        serde_path_to_error::deserialize(deserializer).map_err(|error| {
+            let path = error.path().to_string();
+            BodyError::Invalid(vec![FieldError {
+                field: if path == "." { String::new() } else { path },
+                message: error.inner().to_string(),
+            }])
+        })
+    } else if content_type.starts_with("application/x-www-form-urlencoded") {
+        let body = read_bytes(req, state).await?;
+        from_form(&body)
+    } else if content_type.starts_with("multipart/form-data") {
+        let mut multipart = Multipart::from_request(req, state)
+            .await
+            .map_err(|rejection| {
+                BodyError::Rejected(rejection.status(), "Failed to read multipart body")
+            })?;
+        // Text fields are re-encoded as a form so both share one deserializer.
+        let mut pairs = Vec::new();
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|error| BodyError::Rejected(error.status(), "Failed to read multipart body"))?
+        {
+            let Some(name) = field.name().map(str::to_owned) else {
+                continue;
+            };
+            if field.file_name().is_some() {
+                tracing::debug!(field = %name, "skipping file part in multipart payload");
+                continue;
+            }
+            let value = field.text().await.map_err(|error| {
+                BodyError::Rejected(error.status(), "Failed to read multipart body")
+            })?;
+            pairs.push((name, value));
+        }
+        let encoded = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish();
+        from_form(encoded.as_bytes())
+    } else {
+        Err(BodyError::Rejected(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Expected a JSON, form or multipart body",
+        ))
+    }
+}
+
+async fn read_bytes<S: Send + Sync>(req: Request, state: &S) -> Result<Bytes, BodyError> {
+    Bytes::from_request(req, state)
+        .await
+        .map_err(|rejection| match rejection.status() {
+            StatusCode::PAYLOAD_TOO_LARGE => {
+                BodyError::Rejected(rejection.status(), "Request body too large")
+            }
+            status => BodyError::Rejected(status, "Failed to read request body"),
+        })
+}
+
+fn from_form<T: DeserializeOwned>(body: &[u8]) -> Result<T, BodyError> {
+    match form::parse(body) {
+        SilcrowForm::Valid(data) => Ok(data),
+        SilcrowForm::Invalid(invalid) => Err(BodyError::Invalid(invalid.errors)),
+    }
+}
@@ -0,0 +1,103 @@
+// ./src/extract/toasts.rs
+//
+// Toasts still waiting in the `silcrow_toasts` cookie, read on the server.
+// silcrow.js normally picks the cookie up after a swap; a full page render
+// can embed the toasts instead, so they show without JavaScript.
+
+use crate::response::Toast;
+use crate::response::response::escape_html;
+use crate::response::toast_markup;
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{HeaderValue, header::SET_COOKIE, request::Parts},
+    response::{IntoResponseParts, ResponseParts},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar};
+use std::convert::Infallible;
+
+pub(crate) const TOAST_COOKIE: &str = "silcrow_toasts";
+
+/// The toasts set by the previous response (usually before a redirect) that
+/// no client has shown yet.
+///
+/// ```ignore
+/// async fn dashboard(pending: PendingToasts) -> (PendingToasts, HtmlResponse) {
+///     let toasts = pending.to_html();
+///     (pending, html(layout(&toasts, body())))
+/// }
+/// ```
+///
+/// Returning it from the handler clears the cookie, so silcrow.js does not
+/// show the same toasts again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingToasts(pub Vec<Toast>);
+
+impl PendingToasts {
+    pub fn into_inner(self) -> Vec<Toast> {
+        self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The toasts as `<div class="silcrow-toasts">` markup, one
+    /// `<p class="silcrow-toast" data-level="…">` each; empty when there are
+    /// none. Rich toasts keep their markup, sanitized again here since the
+    /// cookie it came back in is under the client's control.
+    pub fn to_html(&self) -> String {
+        if self.0.is_empty() {
+            return String::new();
+        }
+        let mut markup = String::from("<div class=\"silcrow-toasts\" role=\"status\">");
+        for toast in &self.0 {
+            let content = match toast.html() {
+                Some(html) => toast_markup::sanitize(html),
+                None => escape_html(&toast.message),
+            };
+            markup.push_str(&format!(
+                "<p class=\"silcrow-toast\" data-level=\"{}\">{content}</p>",
+                toast.level.as_str()
+            ));
+        }
+        markup.push_str("</div>");
+        markup
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for PendingToasts
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let jar = CookieJar::from_headers(&parts.headers);
+        let Some(cookie) = jar.get(TOAST_COOKIE) else {
+            return Ok(Self::default());
+        };
+        let toasts = urlencoding::decode(cookie.value())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok());
+        if toasts.is_none() {
+            tracing::debug!("toast cookie did not decode; ignoring it");
+        }
+        Ok(Self(toasts.unwrap_or_default()))
+    }
+}
+
+/// Clears the toast cookie.
+impl IntoResponseParts for PendingToasts {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let mut removal = Cookie::build((TOAST_COOKIE, "")).path("/").build();
+        removal.make_removal();
+        if let Ok(value) = HeaderValue::from_str(&removal.to_string()) {
+            res.headers_mut().append(SET_COOKIE, value);
+        }
+        Ok(res)
+    }
+}
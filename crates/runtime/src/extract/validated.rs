@@ -0,0 +1,152 @@
+// ./src/extract/validated.rs
+//
+// A request body deserialized and checked against its `validator` rules
+// before the handler runs. Failures answer 422 in whichever representation
+// the client asked for, so the handler only ever sees valid data.
+
+use crate::extract::extract::{RequestMode, SilcrowRequest};
+use crate::extract::form::FieldError;
+use crate::extract::payload::{BodyError, deserialize_body};
+use crate::response::ResponseExt;
+use crate::response::response::{escape_html, html, json};
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+/// A body read like [`Payload`](crate::extract::payload::Payload) — JSON,
+/// form or multipart — as `T`, after `T::validate` passed.
+///
+/// ```ignore
+/// #[derive(Deserialize, Validate)]
+/// struct Signup {
+///     #[validate(email)]
+///     email: String,
+/// }
+///
+/// async fn create(Validated(signup): Validated<Signup>) -> NavigateResponse { … }
+/// ```
+///
+/// A body that doesn't deserialize or validate is answered with a
+/// [`ValidationRejection`] before the handler is called.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validated<T>(pub T);
+
+impl<T> Validated<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Field errors answered with `422 Unprocessable Entity`: a
+/// `{"errors": {"field": ["message", …]}}` map for JSON requests, a
+/// `<ul class="silcrow-errors">` fragment for HTML ones, which silcrow.js
+/// swaps into the form's target like any other response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationRejection {
+    pub errors: Vec<FieldError>,
+    pub mode: RequestMode,
+}
+
+impl ValidationRejection {
+    /// `errors` flattened to one entry per message, fields named by path
+    /// (`address.zip`, `items[2].qty`) and sorted.
+    pub fn from_validation_errors(errors: &ValidationErrors, mode: RequestMode) -> Self {
+        let mut flat = Vec::new();
+        flatten(errors, "", &mut flat);
+        flat.sort_by(|a, b| a.field.cmp(&b.field));
+        Self { errors: flat, mode }
+    }
+}
+
+impl IntoResponse for ValidationRejection {
+    fn into_response(self) -> Response {
+        match self.mode {
+            RequestMode::Json => {
+                let mut fields = serde_json::Map::new();
+                for error in self.errors {
+                    if let serde_json::Value::Array(messages) = fields
+                        .entry(error.field)
+                        .or_insert_with(|| serde_json::Value::Array(Vec::new()))
+                    {
+                        messages.push(error.message.into());
+                    }
+                }
+                json(serde_json::json!({ "errors": fields }))
+                    .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+                    .into_response()
+            }
+            RequestMode::Html => {
+                let mut markup = String::from("<ul class=\"silcrow-errors\" role=\"alert\">");
+                for error in &self.errors {
+                    markup.push_str(&format!(
+                        "<li data-field=\"{}\">{}</li>",
+                        escape_html(&error.field),
+                        escape_html(&error.message)
+                    ));
+                }
+                markup.push_str("</ul>");
+                html(markup)
+                    .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+                    .into_response()
+            }
+        }
+    }
+}
+
+fn flatten(errors: &ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{prefix}.{field}")
+        };
+        match kind {
+            ValidationErrorsKind::Field(list) => out.extend(list.iter().map(|error| FieldError {
+                field: path.clone(),
+                message: error.message.as_deref().unwrap_or(&error.code).to_owned(),
+            })),
+            ValidationErrorsKind::Struct(inner) => flatten(inner, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, inner) in items {
+                    flatten(inner, &format!("{path}[{index}]"), out);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for Validated<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let (mut parts, body) = req.into_parts();
+        let mode = SilcrowRequest::from_request_parts(&mut parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?
+            .preferred_mode();
+        let data: T = match deserialize_body(Request::from_parts(parts, body), state).await {
+            Ok(data) => data,
+            Err(BodyError::Rejected(status, message)) => {
+                return Err((status, message).into_response());
+            }
+            Err(BodyError::Invalid(errors)) => {
+                return Err(ValidationRejection { errors, mode }.into_response());
+            }
+        };
+
+        data.validate().map_err(|errors| {
+            ValidationRejection::from_validation_errors(&errors, mode).into_response()
+        })?;
+        Ok(Validated(data))
+    }
+}
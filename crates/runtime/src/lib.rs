@@ -1,28 +1,62 @@
 // ./src/lib.rs
 
 pub mod assets;
+#[cfg(feature = "recorder")]
+pub mod debug;
 pub mod extract;
 pub mod generated_routes;
+pub mod live;
 pub mod response;
 pub mod sse;
+pub mod test;
 pub mod ws;
 
 // ── Core API re-exports ──────────────────────────────────────
 pub use axum::http::StatusCode;
 pub use axum::response::Response;
-pub use extract::extract::{RequestMode, SilcrowRequest};
+pub use axum_extra::extract::cookie::Cookie;
+pub use extract::conditional::IfNoneMatch;
+pub use extract::extract::{
+    Feature, FormatParam, PROTOCOL_VERSION, RenderMode, RequestMode, SilcrowRequest,
+};
+pub use extract::flash::{Flash, FlashLayer};
+pub use extract::form::{FieldError, InvalidForm, SilcrowForm};
+pub use extract::locale::Locale;
+pub use extract::payload::{LimitedPayload, Payload};
+pub use extract::toasts::PendingToasts;
+#[cfg(feature = "validation")]
+pub use extract::validated::{Validated, ValidationRejection};
 pub use generated_routes::{
     GeneratedApiRoute, GeneratedPageRoute, generated_api_routes, generated_routes, pilcrow_router,
     register_generated_api_routes, register_generated_routes,
 };
 pub use pilcrow_macros::sse;
-pub use response::response::ToastLevel;
-pub use response::response::{ErrorResponse, ResponseExt, json, navigate, status};
+pub use response::config::ResponseConfig;
+pub use response::error::PilcrowError;
+pub use response::json_patch::JsonPatchOp;
+#[cfg(feature = "csv")]
+pub use response::response::csv;
+#[cfg(feature = "xml")]
+pub use response::response::xml;
+pub use response::response::{
+    ErrorResponse, IntoPilcrow, ResponseExt, accepted, download, download_stream, fragment, json,
+    json_patch, navigate, no_content, not_modified, status,
+};
+pub use response::response::{SwapMode, Toast, ToastAction, ToastLevel, ToastTransport};
+#[cfg(feature = "compression")]
+pub use sse::compress_sse;
 pub use sse::watch;
 pub use sse::{
-    EmitError, PilcrowStreamExt, SilcrowEvent, SseEmitter, SseRoute, interval, sse_raw, sse_stream,
+    EmitError, Lagged, LastEventId, PilcrowStreamExt, SilcrowEvent, SseBroadcaster, SseChannel,
+    SseConnection, SseEmitter, SseEventIds, SseLagPolicy, SseOptions, SseReplay, SseRoute,
+    SseSender, interval, sse_channel, sse_guarded, sse_interval, sse_patch_stream, sse_raw,
+    sse_stream, sse_with,
+};
+pub use ws::{
+    ConnectionId, OverflowPolicy, RateLimit, RateLimitAction, WsChannel, WsContext, WsDirection,
+    WsEvent, WsEventRouter, WsFormat, WsHub, WsInterceptor, WsMetrics, WsMetricsSnapshot, WsMux,
+    WsOptions, WsRecvError, WsRoute, WsSendError, WsSender, WsStream, WsUpgrade, ws_with,
 };
-pub use ws::ws::{WsEvent, WsRoute, WsStream};
 
 // ── Available but not primary API ────────────────────────────
 #[doc(hidden)]
@@ -0,0 +1,162 @@
+// ./src/live/event.rs
+//
+// An event built once and sent over whichever live transport a client is on:
+// a WebSocket hub, an SSE broadcaster, or both for an SSE fallback route.
+
+use crate::response::{JsonPatchOp, ToastLevel};
+use crate::sse::SilcrowEvent;
+use crate::ws::WsEvent;
+
+/// A live update that every transport can carry.
+///
+/// Converts into [`WsEvent`] and [`SilcrowEvent`], and both publishers accept
+/// it directly:
+///
+/// ```ignore
+/// let event = LiveEvent::patch(&order, "#order");
+/// app.hub.publish(event.clone());
+/// app.broadcaster.publish(event);
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    Patch {
+        target: String,
+        data: serde_json::Value,
+    },
+    JsonPatch {
+        target: String,
+        ops: Vec<JsonPatchOp>,
+    },
+    Html {
+        target: String,
+        markup: String,
+    },
+    Invalidate {
+        target: String,
+    },
+    Navigate {
+        path: String,
+    },
+    Custom {
+        event: String,
+        data: serde_json::Value,
+    },
+    Toast {
+        message: String,
+        level: ToastLevel,
+    },
+    Trigger {
+        event: String,
+        data: serde_json::Value,
+    },
+    PushHistory {
+        url: String,
+    },
+}
+
+impl LiveEvent {
+    pub fn patch(data: impl serde::Serialize, target: &str) -> Self {
+        Self::Patch {
+            target: target.to_owned(),
+            data: crate::serialize_or_null(data, "LiveEvent::patch"),
+        }
+    }
+
+    pub fn json_patch(ops: impl IntoIterator<Item = JsonPatchOp>, target: &str) -> Self {
+        Self::JsonPatch {
+            target: target.to_owned(),
+            ops: ops.into_iter().collect(),
+        }
+    }
+
+    pub fn html(markup: impl Into<String>, target: &str) -> Self {
+        Self::Html {
+            target: target.to_owned(),
+            markup: markup.into(),
+        }
+    }
+
+    pub fn invalidate(target: &str) -> Self {
+        Self::Invalidate {
+            target: target.to_owned(),
+        }
+    }
+
+    pub fn navigate(path: impl Into<String>) -> Self {
+        Self::Navigate { path: path.into() }
+    }
+
+    pub fn custom(event: impl Into<String>, data: impl serde::Serialize) -> Self {
+        Self::Custom {
+            event: event.into(),
+            data: crate::serialize_or_null(data, "LiveEvent::custom"),
+        }
+    }
+
+    pub fn toast(message: impl Into<String>, level: ToastLevel) -> Self {
+        Self::Toast {
+            message: message.into(),
+            level,
+        }
+    }
+
+    pub fn trigger(event: impl Into<String>, data: impl serde::Serialize) -> Self {
+        Self::Trigger {
+            event: event.into(),
+            data: crate::serialize_or_null(data, "LiveEvent::trigger"),
+        }
+    }
+
+    pub fn push_history(url: impl Into<String>) -> Self {
+        Self::PushHistory { url: url.into() }
+    }
+}
+
+impl From<LiveEvent> for WsEvent {
+    fn from(event: LiveEvent) -> Self {
+        match event {
+            LiveEvent::Patch { target, data } => Self::Patch { target, data },
+            LiveEvent::JsonPatch { target, ops } => Self::JsonPatch { target, ops },
+            LiveEvent::Html { target, markup } => Self::Html { target, markup },
+            LiveEvent::Invalidate { target } => Self::Invalidate { target },
+            LiveEvent::Navigate { path } => Self::Navigate { path },
+            LiveEvent::Custom { event, data } => Self::Custom { event, data },
+            LiveEvent::Toast { message, level } => Self::Toast { message, level },
+            LiveEvent::Trigger { event, data } => Self::Trigger { event, data },
+            LiveEvent::PushHistory { url } => Self::PushHistory { url },
+        }
+    }
+}
+
+impl From<WsEvent> for LiveEvent {
+    fn from(event: WsEvent) -> Self {
+        match event {
+            WsEvent::Patch { target, data } => Self::Patch { target, data },
+            WsEvent::JsonPatch { target, ops } => Self::JsonPatch { target, ops },
+            WsEvent::Html { target, markup } => Self::Html { target, markup },
+            WsEvent::Invalidate { target } => Self::Invalidate { target },
+            WsEvent::Navigate { path } => Self::Navigate { path },
+            WsEvent::Custom { event, data } => Self::Custom { event, data },
+            WsEvent::Toast { message, level } => Self::Toast { message, level },
+            WsEvent::Trigger { event, data } => Self::Trigger { event, data },
+            WsEvent::PushHistory { url } => Self::PushHistory { url },
+        }
+    }
+}
+
+impl From<LiveEvent> for SilcrowEvent {
+    fn from(event: LiveEvent) -> Self {
+        WsEvent::from(event).into()
+    }
+}
+
+/// Fails for the SSE-only events `WsEvent` cannot carry either; see
+/// `TryFrom<SilcrowEvent> for WsEvent`.
+impl TryFrom<SilcrowEvent> for LiveEvent {
+    type Error = SilcrowEvent;
+
+    fn try_from(event: SilcrowEvent) -> Result<Self, Self::Error> {
+        WsEvent::try_from(event).map(Self::from)
+    }
+}
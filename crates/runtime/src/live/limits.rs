@@ -0,0 +1,264 @@
+// ./src/live/limits.rs
+//
+// Per-identity caps on concurrent live (SSE/WS) connections.
+
+use crate::extract::extract::{RequestMode, SilcrowRequest};
+use crate::response::response::{ResponseExt, html, json};
+use axum::extract::ConnectInfo;
+use axum::http::StatusCode;
+use axum::http::request::Parts;
+use axum::response::{IntoResponse, Response};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::watch;
+
+/// Close code a WebSocket superseded under [`LimitPolicy::SupersedeOldest`]
+/// is closed with. silcrow.js does not reconnect after it.
+pub(crate) const SUPERSEDED_CLOSE_CODE: u16 = 4001;
+
+/// Works out who opened a connection, from the upgrade or subscribe request.
+type IdentifyFn = Arc<dyn Fn(&Parts) -> Option<String> + Send + Sync>;
+
+/// What happens when an identity opens more connections than allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LimitPolicy {
+    /// Refuse the new connection with a 429.
+    #[default]
+    Reject,
+    /// Admit the new connection and close the oldest one: a WebSocket gets a
+    /// Close frame, an SSE stream a `close` event.
+    SupersedeOldest,
+}
+
+#[derive(Debug)]
+struct Slot {
+    id: u64,
+    superseded: watch::Sender<bool>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    max_per_identity: usize,
+    policy: LimitPolicy,
+    next_id: AtomicU64,
+    slots: Mutex<HashMap<String, VecDeque<Slot>>>,
+}
+
+impl Inner {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, VecDeque<Slot>>> {
+        self.slots
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Tracks live connections per identity (session id, user id, client IP, …).
+///
+/// Cheap to clone; share one limiter across every live route it should cover.
+/// [`SseBroadcaster::with_limiter`](crate::sse::SseBroadcaster::with_limiter)
+/// and [`WsHub::with_limiter`](crate::ws::WsHub::with_limiter) enforce it
+/// for connections accepted through their `*_for` methods and through
+/// [`PilcrowLive::attach`](crate::live::PilcrowLive::attach):
+///
+/// ```ignore
+/// let limiter = ConnectionLimiter::with_policy(3, LimitPolicy::SupersedeOldest)
+///     .identify_by(|parts| parts.extensions.get::<Session>().map(|s| s.id.clone()));
+/// let live = PilcrowLive::new()
+///     .with_sse(FEED, SseBroadcaster::default().with_limiter(limiter.clone()))
+///     .with_ws(CHAT, WsHub::new().with_limiter(limiter));
+/// ```
+#[derive(Clone)]
+pub struct ConnectionLimiter {
+    inner: Arc<Inner>,
+    identify: Option<IdentifyFn>,
+}
+
+impl ConnectionLimiter {
+    /// Allows at most `max_per_identity` concurrent connections, rejecting the excess.
+    pub fn new(max_per_identity: usize) -> Self {
+        Self::with_policy(max_per_identity, LimitPolicy::Reject)
+    }
+
+    pub fn with_policy(max_per_identity: usize, policy: LimitPolicy) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                max_per_identity: max_per_identity.max(1),
+                policy,
+                next_id: AtomicU64::new(0),
+                slots: Mutex::new(HashMap::new()),
+            }),
+            identify: None,
+        }
+    }
+
+    /// How a request's identity is found. Defaults to the client IP from
+    /// axum's `ConnectInfo<SocketAddr>`; requests without one are not
+    /// limited.
+    pub fn identify_by<F>(mut self, identify: F) -> Self
+    where
+        F: Fn(&Parts) -> Option<String> + Send + Sync + 'static,
+    {
+        self.identify = Some(Arc::new(identify));
+        self
+    }
+
+    /// The identity `request` counts against, if it has one.
+    pub fn identity(&self, request: &Parts) -> Option<String> {
+        match &self.identify {
+            Some(identify) => identify(request),
+            None => request
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string()),
+        }
+    }
+
+    /// Claims a slot for whoever sent `request`; `Ok(None)` when it has no
+    /// identity.
+    pub(crate) fn admit(
+        &self,
+        request: &Parts,
+    ) -> Result<Option<ConnectionPermit>, ConnectionLimitExceeded> {
+        self.identity(request)
+            .map(|identity| self.acquire(identity))
+            .transpose()
+    }
+
+    /// Claims a connection slot for `identity`. Hold the permit for the connection's lifetime.
+    pub fn acquire(
+        &self,
+        identity: impl Into<String>,
+    ) -> Result<ConnectionPermit, ConnectionLimitExceeded> {
+        let identity = identity.into();
+        let mut slots = self.inner.lock();
+        let active = slots.entry(identity.clone()).or_default();
+
+        if active.len() >= self.inner.max_per_identity {
+            match self.inner.policy {
+                LimitPolicy::Reject => {
+                    return Err(ConnectionLimitExceeded {
+                        identity,
+                        limit: self.inner.max_per_identity,
+                    });
+                }
+                LimitPolicy::SupersedeOldest => {
+                    if let Some(oldest) = active.pop_front() {
+                        let _ = oldest.superseded.send(true);
+                    }
+                }
+            }
+        }
+
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = watch::channel(false);
+        active.push_back(Slot { id, superseded: tx });
+
+        Ok(ConnectionPermit {
+            inner: self.inner.clone(),
+            identity,
+            id,
+            superseded: rx,
+        })
+    }
+
+    /// Number of live connections currently held by `identity`.
+    pub fn active(&self, identity: &str) -> usize {
+        self.inner.lock().get(identity).map_or(0, VecDeque::len)
+    }
+}
+
+impl std::fmt::Debug for ConnectionLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionLimiter")
+            .field("max_per_identity", &self.inner.max_per_identity)
+            .field("policy", &self.inner.policy)
+            .field("identify", &self.identify.is_some())
+            .finish()
+    }
+}
+
+/// A claimed connection slot. Dropping it frees the slot.
+#[derive(Debug)]
+pub struct ConnectionPermit {
+    inner: Arc<Inner>,
+    identity: String,
+    id: u64,
+    superseded: watch::Receiver<bool>,
+}
+
+impl ConnectionPermit {
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    /// `true` once a newer connection from the same identity took this slot.
+    pub fn is_superseded(&self) -> bool {
+        *self.superseded.borrow()
+    }
+
+    /// Resolves when a newer connection from the same identity takes this slot.
+    /// Race it against the stream loop to close the older connection.
+    pub async fn superseded(&mut self) {
+        let _ = self.superseded.wait_for(|superseded| *superseded).await;
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let mut slots = self.inner.lock();
+        if let Some(active) = slots.get_mut(&self.identity) {
+            active.retain(|slot| slot.id != self.id);
+            if active.is_empty() {
+                slots.remove(&self.identity);
+            }
+        }
+    }
+}
+
+/// Returned when an identity is already at its connection limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionLimitExceeded {
+    pub identity: String,
+    pub limit: usize,
+}
+
+impl ConnectionLimitExceeded {
+    fn message(&self) -> String {
+        format!(
+            "Too many live connections (limit {}). Close another tab and retry.",
+            self.limit
+        )
+    }
+
+    /// Renders the rejection in the representation the client asked for.
+    pub fn into_negotiated_response(self, req: &SilcrowRequest) -> Response {
+        match req.preferred_mode() {
+            RequestMode::Html => html(format!("<p>{}</p>", self.message()))
+                .with_status(StatusCode::TOO_MANY_REQUESTS)
+                .into_response(),
+            RequestMode::Json => self.into_response(),
+        }
+    }
+}
+
+impl std::fmt::Display for ConnectionLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "connection limit of {} reached", self.limit)
+    }
+}
+
+impl std::error::Error for ConnectionLimitExceeded {}
+
+impl IntoResponse for ConnectionLimitExceeded {
+    fn into_response(self) -> Response {
+        json(serde_json::json!({
+            "error": "connection_limit",
+            "message": self.message(),
+            "limit": self.limit,
+        }))
+        .with_status(StatusCode::TOO_MANY_REQUESTS)
+        .into_response()
+    }
+}
@@ -0,0 +1,22 @@
+// src/live/mod.rs
+mod event;
+mod limits;
+mod priority;
+#[cfg(feature = "redis")]
+mod redis_hub;
+mod registry;
+
+pub use event::LiveEvent;
+pub(crate) use limits::SUPERSEDED_CLOSE_CODE;
+pub use limits::{ConnectionLimitExceeded, ConnectionLimiter, ConnectionPermit, LimitPolicy};
+
+pub(crate) use priority::fifo_channel;
+pub use priority::{
+    EventPriority, Prioritized, PriorityReceiver, PrioritySender, PushError, PushOutcome,
+    WeakPrioritySender, priority_channel,
+};
+
+pub use registry::PilcrowLive;
+
+#[cfg(feature = "redis")]
+pub use redis_hub::{RedisEnvelope, RedisHub};
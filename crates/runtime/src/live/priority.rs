@@ -0,0 +1,380 @@
+// ./src/live/priority.rs
+//
+// Bounded outbound queue that drains critical live instructions first and
+// sheds bulky low-priority frames when a client falls behind.
+
+use crate::ws::WsEvent;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::Notify;
+
+/// Delivery priority of a live event. Higher priorities are drained first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventPriority {
+    /// Bulky markup frames — first to be dropped under backpressure.
+    Low,
+    /// Data patches, invalidations and custom events.
+    Normal,
+    /// Navigation and notifications the user must see.
+    High,
+}
+
+impl EventPriority {
+    const LANES: usize = 3;
+
+    fn lane(self) -> usize {
+        self as usize
+    }
+}
+
+/// Classifies an outbound event for the priority queue.
+pub trait Prioritized {
+    fn priority(&self) -> EventPriority;
+
+    /// Whether the item ends the stream. It is delivered after everything
+    /// queued before it, is never shed, and nothing is accepted after it.
+    fn is_final(&self) -> bool {
+        false
+    }
+}
+
+impl Prioritized for WsEvent {
+    fn priority(&self) -> EventPriority {
+        match self {
+            Self::Navigate { .. } | Self::Toast { .. } => EventPriority::High,
+            Self::Html { .. } => EventPriority::Low,
+            Self::Patch { .. }
+            | Self::JsonPatch { .. }
+            | Self::Invalidate { .. }
+            | Self::Custom { .. }
+            | Self::Trigger { .. }
+            | Self::PushHistory { .. } => EventPriority::Normal,
+        }
+    }
+}
+
+/// Result of a successful [`PrioritySender::push`].
+#[derive(Debug)]
+pub enum PushOutcome<T> {
+    /// The item was queued without displacing anything.
+    Queued,
+    /// The queue was full; the oldest lower-priority item was dropped to make room.
+    Displaced(T),
+}
+
+/// Why an item could not be queued. The rejected item is handed back.
+#[derive(Debug)]
+pub enum PushError<T> {
+    /// The queue is full of items at the same or higher priority.
+    Full(T),
+    /// The receiver is gone.
+    Closed(T),
+}
+
+impl<T> PushError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(item) | Self::Closed(item) => item,
+        }
+    }
+}
+
+struct State<T> {
+    lanes: [VecDeque<T>; EventPriority::LANES],
+    /// The final item, held back until every lane has drained.
+    last: Option<T>,
+    /// Set once the final item has been queued.
+    finished: bool,
+    receiver_alive: bool,
+}
+
+impl<T> State<T> {
+    fn len(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum::<usize>() + usize::from(self.last.is_some())
+    }
+
+    fn pop_highest(&mut self) -> Option<T> {
+        self.lanes
+            .iter_mut()
+            .rev()
+            .find_map(VecDeque::pop_front)
+            .or_else(|| self.last.take())
+    }
+
+    fn evict_below(&mut self, priority: EventPriority) -> Option<T> {
+        self.lanes[..priority.lane()]
+            .iter_mut()
+            .find_map(VecDeque::pop_front)
+    }
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    capacity: usize,
+    /// `false` for a [`fifo_channel`]: one lane, nothing shed.
+    prioritized: bool,
+    senders: AtomicUsize,
+    item_ready: Notify,
+    space_ready: Notify,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Creates a bounded priority queue holding at most `capacity` items.
+pub fn priority_channel<T: Prioritized>(
+    capacity: usize,
+) -> (PrioritySender<T>, PriorityReceiver<T>) {
+    channel(capacity, true)
+}
+
+/// The same queue without priorities: items are delivered in the order they
+/// were sent and never shed; senders wait while it is full. Final items
+/// still end it.
+pub(crate) fn fifo_channel<T: Prioritized>(
+    capacity: usize,
+) -> (PrioritySender<T>, PriorityReceiver<T>) {
+    channel(capacity, false)
+}
+
+fn channel<T: Prioritized>(
+    capacity: usize,
+    prioritized: bool,
+) -> (PrioritySender<T>, PriorityReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            lanes: std::array::from_fn(|_| VecDeque::new()),
+            last: None,
+            finished: false,
+            receiver_alive: true,
+        }),
+        capacity: capacity.max(1),
+        prioritized,
+        senders: AtomicUsize::new(1),
+        item_ready: Notify::new(),
+        space_ready: Notify::new(),
+    });
+    (
+        PrioritySender {
+            shared: shared.clone(),
+        },
+        PriorityReceiver { shared },
+    )
+}
+
+/// Producer half of a [`priority_channel`]. Cloneable.
+pub struct PrioritySender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Prioritized> PrioritySender<T> {
+    /// Queues `item` without waiting.
+    ///
+    /// When the queue is full, the oldest item of the lowest priority below
+    /// `item`'s is dropped to make room; otherwise `item` is rejected. A
+    /// [final](Prioritized::is_final) item is always queued, and closes the
+    /// queue to further pushes.
+    pub fn push(&self, item: T) -> Result<PushOutcome<T>, PushError<T>> {
+        let outcome = {
+            let mut state = self.shared.lock();
+            if !state.receiver_alive || state.finished {
+                return Err(PushError::Closed(item));
+            }
+            if item.is_final() {
+                state.last = Some(item);
+                state.finished = true;
+                drop(state);
+                self.shared.item_ready.notify_one();
+                return Ok(PushOutcome::Queued);
+            }
+            let priority = match self.shared.prioritized {
+                true => item.priority(),
+                false => EventPriority::Normal,
+            };
+            let outcome = if state.len() < self.shared.capacity {
+                PushOutcome::Queued
+            } else if !self.shared.prioritized {
+                return Err(PushError::Full(item));
+            } else {
+                match state.evict_below(priority) {
+                    Some(dropped) => PushOutcome::Displaced(dropped),
+                    None => return Err(PushError::Full(item)),
+                }
+            };
+            state.lanes[priority.lane()].push_back(item);
+            outcome
+        };
+        self.shared.item_ready.notify_one();
+        Ok(outcome)
+    }
+
+    /// Queues `item`, waiting for space only when nothing lower-priority can be dropped.
+    pub async fn send(&self, mut item: T) -> Result<PushOutcome<T>, T> {
+        loop {
+            let space = self.shared.space_ready.notified();
+            match self.push(item) {
+                Ok(outcome) => return Ok(outcome),
+                Err(PushError::Closed(rejected)) => return Err(rejected),
+                Err(PushError::Full(rejected)) => item = rejected,
+            }
+            space.await;
+        }
+    }
+
+    /// Returns `true` once the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver_alive
+    }
+
+    /// Resolves once the receiver has been dropped.
+    pub async fn closed(&self) {
+        loop {
+            let dropped = self.shared.space_ready.notified();
+            if self.is_closed() {
+                return;
+            }
+            dropped.await;
+        }
+    }
+
+    /// A handle that observes the channel without keeping it open.
+    pub fn downgrade(&self) -> WeakPrioritySender<T> {
+        WeakPrioritySender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Clone for PrioritySender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::AcqRel);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for PrioritySender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.item_ready.notify_one();
+            self.shared.space_ready.notify_waiters();
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for PrioritySender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrioritySender")
+            .field("capacity", &self.shared.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Non-counting handle from [`PrioritySender::downgrade`]: it cannot send,
+/// and the receiver still sees the channel end once every sender is gone.
+pub struct WeakPrioritySender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> WeakPrioritySender<T> {
+    /// Returns `true` once the receiver has been dropped or every sender is gone.
+    pub fn is_closed(&self) -> bool {
+        self.shared.senders.load(Ordering::Acquire) == 0 || !self.shared.lock().receiver_alive
+    }
+
+    /// Resolves once the receiver has been dropped or every sender is gone.
+    pub async fn closed(&self) {
+        loop {
+            let changed = self.shared.space_ready.notified();
+            if self.is_closed() {
+                return;
+            }
+            changed.await;
+        }
+    }
+}
+
+impl<T> Clone for WeakPrioritySender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for WeakPrioritySender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakPrioritySender")
+            .field("capacity", &self.shared.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Consumer half of a [`priority_channel`].
+pub struct PriorityReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> PriorityReceiver<T> {
+    /// Waits for the highest-priority queued item.
+    /// Returns `None` once every sender is gone, or a final item was
+    /// received, and the queue is drained.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            let ready = self.shared.item_ready.notified();
+            let (popped, finished) = {
+                let mut state = self.shared.lock();
+                (state.pop_highest(), state.finished)
+            };
+            if let Some(item) = popped {
+                self.shared.space_ready.notify_waiters();
+                return Some(item);
+            }
+            if finished || self.shared.senders.load(Ordering::Acquire) == 0 {
+                return None;
+            }
+            ready.await;
+        }
+    }
+
+    /// Adapts the receiver into a `Stream` that ends once every sender is gone.
+    pub fn into_stream(self) -> impl futures_core::Stream<Item = T> + Send + 'static
+    where
+        T: Send + 'static,
+    {
+        futures_util::stream::unfold(self, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        })
+    }
+
+    /// Number of items currently queued.
+    pub fn len(&self) -> usize {
+        self.shared.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Drop for PriorityReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_alive = false;
+        self.shared.space_ready.notify_waiters();
+    }
+}
+
+impl<T> std::fmt::Debug for PriorityReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriorityReceiver")
+            .field("capacity", &self.shared.capacity)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,243 @@
+// ./src/live/redis_hub.rs
+//
+// Relays hub and broadcaster publishes through Redis pub/sub so every server
+// instance delivers them to its own locally connected clients.
+
+use crate::live::LiveEvent;
+use crate::sse::{SilcrowEvent, SseBroadcaster};
+use crate::ws::{WsEvent, WsHub};
+use futures_util::StreamExt;
+use redis::{AsyncCommands, RedisError, aio::MultiplexedConnection};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Wait before re-subscribing after the pub/sub connection drops.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// What travels over the Redis channel, as JSON. Other services can publish
+/// these to the channel directly.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "to", rename_all = "snake_case")]
+pub enum RedisEnvelope {
+    /// For each instance's [`WsHub`]: every connection, or the members of `room`.
+    Hub {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        event: WsEvent,
+    },
+    /// For each instance's [`SseBroadcaster`]: every subscriber, or those of
+    /// `topic`.
+    Broadcaster {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        topic: Option<String>,
+        event: LiveEvent,
+    },
+}
+
+/// Where an instance delivers relayed envelopes.
+#[derive(Debug, Clone)]
+struct Local {
+    hub: WsHub,
+    broadcaster: Option<SseBroadcaster>,
+}
+
+impl Local {
+    fn deliver(&self, envelope: RedisEnvelope) {
+        match envelope {
+            RedisEnvelope::Hub { room: None, event } => {
+                self.hub.publish(event);
+            }
+            RedisEnvelope::Hub {
+                room: Some(room),
+                event,
+            } => {
+                self.hub.publish_to(&room, event);
+            }
+            RedisEnvelope::Broadcaster { topic, event } => {
+                let Some(broadcaster) = &self.broadcaster else {
+                    tracing::debug!("RedisHub has no broadcaster; dropped an SSE event");
+                    return;
+                };
+                let event = SilcrowEvent::from(event);
+                match topic {
+                    Some(topic) => broadcaster.publish_to(&topic, event),
+                    None => broadcaster.publish(event),
+                };
+            }
+        }
+    }
+}
+
+/// Aborts the subscriber task once the last `RedisHub` clone is dropped.
+#[derive(Debug)]
+struct Subscriber(JoinHandle<()>);
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// A [`WsHub`], and optionally an [`SseBroadcaster`], whose broadcasts go
+/// through a Redis channel.
+///
+/// Every instance connects to the same channel. `publish` sends the event to
+/// Redis only; each instance — this one included — receives it back and fans
+/// it out to its local connections, so a client sees it exactly once
+/// regardless of which instance it is connected to. `publish_sse` does the
+/// same for SSE subscribers.
+#[derive(Debug, Clone)]
+pub struct RedisHub {
+    hub: WsHub,
+    channel: Arc<str>,
+    publisher: MultiplexedConnection,
+    _subscriber: Arc<Subscriber>,
+}
+
+impl RedisHub {
+    /// Connects to `url`, subscribes to `channel`, and starts relaying into `hub`.
+    pub async fn connect(
+        url: &str,
+        channel: impl Into<String>,
+        hub: WsHub,
+    ) -> Result<Self, RedisError> {
+        Self::open(
+            url,
+            channel.into(),
+            Local {
+                hub,
+                broadcaster: None,
+            },
+        )
+        .await
+    }
+
+    /// Like [`RedisHub::connect`], also relaying into `broadcaster`.
+    pub async fn connect_with_sse(
+        url: &str,
+        channel: impl Into<String>,
+        hub: WsHub,
+        broadcaster: SseBroadcaster,
+    ) -> Result<Self, RedisError> {
+        Self::open(
+            url,
+            channel.into(),
+            Local {
+                hub,
+                broadcaster: Some(broadcaster),
+            },
+        )
+        .await
+    }
+
+    async fn open(url: &str, channel: String, local: Local) -> Result<Self, RedisError> {
+        let client = redis::Client::open(url)?;
+        let publisher = client.get_multiplexed_async_connection().await?;
+        let channel: Arc<str> = channel.into();
+
+        // Subscribe before returning so nothing published afterwards is missed.
+        let mut pubsub = client.get_async_pubsub().await?;
+        pubsub.subscribe(channel.as_ref()).await?;
+        let hub = local.hub.clone();
+        let task = tokio::spawn(relay(client, pubsub, channel.clone(), local));
+
+        Ok(Self {
+            hub,
+            channel,
+            publisher,
+            _subscriber: Arc::new(Subscriber(task)),
+        })
+    }
+
+    /// The local hub: register connections and manage rooms through it.
+    pub fn hub(&self) -> &WsHub {
+        &self.hub
+    }
+
+    /// Sends `event` to every client on every instance.
+    pub async fn publish(&self, event: WsEvent) -> Result<(), RedisError> {
+        self.send(RedisEnvelope::Hub { room: None, event }).await
+    }
+
+    /// Sends `event` to the members of `room` on every instance.
+    pub async fn publish_to(&self, room: &str, event: WsEvent) -> Result<(), RedisError> {
+        let room = Some(room.to_owned());
+        self.send(RedisEnvelope::Hub { room, event }).await
+    }
+
+    /// Sends `event` to every SSE subscriber on every instance connected
+    /// with a broadcaster.
+    pub async fn publish_sse(&self, event: impl Into<LiveEvent>) -> Result<(), RedisError> {
+        let event = event.into();
+        self.send(RedisEnvelope::Broadcaster { topic: None, event })
+            .await
+    }
+
+    /// Sends `event` to the SSE subscribers of `topic` on every instance
+    /// connected with a broadcaster.
+    pub async fn publish_sse_to(
+        &self,
+        topic: &str,
+        event: impl Into<LiveEvent>,
+    ) -> Result<(), RedisError> {
+        let topic = Some(topic.to_owned());
+        let event = event.into();
+        self.send(RedisEnvelope::Broadcaster { topic, event }).await
+    }
+
+    async fn send(&self, envelope: RedisEnvelope) -> Result<(), RedisError> {
+        let payload = serde_json::to_string(&envelope).map_err(|e| {
+            RedisError::from((
+                redis::ErrorKind::TypeError,
+                "RedisHub serialization failed",
+                e.to_string(),
+            ))
+        })?;
+        let mut publisher = self.publisher.clone();
+        publisher
+            .publish::<_, _, ()>(self.channel.as_ref(), payload)
+            .await
+    }
+}
+
+/// Delivers each relayed envelope locally, re-subscribing whenever Redis drops the connection.
+async fn relay(
+    client: redis::Client,
+    mut pubsub: redis::aio::PubSub,
+    channel: Arc<str>,
+    local: Local,
+) {
+    loop {
+        let mut messages = pubsub.into_on_message();
+        while let Some(message) = messages.next().await {
+            match message
+                .get_payload::<String>()
+                .map_err(|e| e.to_string())
+                .and_then(|payload| {
+                    serde_json::from_str::<RedisEnvelope>(&payload).map_err(|e| e.to_string())
+                }) {
+                Ok(envelope) => local.deliver(envelope),
+                Err(e) => tracing::warn!("RedisHub dropped malformed message: {e}"),
+            }
+        }
+
+        tracing::warn!("RedisHub subscription to {channel} lost — reconnecting");
+        pubsub = loop {
+            tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            match resubscribe(&client, &channel).await {
+                Ok(pubsub) => break pubsub,
+                Err(e) => tracing::warn!("RedisHub resubscribe failed: {e}"),
+            }
+        };
+    }
+}
+
+async fn resubscribe(
+    client: &redis::Client,
+    channel: &str,
+) -> Result<redis::aio::PubSub, RedisError> {
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(channel).await?;
+    Ok(pubsub)
+}
@@ -0,0 +1,137 @@
+// ./src/live/registry.rs
+//
+// One place to declare an app's live endpoints: a broadcaster per SseRoute and
+// a hub per WsRoute, mounted on the router and reachable from every handler.
+
+use crate::sse::{LastEventId, SseBroadcaster, SseRoute};
+use crate::ws::{WsHub, WsRoute};
+use axum::extract::{FromRequestParts, WebSocketUpgrade};
+use axum::http::StatusCode;
+use axum::http::request::Parts;
+use axum::routing::get;
+use axum::{Extension, Router, async_trait};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Publisher registry for live routes.
+///
+/// `attach` mounts a subscriber endpoint for every registered route and makes
+/// the registry extractable in handlers, so publishing needs no plumbing:
+///
+/// ```ignore
+/// const FEED: SseRoute = SseRoute::new("/events/feed");
+/// const CHAT: WsRoute = WsRoute::new("/ws/chat");
+///
+/// let live = PilcrowLive::new().sse(FEED).ws(CHAT);
+/// let app = live.attach(Router::new().route("/orders", post(create_order)));
+///
+/// async fn create_order(live: PilcrowLive) -> Response {
+///     if let Some(feed) = live.broadcaster(FEED) {
+///         feed.publish(SilcrowEvent::invalidate("#orders"));
+///     }
+///     // ...
+/// }
+/// ```
+///
+/// Apps with their own router state can hold the registry in it and
+/// implement `FromRef` to use `State<PilcrowLive>` instead.
+#[derive(Debug, Clone, Default)]
+pub struct PilcrowLive {
+    sse: Arc<HashMap<&'static str, SseBroadcaster>>,
+    ws: Arc<HashMap<&'static str, WsHub>>,
+}
+
+impl PilcrowLive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `route` with a default [`SseBroadcaster`].
+    pub fn sse(self, route: SseRoute) -> Self {
+        self.with_sse(route, SseBroadcaster::default())
+    }
+
+    /// Registers `route`, publishing through `broadcaster`. A later
+    /// registration for the same path wins.
+    pub fn with_sse(mut self, route: SseRoute, broadcaster: SseBroadcaster) -> Self {
+        Arc::make_mut(&mut self.sse).insert(route.path(), broadcaster);
+        self
+    }
+
+    /// Registers `route` with a new [`WsHub`].
+    pub fn ws(self, route: WsRoute) -> Self {
+        self.with_ws(route, WsHub::new())
+    }
+
+    /// Registers `route`, publishing through `hub`. A later registration for
+    /// the same path wins.
+    pub fn with_ws(mut self, route: WsRoute, hub: WsHub) -> Self {
+        Arc::make_mut(&mut self.ws).insert(route.path(), hub);
+        self
+    }
+
+    /// The broadcaster registered for `route`.
+    pub fn broadcaster(&self, route: SseRoute) -> Option<&SseBroadcaster> {
+        self.sse.get(route.path())
+    }
+
+    /// The hub registered for `route`.
+    pub fn hub(&self, route: WsRoute) -> Option<&WsHub> {
+        self.ws.get(route.path())
+    }
+
+    /// Mounts a `GET` subscriber endpoint at every registered path and makes
+    /// the registry available to handlers.
+    ///
+    /// SSE endpoints resume from the client's `Last-Event-ID`. WebSocket
+    /// endpoints register each connection with the route's hub and deliver
+    /// whatever it publishes; client messages are ignored. Both count
+    /// connections against the limiter given to the broadcaster or hub, if
+    /// any.
+    pub fn attach<S>(self, router: Router<S>) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let mut router = router;
+        for (&path, broadcaster) in self.sse.iter() {
+            let broadcaster = broadcaster.clone();
+            router = router.route(
+                path,
+                get(
+                    move |request: Parts, last_event_id: LastEventId| async move {
+                        broadcaster.handler_for(&request, last_event_id)
+                    },
+                ),
+            );
+        }
+        for (&path, hub) in self.ws.iter() {
+            let hub = hub.clone();
+            router = router.route(
+                path,
+                get(
+                    move |request: Parts, upgrade: WebSocketUpgrade| async move {
+                        hub.handle_for(&request, upgrade, |mut stream| async move {
+                            while stream.recv().await.is_some() {}
+                        })
+                    },
+                ),
+            );
+        }
+        router.layer(Extension(self))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for PilcrowLive
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Self>().cloned().ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "PilcrowLive is not attached to this router",
+        ))
+    }
+}
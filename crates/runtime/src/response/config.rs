@@ -0,0 +1,137 @@
+// ./src/response/config.rs
+//
+// App-wide response settings. They are installed as a layer and scoped to the
+// request being handled, so two routers (or two tests) in one process can
+// differ without sharing global state.
+//
+// The layer also records the mode the request prefers, so responses built
+// without a `SilcrowRequest` at hand (a `?`-converted error) still match it.
+
+use crate::extract::extract::{RequestMode, SilcrowRequest};
+use crate::response::response::ToastTransport;
+use axum::extract::Request;
+use futures_util::future::BoxFuture;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+tokio::task_local! {
+    static CURRENT: ResponseConfig;
+    static MODE: RequestMode;
+}
+
+/// Settings for the responses built while handling a request, applied with
+/// `Router::layer`. Routes outside the layer, and responses built outside a
+/// request (in a spawned task, in a unit test), get the defaults.
+///
+/// Layers nest: one on a single route overrides only the settings it sets.
+///
+/// ```ignore
+/// let app = Router::new()
+///     .route("/orders/:id", get(show))
+///     .route("/api/orders", get(list).layer(ResponseConfig::new().toast_transport(ToastTransport::Body)))
+///     .layer(
+///         ResponseConfig::new()
+///             .expose_error_details(false)
+///             .toast_transport(ToastTransport::Header),
+///     );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseConfig {
+    expose_error_details: Option<bool>,
+    toast_transport: Option<ToastTransport>,
+}
+
+impl ResponseConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether errors converted into [`PilcrowError`](crate::PilcrowError)
+    /// carry their source chain and backtrace as `detail`. Defaults to on in
+    /// debug builds and off in release.
+    pub fn expose_error_details(mut self, enabled: bool) -> Self {
+        self.expose_error_details = Some(enabled);
+        self
+    }
+
+    /// Where toasts travel. Defaults to [`ToastTransport::Body`].
+    pub fn toast_transport(mut self, transport: ToastTransport) -> Self {
+        self.toast_transport = Some(transport);
+        self
+    }
+
+    /// The settings of the request being handled, or the defaults.
+    pub(crate) fn current() -> Self {
+        CURRENT.try_with(|config| *config).unwrap_or_default()
+    }
+
+    /// The mode the request being handled prefers, if it passed through a
+    /// [`ResponseConfig`] layer.
+    pub(crate) fn request_mode() -> Option<RequestMode> {
+        MODE.try_with(|mode| *mode).ok()
+    }
+
+    pub(crate) fn error_details_exposed(&self) -> bool {
+        self.expose_error_details.unwrap_or(cfg!(debug_assertions))
+    }
+
+    pub(crate) fn toast_transport_or_default(&self) -> ToastTransport {
+        self.toast_transport.unwrap_or_default()
+    }
+
+    /// `self`, with the settings `inner` sets taking precedence.
+    fn overridden_by(self, inner: Self) -> Self {
+        Self {
+            expose_error_details: inner.expose_error_details.or(self.expose_error_details),
+            toast_transport: inner.toast_transport.or(self.toast_transport),
+        }
+    }
+}
+
+impl<S> Layer<S> for ResponseConfig {
+    type Service = ResponseConfigService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ResponseConfigService {
+            inner,
+            config: *self,
+        }
+    }
+}
+
+/// The service [`ResponseConfig`] wraps each route in.
+#[derive(Debug, Clone)]
+pub struct ResponseConfigService<S> {
+    inner: S,
+    config: ResponseConfig,
+}
+
+impl<S, B> Service<Request<B>> for ResponseConfigService<S>
+where
+    S: Service<Request<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let config = self.config;
+        let (parts, body) = request.into_parts();
+        let mode = match parts.extensions.get::<SilcrowRequest>() {
+            Some(cached) => cached.preferred_mode(),
+            None => SilcrowRequest::parse(&parts).preferred_mode(),
+        };
+        let response = self.inner.call(Request::from_parts(parts, body));
+        Box::pin(async move {
+            // Read when polled, inside any outer layer's scope.
+            let config = ResponseConfig::current().overridden_by(config);
+            CURRENT.scope(config, MODE.scope(mode, response)).await
+        })
+    }
+}
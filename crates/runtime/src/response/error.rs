@@ -0,0 +1,218 @@
+// ./src/response/error.rs
+//
+// One error type for handlers that serve both HTML and JSON. It renders as an
+// HTML fragment or a JSON object depending on what the request asked for, so
+// handlers stop hand-rolling the same split.
+//
+// Any `std::error::Error` converts into it, so `?` works in handlers that
+// return `Result<_, PilcrowError>`. What the conversion reveals follows the
+// request's `ResponseConfig`: the error chain (and a backtrace, when
+// captured) in debug builds, a bare "Internal Server Error" in release.
+
+use crate::extract::extract::{RequestMode, SilcrowRequest};
+use crate::response::config::ResponseConfig;
+use crate::response::response::{BaseResponse, ResponseExt, escape_html, html, json};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+/// An error response: status, a message safe to show users, and optional
+/// detail.
+///
+/// Renders as `<div class="silcrow-error">` markup for HTML requests and as
+/// `{"error": {"status", "message", "detail"}}` for JSON ones. Call
+/// [`for_request`](Self::for_request) to pick the mode from the request;
+/// without it the error renders in the mode recorded by the
+/// [`ResponseConfig`] layer, or as HTML outside one. Toasts and other
+/// modifiers from [`ResponseExt`] ride along.
+///
+/// ```ignore
+/// async fn show(req: SilcrowRequest, Path(id): Path<u64>) -> Result<Response, PilcrowError> {
+///     let order = find(id)
+///         .ok_or_else(|| PilcrowError::not_found("Order not found").for_request(&req))?;
+///     Ok(render(order, &req))
+/// }
+/// ```
+pub struct PilcrowError {
+    pub status: StatusCode,
+    pub message: String,
+    pub detail: Option<String>,
+    pub mode: Option<RequestMode>,
+    /// Boxed so `Result<_, PilcrowError>` stays small.
+    pub base: Box<BaseResponse>,
+}
+
+impl PilcrowError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            detail: None,
+            mode: None,
+            base: Box::default(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Renders in whichever mode `request` prefers.
+    pub fn for_request(self, request: &SilcrowRequest) -> Self {
+        self.with_mode(request.preferred_mode())
+    }
+
+    pub fn with_mode(mut self, mode: RequestMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// A 500 for `error`. The error is always logged; whether its chain
+    /// reaches the client follows
+    /// [`ResponseConfig::expose_error_details`] for the request being
+    /// handled, and under a [`ResponseConfig`] layer it renders in the mode
+    /// that request prefers.
+    ///
+    /// Also takes types that only deref to an error, e.g. a boxed one.
+    pub fn from_error(error: &(dyn std::error::Error + 'static)) -> Self {
+        let chain = error_chain(error);
+        tracing::error!(error = %chain, "handler failed");
+        let mut converted = Self::internal("Internal Server Error");
+        converted.mode = ResponseConfig::request_mode();
+        if ResponseConfig::current().error_details_exposed() {
+            let backtrace = std::backtrace::Backtrace::capture();
+            converted.detail = Some(match backtrace.status() {
+                std::backtrace::BacktraceStatus::Captured => format!("{chain}\n\n{backtrace}"),
+                _ => chain,
+            });
+        }
+        converted
+    }
+
+    fn render_html(&self) -> String {
+        let mut markup = format!(
+            "<div class=\"silcrow-error\" role=\"alert\" data-status=\"{}\"><p>{}</p>",
+            self.status.as_u16(),
+            escape_html(&self.message)
+        );
+        if let Some(detail) = &self.detail {
+            markup.push_str("<pre>");
+            markup.push_str(&escape_html(detail));
+            markup.push_str("</pre>");
+        }
+        markup.push_str("</div>");
+        markup
+    }
+
+    fn render_json(&self) -> serde_json::Value {
+        let mut error = serde_json::json!({
+            "status": self.status.as_u16(),
+            "message": self.message,
+        });
+        if let Some(detail) = &self.detail {
+            error["detail"] = serde_json::Value::String(detail.clone());
+        }
+        serde_json::json!({ "error": error })
+    }
+}
+
+/// `error: cause: root cause`, following `source()`.
+fn error_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        chain.push_str(": ");
+        chain.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    chain
+}
+
+/// Lets `?` turn any error, including a `thiserror` enum, into a 500.
+/// `PilcrowError` itself is deliberately not a `std::error::Error`, which is
+/// what keeps this blanket impl coherent.
+#[cfg(not(feature = "anyhow"))]
+impl<E> From<E> for PilcrowError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn from(error: E) -> Self {
+        Self::from_error(&error)
+    }
+}
+
+/// With the `anyhow` feature the blanket impl widens to everything anyhow
+/// takes, `anyhow::Error` included, so `.context(..)?` works in handlers.
+/// Every error accepted without the feature is still accepted.
+#[cfg(feature = "anyhow")]
+impl<E> From<E> for PilcrowError
+where
+    E: Into<anyhow::Error>,
+{
+    fn from(error: E) -> Self {
+        let error: anyhow::Error = error.into();
+        Self::from_error(error.as_ref())
+    }
+}
+
+impl ResponseExt for PilcrowError {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+
+impl IntoResponse for PilcrowError {
+    fn into_response(mut self) -> Response {
+        self.base.status = Some(self.status);
+        let mode = self.mode.or_else(ResponseConfig::request_mode);
+        match mode.unwrap_or(RequestMode::Html) {
+            RequestMode::Html => {
+                let mut response = html(self.render_html());
+                response.base = *self.base;
+                response.into_response()
+            }
+            RequestMode::Json => {
+                let mut response = json(self.render_json());
+                response.base = *self.base;
+                response.into_response()
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for PilcrowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PilcrowError")
+            .field("status", &self.status)
+            .field("message", &self.message)
+            .field("detail", &self.detail)
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Display for PilcrowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PilcrowError({}): {}", self.status, self.message)
+    }
+}
@@ -1,15 +1,86 @@
+//! Typed Silcrow headers. Response modifiers write them; handlers read the
+//! request-side ones (`silcrow-target`, `silcrow-current-url`, …) with
+//! `axum_extra::TypedHeader`, values already decoded:
+//!
+//! ```ignore
+//! use axum_extra::TypedHeader;
+//! use runtime::response::headers::{SilcrowCurrentUrl, SilcrowTarget};
+//!
+//! async fn cart(
+//!     target: Option<TypedHeader<SilcrowTarget>>,
+//!     TypedHeader(from): TypedHeader<SilcrowCurrentUrl>,
+//! ) -> HtmlResponse {
+//!     match target.as_deref().map(SilcrowTarget::as_str) {
+//!         Some("#cart-badge") => html(badge()),
+//!         _ => html(cart_page(from.as_str())),
+//!     }
+//! }
+//! ```
+
 use headers::{Error, Header, HeaderName, HeaderValue};
+use std::borrow::Cow;
 use std::iter;
 
+/// Marks a header value that was percent-encoded because it is not plain
+/// visible ASCII (a selector like `#café`, patch data with unicode, a
+/// newline). Borrowed from RFC 8187's extended-value syntax; silcrow.js
+/// strips it and decodes the rest.
+const ENCODED_PREFIX: &str = "utf-8''";
+
+/// `value` as a header value, percent-encoded behind [`ENCODED_PREFIX`] when
+/// it cannot be sent verbatim.
+/// `HeaderValue::from_str` would pass UTF-8 through as opaque bytes, which
+/// `fetch` then mangles, so anything outside printable ASCII is encoded.
+pub(crate) fn encode_value(value: &str) -> Option<HeaderValue> {
+    let printable = value
+        .bytes()
+        .all(|b| b == b'\t' || (0x20..0x7f).contains(&b));
+    if printable && !value.starts_with(ENCODED_PREFIX) {
+        return HeaderValue::from_str(value).ok();
+    }
+    let encoded = format!("{ENCODED_PREFIX}{}", urlencoding::encode(value));
+    HeaderValue::from_str(&encoded).ok()
+}
+
+/// Reverses [`encode_value`]; other values pass through unchanged.
+pub(crate) fn decode_value(value: &str) -> Cow<'_, str> {
+    value
+        .strip_prefix(ENCODED_PREFIX)
+        .and_then(|encoded| urlencoding::decode(encoded).ok())
+        .unwrap_or(Cow::Borrowed(value))
+}
+
 /// Macro to easily define a custom string-valued header for Pilcrow.
 macro_rules! define_string_header {
     ($struct_name:ident, $header_name:expr) => {
+        #[doc = concat!("The `", $header_name, "` header.")]
         #[derive(Debug, Clone, PartialEq, Eq)]
         pub struct $struct_name(pub String);
 
         impl $struct_name {
-            #[allow(dead_code)]
-            pub(crate) const NAME: &'static str = $header_name;
+            pub const NAME: &'static str = $header_name;
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $struct_name {
+            fn from(value: String) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<&str> for $struct_name {
+            fn from(value: &str) -> Self {
+                Self(value.to_owned())
+            }
+        }
+
+        impl From<$struct_name> for String {
+            fn from(header: $struct_name) -> Self {
+                header.0
+            }
         }
 
         impl Header for $struct_name {
@@ -24,11 +95,11 @@ macro_rules! define_string_header {
             {
                 let value = values.next().ok_or_else(Error::invalid)?;
                 let s = value.to_str().map_err(|_| Error::invalid())?;
-                Ok($struct_name(s.to_string()))
+                Ok($struct_name(decode_value(s).into_owned()))
             }
 
             fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
-                if let Ok(value) = HeaderValue::from_str(&self.0) {
+                if let Some(value) = encode_value(&self.0) {
                     values.extend(iter::once(value));
                 }
             }
@@ -48,3 +119,17 @@ define_string_header!(SilcrowInvalidate, "silcrow-invalidate");
 define_string_header!(SilcrowNavigate, "silcrow-navigate");
 define_string_header!(SilcrowSse, "silcrow-sse");
 define_string_header!(SilcrowWs, "silcrow-ws");
+define_string_header!(SilcrowSwap, "silcrow-swap");
+define_string_header!(SilcrowPatchBody, "silcrow-patch-body");
+define_string_header!(SilcrowHook, "silcrow-hook");
+define_string_header!(SilcrowPreserve, "silcrow-preserve");
+define_string_header!(SilcrowConfirm, "silcrow-confirm");
+define_string_header!(SilcrowRemove, "silcrow-remove");
+define_string_header!(SilcrowTitle, "silcrow-title");
+define_string_header!(SilcrowMeta, "silcrow-meta");
+define_string_header!(SilcrowPoll, "silcrow-poll");
+define_string_header!(SilcrowToast, "silcrow-toast");
+define_string_header!(SilcrowCurrentUrl, "silcrow-current-url");
+define_string_header!(SilcrowBoosted, "silcrow-boosted");
+define_string_header!(SilcrowHistoryRestore, "silcrow-history-restore");
+define_string_header!(SilcrowVersion, "silcrow-version");
@@ -0,0 +1,79 @@
+// ./src/response/json_patch.rs
+//
+// RFC 6902 JSON Patch operations. Instead of resending a whole bound object,
+// the server sends the handful of changes and the client applies them to the
+// data it last patched into the target, then re-renders the bindings.
+
+use serde::{Deserialize, Serialize};
+
+/// One RFC 6902 operation. Paths are JSON Pointers (`/rows/3/status`;
+/// `/rows/-` appends).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum JsonPatchOp {
+    Add {
+        path: String,
+        value: serde_json::Value,
+    },
+    Remove {
+        path: String,
+    },
+    Replace {
+        path: String,
+        value: serde_json::Value,
+    },
+    Move {
+        from: String,
+        path: String,
+    },
+    Copy {
+        from: String,
+        path: String,
+    },
+    /// Aborts the whole patch unless the value at `path` equals `value`.
+    Test {
+        path: String,
+        value: serde_json::Value,
+    },
+}
+
+impl JsonPatchOp {
+    pub fn add(path: impl Into<String>, value: impl Serialize) -> Self {
+        Self::Add {
+            path: path.into(),
+            value: crate::serialize_or_null(value, "JsonPatchOp::add"),
+        }
+    }
+
+    pub fn remove(path: impl Into<String>) -> Self {
+        Self::Remove { path: path.into() }
+    }
+
+    pub fn replace(path: impl Into<String>, value: impl Serialize) -> Self {
+        Self::Replace {
+            path: path.into(),
+            value: crate::serialize_or_null(value, "JsonPatchOp::replace"),
+        }
+    }
+
+    pub fn move_from(from: impl Into<String>, path: impl Into<String>) -> Self {
+        Self::Move {
+            from: from.into(),
+            path: path.into(),
+        }
+    }
+
+    pub fn copy_from(from: impl Into<String>, path: impl Into<String>) -> Self {
+        Self::Copy {
+            from: from.into(),
+            path: path.into(),
+        }
+    }
+
+    pub fn test(path: impl Into<String>, value: impl Serialize) -> Self {
+        Self::Test {
+            path: path.into(),
+            value: crate::serialize_or_null(value, "JsonPatchOp::test"),
+        }
+    }
+}
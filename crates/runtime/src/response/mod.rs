@@ -1,2 +1,22 @@
-pub(crate) mod headers;
+pub mod config;
+pub mod error;
+pub mod headers;
+pub mod json_patch;
+#[allow(clippy::module_inception)]
 pub mod response;
+#[cfg(any(feature = "maud", feature = "askama", feature = "minijinja"))]
+mod templates;
+pub(crate) mod toast_markup;
+
+pub use config::ResponseConfig;
+pub use error::PilcrowError;
+pub use json_patch::JsonPatchOp;
+#[cfg(feature = "csv")]
+pub use response::CsvResponse;
+#[cfg(feature = "xml")]
+pub use response::XmlResponse;
+pub use response::{
+    AdaptedResponse, BaseResponse, DownloadResponse, EmptyResponse, ErrorResponse, HtmlResponse,
+    IntoPilcrow, JsonPatchResponse, JsonResponse, NavigateResponse, ResponseExt, SwapMode, Toast,
+    ToastAction, ToastLevel, ToastTransport,
+};
@@ -1,13 +1,22 @@
+use crate::extract::conditional::quoted_etag;
+use crate::extract::extract::SilcrowRequest;
+use crate::extract::toasts::TOAST_COOKIE;
+use crate::response::config::ResponseConfig;
 use crate::response::headers::*;
+use crate::response::json_patch::JsonPatchOp;
+use crate::response::toast_markup;
 use axum::{
     Json,
     http::{HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Redirect, Response},
 };
 use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use cookie::CookieBuilder;
 use cookie::time::Duration;
 use headers::HeaderMapExt;
+use http_body_util::BodyExt;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 pub type ErrorResponse = Response;
 
@@ -29,14 +38,157 @@ impl ToastLevel {
             _ => Self::Info,
         }
     }
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Success => "success",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How the client places HTML into its target, sent as `silcrow-swap`.
+/// Names follow `Element.insertAdjacentHTML`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SwapMode {
+    /// Replace the target's children (the default).
+    #[default]
+    InnerHtml,
+    /// Replace the target itself.
+    OuterHtml,
+    BeforeBegin,
+    AfterBegin,
+    BeforeEnd,
+    AfterEnd,
+}
+
+impl SwapMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InnerHtml => "innerHTML",
+            Self::OuterHtml => "outerHTML",
+            Self::BeforeBegin => "beforebegin",
+            Self::AfterBegin => "afterbegin",
+            Self::BeforeEnd => "beforeend",
+            Self::AfterEnd => "afterend",
+        }
+    }
+}
+
+/// A toast notification. Only `message` and `level` are required; the rest
+/// are hints the client's toast handler receives alongside them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Toast {
     pub message: String,
     pub level: ToastLevel,
+    /// How long the toast stays up; `None` leaves it to the handler.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<ToastAction>,
+    /// Toasts sharing a key replace one another instead of stacking, e.g.
+    /// "Saving…" followed by "Saved".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Sanitized inline markup to show instead of `message`, which then
+    /// holds the same text without tags. Only [`Toast::rich`] sets it, and
+    /// deserializing sanitizes it again, so it never holds raw markup.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_markup"
+    )]
+    html: Option<String>,
+}
+
+fn deserialize_markup<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let markup = Option::<String>::deserialize(deserializer)?;
+    Ok(markup.map(|markup| toast_markup::sanitize(&markup)))
+}
+
+/// A link rendered inside a toast, e.g. "Undo".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToastAction {
+    pub label: String,
+    pub href: String,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>, level: ToastLevel) -> Self {
+        Self {
+            message: message.into(),
+            level,
+            duration_ms: None,
+            id: None,
+            action: None,
+            key: None,
+            html: None,
+        }
+    }
+
+    /// A toast with bold, italic, code and link markup. `markup` is
+    /// sanitized here — other tags are escaped, links keep only `http`,
+    /// `https`, `mailto` or site-relative targets — so user input is safe
+    /// to pass.
+    pub fn rich(markup: &str, level: ToastLevel) -> Self {
+        let mut toast = Self::new(toast_markup::plain_text(markup), level);
+        toast.html = Some(toast_markup::sanitize(markup));
+        toast
+    }
+
+    /// The sanitized markup of a [`rich`](Self::rich) toast.
+    pub fn html(&self) -> Option<&str> {
+        self.html.as_deref()
+    }
+
+    pub fn with_duration(mut self, duration: std::time::Duration) -> Self {
+        self.duration_ms = Some(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX));
+        self
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn with_action(mut self, label: impl Into<String>, href: impl Into<String>) -> Self {
+        self.action = Some(ToastAction {
+            label: label.into(),
+            href: href.into(),
+        });
+        self
+    }
+}
+
+/// Where a response's toasts travel. Chosen per router, or per route, with
+/// [`ResponseConfig::toast_transport`](crate::ResponseConfig::toast_transport).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToastTransport {
+    /// In the body as `_toasts` for JSON responses, otherwise a cookie.
+    #[default]
+    Body,
+    /// A short-lived `silcrow_toasts` cookie, whatever the body.
+    Cookie,
+    /// A `silcrow-toast` header, for CDNs or cookie policies that drop
+    /// `Set-Cookie`.
+    Header,
 }
 
+/// `silcrow-patch` values longer than this travel in the body instead, past
+/// the 4–8 KiB header limits common in proxies.
+const PATCH_HEADER_LIMIT: usize = 4 * 1024;
+
 #[derive(Default)]
 pub struct BaseResponse {
     pub headers: HeaderMap,
@@ -46,43 +198,77 @@ pub struct BaseResponse {
 }
 
 impl BaseResponse {
+    /// Takes the accumulated patches out of `silcrow-patch` when the header
+    /// has grown past [`PATCH_HEADER_LIMIT`], leaving a `silcrow-patch-body`
+    /// marker so the client looks for them in the body.
+    fn take_oversized_patches(&mut self) -> Option<serde_json::Value> {
+        let oversized = self
+            .headers
+            .get(SilcrowPatch::NAME)
+            .is_some_and(|value| value.len() > PATCH_HEADER_LIMIT);
+        if !oversized {
+            return None;
+        }
+        let patches = self.headers.typed_get::<SilcrowPatch>()?;
+        self.headers.remove(SilcrowPatch::NAME);
+        let patches = serde_json::from_str(&patches.0).ok()?;
+        self.headers.typed_insert(SilcrowPatchBody("1".to_string()));
+        Some(patches)
+    }
+
     pub fn apply_to_response(&self, response: &mut Response) {
+        // Set headers replace the handler's, but every value of a
+        // multi-valued one (`Link`) is kept.
+        for name in self.headers.keys() {
+            response.headers_mut().remove(name);
+        }
         self.headers.iter().for_each(|(name, value)| {
-            response.headers_mut().insert(name.clone(), value.clone());
+            response.headers_mut().append(name.clone(), value.clone());
         });
         if let Some(code) = self.status {
             *response.status_mut() = code;
         }
-        if self.toasts.is_empty() {
-            for cookie in self.cookies.iter() {
-                if let Ok(header_value) = HeaderValue::from_str(&cookie.to_string()) {
-                    response
-                        .headers_mut()
-                        .append(axum::http::header::SET_COOKIE, header_value);
-                }
-            }
-        } else {
-            let mut final_jar = self.cookies.clone();
-            if let Ok(json_string) = serde_json::to_string(&self.toasts) {
+        let mut final_jar = self.cookies.clone();
+        if !self.toasts.is_empty()
+            && let Ok(json_string) = serde_json::to_string(&self.toasts)
+        {
+            if ResponseConfig::current().toast_transport_or_default() == ToastTransport::Header {
+                response
+                    .headers_mut()
+                    .typed_insert(SilcrowToast(json_string));
+            } else {
                 let encoded = urlencoding::encode(&json_string).into_owned();
-                let toast_cookie = Cookie::build(("silcrow_toasts", encoded))
+                let toast_cookie = Cookie::build((TOAST_COOKIE, encoded))
                     .path("/")
                     .same_site(SameSite::Lax)
                     .max_age(Duration::seconds(5))
                     .build();
                 final_jar = final_jar.add(toast_cookie);
             }
-            for cookie in final_jar.iter() {
-                if let Ok(header_value) = HeaderValue::from_str(&cookie.to_string()) {
-                    response
-                        .headers_mut()
-                        .append(axum::http::header::SET_COOKIE, header_value);
-                }
+        }
+        for cookie in final_jar.iter() {
+            if let Ok(header_value) = HeaderValue::from_str(&cookie.to_string()) {
+                response
+                    .headers_mut()
+                    .append(axum::http::header::SET_COOKIE, header_value);
             }
         }
     }
 }
 
+/// Appends `entry` to the `silcrow-patch` list.
+fn push_patch_entry(headers: &mut HeaderMap, entry: serde_json::Value) {
+    let mut patches = match headers
+        .typed_get::<SilcrowPatch>()
+        .and_then(|existing| serde_json::from_str(&existing.0).ok())
+    {
+        Some(serde_json::Value::Array(patches)) => patches,
+        _ => Vec::new(),
+    };
+    patches.push(entry);
+    headers.typed_insert(SilcrowPatch(serde_json::Value::Array(patches).to_string()));
+}
+
 pub trait ResponseExt: Sized {
     fn base_mut(&mut self) -> &mut BaseResponse;
 
@@ -92,10 +278,34 @@ pub trait ResponseExt: Sized {
         }
         self
     }
+    /// Adds a `Link: <href>; rel="rel"` header, e.g. to `preload` the SSE
+    /// endpoint or `prefetch` the next page. Repeated calls add more links.
+    fn with_link(mut self, rel: &str, href: &str) -> Self {
+        match HeaderValue::from_str(&format!("<{href}>; rel=\"{rel}\"")) {
+            Ok(value) => {
+                self.base_mut()
+                    .headers
+                    .append(axum::http::header::LINK, value);
+            }
+            Err(_) => tracing::warn!(rel, href, "invalid Link header value; not sent"),
+        }
+        self
+    }
     fn with_status(mut self, status: StatusCode) -> Self {
         self.base_mut().status = Some(status);
         self
     }
+    /// Sets the `ETag` header; a bare tag (`v42`) is quoted. Pair with
+    /// [`SilcrowRequest::fresh`](crate::SilcrowRequest::fresh) and
+    /// [`not_modified`] to skip re-rendering unchanged content.
+    fn with_etag(mut self, etag: &str) -> Self {
+        if let Ok(value) = HeaderValue::from_str(&quoted_etag(etag)) {
+            self.base_mut()
+                .headers
+                .insert(axum::http::header::ETAG, value);
+        }
+        self
+    }
     fn no_cache(mut self) -> Self {
         self.base_mut()
             .headers
@@ -103,18 +313,147 @@ pub trait ResponseExt: Sized {
         self
     }
 
+    fn with_cookie(mut self, cookie: Cookie<'static>) -> Self {
+        let base = self.base_mut();
+        base.cookies = std::mem::take(&mut base.cookies).add(cookie);
+        self
+    }
+    /// Sets cookie `name`, with `configure` filling in the attributes:
+    ///
+    /// ```ignore
+    /// html(page).cookie("theme", "dark", |c| c.path("/").http_only(true).secure(true))
+    /// ```
+    fn cookie(
+        self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+        configure: impl FnOnce(CookieBuilder<'static>) -> CookieBuilder<'static>,
+    ) -> Self {
+        self.with_cookie(configure(Cookie::build((name.into(), value.into()))).build())
+    }
+    /// Expires cookie `name` on the client. The removal is sent for path
+    /// `/`, which is where [`cookie`](Self::cookie) callers usually set it; a
+    /// cookie set on another path needs `with_cookie` with a removal cookie
+    /// for that path.
+    fn remove_cookie(self, name: impl Into<String>) -> Self {
+        let mut cookie = Cookie::build((name.into(), "")).path("/").build();
+        cookie.make_removal();
+        self.with_cookie(cookie)
+    }
+    /// Remembers `tag` (`de`, `pt-BR`) as the user's language: the
+    /// [`Locale`](crate::extract::locale::Locale) extractor puts it ahead of
+    /// `Accept-Language` from the next request on. An implausible tag is
+    /// logged and ignored.
+    fn with_locale(self, tag: &str) -> Self {
+        match crate::extract::locale::locale_cookie(tag) {
+            Some(cookie) => self.with_cookie(cookie),
+            None => self,
+        }
+    }
+    /// Forgets the language set with [`with_locale`](Self::with_locale), so
+    /// `Accept-Language` decides again.
+    fn clear_locale(self) -> Self {
+        self.remove_cookie(crate::extract::locale::LOCALE_COOKIE)
+    }
+
     fn with_toast(mut self, message: impl Into<String>, level: ToastLevel) -> Self {
-        self.base_mut().toasts.push(Toast {
-            message: message.into(),
-            level,
-        });
+        self.base_mut().toasts.push(Toast::new(message, level));
+        self
+    }
+    /// Like [`with_toast`](Self::with_toast), for a toast built with a
+    /// duration, id, action or key. A keyed toast replaces any earlier one
+    /// with the same key on this response.
+    fn toast(mut self, toast: Toast) -> Self {
+        let toasts = &mut self.base_mut().toasts;
+        if let Some(key) = &toast.key {
+            toasts.retain(|queued| queued.key.as_ref() != Some(key));
+        }
+        toasts.push(toast);
         self
     }
-    fn trigger_event(mut self, event_name: &str) -> Self {
-        let map = serde_json::json!({ event_name: {} });
+    /// A toast with limited markup; see [`Toast::rich`].
+    fn with_rich_toast(self, markup: &str, level: ToastLevel) -> Self {
+        self.toast(Toast::rich(markup, level))
+    }
+    /// A toast the client replaces, rather than stacks, when another with
+    /// the same `key` arrives — from this response or a later one.
+    fn with_toast_keyed(
+        self,
+        key: impl Into<String>,
+        message: impl Into<String>,
+        level: ToastLevel,
+    ) -> Self {
+        self.toast(Toast::new(message, level).with_key(key))
+    }
+    fn trigger_event(self, event_name: &str) -> Self {
+        self.trigger_event_with(event_name, &serde_json::json!({}))
+    }
+    /// Fires `event_name` on the client with `data` as the event's `detail`.
+    /// Repeated calls merge into one `silcrow-trigger` object; a repeated
+    /// name keeps the latest data.
+    fn trigger_event_with(mut self, event_name: &str, data: &impl serde::Serialize) -> Self {
+        let headers = &mut self.base_mut().headers;
+        let mut events = match headers
+            .typed_get::<SilcrowTrigger>()
+            .and_then(|existing| serde_json::from_str(&existing.0).ok())
+        {
+            Some(serde_json::Value::Object(events)) => events,
+            _ => serde_json::Map::new(),
+        };
+        events.insert(
+            event_name.to_owned(),
+            crate::serialize_or_null(data, "ResponseExt::trigger_event_with"),
+        );
+        headers.typed_insert(SilcrowTrigger(
+            serde_json::Value::Object(events).to_string(),
+        ));
+        self
+    }
+    /// Calls the client function registered as `name` with
+    /// `Silcrow.hook(name, fn)`, passing `args`: `fn(args, {target})`. For
+    /// effects that aren't swaps — opening a modal, scrolling a chart. Only
+    /// registered hooks run; repeated calls queue in order in `silcrow-hook`.
+    fn run_hook(mut self, name: &str, args: &impl serde::Serialize) -> Self {
+        let headers = &mut self.base_mut().headers;
+        let mut hooks = match headers
+            .typed_get::<SilcrowHook>()
+            .and_then(|existing| serde_json::from_str(&existing.0).ok())
+        {
+            Some(serde_json::Value::Array(hooks)) => hooks,
+            _ => Vec::new(),
+        };
+        hooks.push(serde_json::json!({
+            "name": name,
+            "args": crate::serialize_or_null(args, "ResponseExt::run_hook"),
+        }));
+        headers.typed_insert(SilcrowHook(serde_json::Value::Array(hooks).to_string()));
+        self
+    }
+    /// Keeps elements matching `selector` across an HTML swap — the same
+    /// nodes stay in place, so a playing video, an open `<details>` or a
+    /// half-typed input survive. Repeated calls widen the selector list.
+    fn preserve(mut self, selector: &str) -> Self {
+        let headers = &mut self.base_mut().headers;
+        let selectors = match headers.typed_get::<SilcrowPreserve>() {
+            Some(existing) => format!("{}, {selector}", existing.0),
+            None => selector.to_owned(),
+        };
+        headers.typed_insert(SilcrowPreserve(selectors));
+        self
+    }
+    /// Has the client ask `message` and, only if the user agrees, POST to
+    /// `on_confirm_path`. The answer is swapped into the current target, so
+    /// a destructive action can be confirmed without any client-side code.
+    /// `Silcrow.onConfirm` replaces the native `confirm()` dialog.
+    fn confirm(mut self, message: &str, on_confirm_path: &str) -> Self {
+        let request = serde_json::json!({
+            "message": message,
+            "path": on_confirm_path,
+            "method": "POST",
+        });
         self.base_mut()
             .headers
-            .typed_insert(SilcrowTrigger(map.to_string()));
+            .typed_insert(SilcrowConfirm(request.to_string()));
         self
     }
     fn retarget(mut self, selector: &str) -> Self {
@@ -123,17 +462,41 @@ pub trait ResponseExt: Sized {
             .typed_insert(SilcrowRetarget(selector.to_string()));
         self
     }
+    /// Swaps the same content into every element in `selectors`, e.g. a
+    /// counter shown in both the header and the sidebar. Sent as a JSON
+    /// array in `silcrow-retarget`; the first selector that matches becomes
+    /// the main target.
+    fn retarget_many(mut self, selectors: &[&str]) -> Self {
+        let list = serde_json::json!(selectors).to_string();
+        self.base_mut().headers.typed_insert(SilcrowRetarget(list));
+        self
+    }
+    fn swap(mut self, mode: SwapMode) -> Self {
+        self.base_mut()
+            .headers
+            .typed_insert(SilcrowSwap(mode.as_str().to_string()));
+        self
+    }
     fn push_history(mut self, url: &str) -> Self {
         self.base_mut()
             .headers
             .typed_insert(SilcrowPush(url.to_string()));
         self
     }
+    /// Queues a data patch for `selector`. Repeated calls accumulate: the
+    /// `silcrow-patch` header carries a JSON array of `{target, data}` entries,
+    /// applied in order.
     fn patch_target(mut self, selector: &str, data: &impl serde::Serialize) -> Self {
-        let payload = serde_json::json!({ "data": data, "target": selector });
-        self.base_mut()
-            .headers
-            .typed_insert(SilcrowPatch(payload.to_string()));
+        let entry = serde_json::json!({ "data": data, "target": selector });
+        push_patch_entry(&mut self.base_mut().headers, entry);
+        self
+    }
+    /// Queues RFC 6902 operations for `selector`, applied to the data last
+    /// patched into it. Shares the `silcrow-patch` list with
+    /// [`patch_target`](Self::patch_target).
+    fn json_patch_target(mut self, selector: &str, ops: &[JsonPatchOp]) -> Self {
+        let entry = serde_json::json!({ "ops": ops, "target": selector });
+        push_patch_entry(&mut self.base_mut().headers, entry);
         self
     }
     fn invalidate_target(mut self, selector: &str) -> Self {
@@ -142,6 +505,62 @@ pub trait ResponseExt: Sized {
             .typed_insert(SilcrowInvalidate(selector.to_string()));
         self
     }
+    /// Sets `document.title` after a fragment swap, which would otherwise
+    /// leave the previous page's title in place.
+    fn with_title(mut self, title: &str) -> Self {
+        self.base_mut()
+            .headers
+            .typed_insert(SilcrowTitle(title.to_owned()));
+        self
+    }
+    /// Sets the `content` of `<meta name="…">` in the page head, adding the
+    /// tag if it is missing. Repeated calls merge; a repeated name keeps the
+    /// latest content.
+    fn with_meta(mut self, name: &str, content: &str) -> Self {
+        let headers = &mut self.base_mut().headers;
+        let mut tags = match headers
+            .typed_get::<SilcrowMeta>()
+            .and_then(|existing| serde_json::from_str(&existing.0).ok())
+        {
+            Some(serde_json::Value::Object(tags)) => tags,
+            _ => serde_json::Map::new(),
+        };
+        tags.insert(name.to_owned(), content.into());
+        headers.typed_insert(SilcrowMeta(serde_json::Value::Object(tags).to_string()));
+        self
+    }
+    /// Has the client re-request this URL into the same target every
+    /// `interval`, skipping its cache — live-ish data without SSE or a
+    /// WebSocket. A later `poll_every` changes the interval; polling ends
+    /// with [`stop_polling`](Self::stop_polling) or when the target leaves
+    /// the page.
+    fn poll_every(mut self, interval: std::time::Duration) -> Self {
+        let millis = interval.as_millis().max(1);
+        self.base_mut()
+            .headers
+            .typed_insert(SilcrowPoll(millis.to_string()));
+        self
+    }
+    /// Cancels polling started by [`poll_every`](Self::poll_every) for this
+    /// target, e.g. once a job finishes.
+    fn stop_polling(mut self) -> Self {
+        self.base_mut()
+            .headers
+            .typed_insert(SilcrowPoll("stop".to_owned()));
+        self
+    }
+    /// Deletes every element matching `selector` from the page, e.g. the row
+    /// a delete handler just removed, without re-rendering its table. Pairs
+    /// with [`no_content`]. Repeated calls widen the selector list.
+    fn remove_target(mut self, selector: &str) -> Self {
+        let headers = &mut self.base_mut().headers;
+        let selectors = match headers.typed_get::<SilcrowRemove>() {
+            Some(existing) => format!("{}, {selector}", existing.0),
+            None => selector.to_owned(),
+        };
+        headers.typed_insert(SilcrowRemove(selectors));
+        self
+    }
     fn client_navigate(mut self, path: &str) -> Self {
         self.base_mut()
             .headers
@@ -166,7 +585,9 @@ pub trait ResponseExt: Sized {
 }
 
 pub struct HtmlResponse {
-    pub data: String,
+    /// Borrowed for `&'static str` markup, so static pages and fragments are
+    /// sent without a copy.
+    pub data: Cow<'static, str>,
     pub base: BaseResponse,
 }
 impl From<String> for HtmlResponse {
@@ -180,8 +601,90 @@ impl From<&str> for HtmlResponse {
         html(s.to_owned())
     }
 }
+impl HtmlResponse {
+    /// Renders any template that implements `Display`, such as a
+    /// hand-written view. A failing render becomes a 500 instead of the
+    /// panic `to_string` would raise. The `maud`, `askama` and `minijinja`
+    /// features add direct integrations: `From<maud::Markup>`,
+    /// `HtmlResponse::askama` and `HtmlResponse::minijinja`.
+    ///
+    /// ```ignore
+    /// async fn show(Path(id): Path<u64>) -> Result<HtmlResponse, PilcrowError> {
+    ///     HtmlResponse::render(&OrderPage { order: find(id)? })
+    /// }
+    /// ```
+    pub fn render(template: &impl std::fmt::Display) -> Result<Self, crate::PilcrowError> {
+        use std::fmt::Write;
+        let mut markup = String::new();
+        write!(markup, "{template}").map_err(|_| {
+            tracing::error!("template failed to render");
+            crate::PilcrowError::internal("Template rendering failed")
+        })?;
+        Ok(html(markup))
+    }
+
+    /// Wraps the markup in `layout` for full page loads — a hard refresh, a
+    /// shared link — and leaves it a bare fragment when silcrow.js asked,
+    /// since it only swaps the fragment in.
+    ///
+    /// ```ignore
+    /// html(render_orders(&orders)).in_layout(&req, |body| page("Orders", &body))
+    /// ```
+    pub fn in_layout(
+        mut self,
+        request: &crate::SilcrowRequest,
+        layout: impl FnOnce(String) -> String,
+    ) -> Self {
+        if !request.is_silcrow {
+            self.data = layout(self.data.into_owned()).into();
+        }
+        self
+    }
+
+    /// Appends an out-of-band fragment: once the main content is swapped into
+    /// its target, the client replaces the contents of the element matching
+    /// `selector` with `markup`. One response can then also refresh a navbar
+    /// badge or a sidebar count. Each fragment travels in the body as a
+    /// `<template s-oob>` element, so it is never rendered in place.
+    pub fn with_oob_fragment(mut self, selector: &str, markup: impl AsRef<str>) -> Self {
+        self.data.to_mut().push_str("<template s-oob=\"");
+        self.data.to_mut().push_str(&escape_html(selector));
+        self.data.to_mut().push_str("\">");
+        self.data.to_mut().push_str(markup.as_ref());
+        self.data.to_mut().push_str("</template>");
+        self
+    }
+}
+
+pub(crate) fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 impl IntoResponse for HtmlResponse {
-    fn into_response(self) -> Response {
+    fn into_response(mut self) -> Response {
+        if let Some(patches) = self.base.take_oversized_patches() {
+            // `<`, `>` and `&` only occur inside JSON strings, where the
+            // escapes mean the same thing; the template's text is then
+            // exactly the JSON.
+            let json = patches
+                .to_string()
+                .replace('<', "\\u003c")
+                .replace('>', "\\u003e")
+                .replace('&', "\\u0026");
+            self.data.to_mut().push_str("<template s-patch>");
+            self.data.to_mut().push_str(&json);
+            self.data.to_mut().push_str("</template>");
+        }
         let mut response = axum::response::Html(self.data).into_response();
         self.base.apply_to_response(&mut response);
         response
@@ -193,30 +696,84 @@ pub struct JsonResponse<T> {
     pub base: BaseResponse,
 }
 
+/// Builds the 500 returned when a response payload fails to serialize.
+///
+/// Debug builds carry an `application/problem+json` body naming the payload type
+/// and the serde error; release builds return an empty body so internals never leak.
+fn serialization_failure_response(
+    response: &str,
+    type_name: &str,
+    error: &dyn std::fmt::Display,
+) -> Response {
+    tracing::error!(
+        payload_type = type_name,
+        "{response} serialization failed: {error}"
+    );
+    if !cfg!(debug_assertions) {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let problem = serde_json::json!({
+        "type": "about:blank",
+        "title": "Response serialization failed",
+        "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+        "detail": error.to_string(),
+        "payload_type": type_name,
+    });
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        [(
+            axum::http::header::CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        )],
+        problem.to_string(),
+    )
+        .into_response()
+}
+
+/// Adds a reserved `_`-prefixed key to a JSON body, wrapping non-object
+/// payloads as `{"data": ...}` first.
+fn attach_reserved(
+    payload: serde_json::Value,
+    key: &str,
+    value: serde_json::Value,
+) -> serde_json::Value {
+    match payload {
+        serde_json::Value::Object(mut map) => {
+            map.insert(key.to_string(), value);
+            serde_json::Value::Object(map)
+        }
+        other => serde_json::json!({
+            "data": other,
+            key: value
+        }),
+    }
+}
+
 impl<T: serde::Serialize> IntoResponse for JsonResponse<T> {
-    fn into_response(self) -> Response {
+    fn into_response(mut self) -> Response {
+        let patches = self.base.take_oversized_patches();
+        // Toasts that go in the body are taken so they don't also get a cookie.
+        let toasts =
+            if ResponseConfig::current().toast_transport_or_default() == ToastTransport::Body {
+                std::mem::take(&mut self.base.toasts)
+            } else {
+                Vec::new()
+            };
         serde_json::to_value(&self.data)
             .map_err(|e| {
-                tracing::error!("JsonResponse serialization failed: {e}");
-                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                serialization_failure_response("JsonResponse", std::any::type_name::<T>(), &e)
             })
             .map(|json_payload| {
-                if self.base.toasts.is_empty() {
+                if toasts.is_empty() {
                     json_payload
                 } else {
-                    let toasts_json = serde_json::json!(self.base.toasts);
-                    match json_payload {
-                        serde_json::Value::Object(mut map) => {
-                            map.insert("_toasts".to_string(), toasts_json);
-                            serde_json::Value::Object(map)
-                        }
-                        other => serde_json::json!({
-                            "data": other,
-                            "_toasts": toasts_json
-                        }),
-                    }
+                    attach_reserved(json_payload, "_toasts", serde_json::json!(toasts))
                 }
             })
+            .map(|json_payload| match patches {
+                Some(patches) => attach_reserved(json_payload, "_patches", patches),
+                None => json_payload,
+            })
             .map(|final_payload| {
                 let mut response = Json(final_payload).into_response();
                 self.base.apply_to_response(&mut response);
@@ -243,12 +800,372 @@ impl IntoResponse for NavigateResponse {
     }
 }
 
-pub fn html(data: impl Into<String>) -> HtmlResponse {
+impl NavigateResponse {
+    /// Hands `data` to the request the redirect leads to, which reads it with
+    /// the [`Flash`](crate::extract::flash::Flash) extractor.
+    pub fn with_flash(self, data: &impl Serialize) -> Self {
+        match crate::extract::flash::flash_cookie(data) {
+            Some(cookie) => self.with_cookie(cookie),
+            None => self,
+        }
+    }
+}
+
+/// Rows serialized as CSV by [`csv`], sent as `text/csv`.
+#[cfg(feature = "csv")]
+pub struct CsvResponse<T> {
+    pub rows: Vec<T>,
+    pub base: BaseResponse,
+}
+
+/// A value serialized as XML by [`xml`], sent as `application/xml`.
+#[cfg(feature = "xml")]
+pub struct XmlResponse<T> {
+    pub data: T,
+    pub base: BaseResponse,
+}
+
+fn text_response(data: String, content_type: &'static str, base: &BaseResponse) -> Response {
+    let mut response = (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            HeaderValue::from_static(content_type),
+        )],
+        data,
+    )
+        .into_response();
+    base.apply_to_response(&mut response);
+    response
+}
+
+#[cfg(feature = "csv")]
+impl<T: Serialize> IntoResponse for CsvResponse<T> {
+    fn into_response(self) -> Response {
+        let mut writer = ::csv::WriterBuilder::new()
+            .terminator(::csv::Terminator::CRLF)
+            .from_writer(Vec::new());
+        let data = self
+            .rows
+            .iter()
+            .try_for_each(|row| writer.serialize(row))
+            .map_err(|e| e.to_string())
+            .and_then(|()| writer.into_inner().map_err(|e| e.to_string()))
+            .and_then(|bytes| String::from_utf8(bytes).map_err(|e| e.to_string()));
+        match data {
+            Ok(data) => text_response(data, "text/csv; charset=utf-8", &self.base),
+            Err(e) => serialization_failure_response("CsvResponse", std::any::type_name::<T>(), &e),
+        }
+    }
+}
+
+#[cfg(feature = "xml")]
+impl<T: Serialize> IntoResponse for XmlResponse<T> {
+    fn into_response(self) -> Response {
+        match quick_xml::se::to_string(&self.data) {
+            Ok(document) => text_response(
+                format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>{document}"),
+                "application/xml; charset=utf-8",
+                &self.base,
+            ),
+            Err(e) => serialization_failure_response("XmlResponse", std::any::type_name::<T>(), &e),
+        }
+    }
+}
+
+/// Rows as CSV, one CRLF-terminated record per row. Structs get a header
+/// record from their field names; tuples and sequences are written as is.
+/// Fields holding a comma, quote or line break are quoted.
+///
+/// ```ignore
+/// #[derive(Serialize)]
+/// struct Row<'a> { id: u64, customer: &'a str }
+///
+/// csv(orders.iter().map(|o| Row { id: o.id, customer: &o.customer }))
+///     .with_header("content-disposition", "attachment; filename=\"orders.csv\"")
+/// ```
+#[cfg(feature = "csv")]
+pub fn csv<T: Serialize>(rows: impl IntoIterator<Item = T>) -> CsvResponse<T> {
+    CsvResponse {
+        rows: rows.into_iter().collect(),
+        base: BaseResponse::default(),
+    }
+}
+
+/// `data` serialized as an XML document. The root element is named after
+/// the type; rename it with `#[serde(rename = "feed")]`.
+///
+/// ```ignore
+/// #[derive(Serialize)]
+/// #[serde(rename = "feed")]
+/// struct Feed { title: String, entry: Vec<Entry> }
+///
+/// xml(Feed { title: "Orders".into(), entry })
+/// ```
+#[cfg(feature = "xml")]
+pub fn xml<T: Serialize>(data: T) -> XmlResponse<T> {
+    XmlResponse {
+        data,
+        base: BaseResponse::default(),
+    }
+}
+
+/// A file sent as an attachment, built by [`download`] or
+/// [`download_stream`].
+///
+/// A streamed file can end with trailer fields computed once the last chunk
+/// is out — a row count, a checksum:
+///
+/// ```ignore
+/// let rows = Arc::new(AtomicUsize::new(0));
+/// let counted = rows.clone();
+/// download_stream(csv_rows(counted), "orders.csv").with_trailers(&req, &["x-row-count"], move || {
+///     let mut trailers = HeaderMap::new();
+///     trailers.insert("x-row-count", rows.load(Ordering::Relaxed).into());
+///     trailers
+/// })
+/// ```
+pub struct DownloadResponse {
+    pub body: axum::body::Body,
+    pub filename: String,
+    /// Guessed from the filename's extension unless set.
+    pub content_type: Option<String>,
+    pub base: BaseResponse,
+}
+
+impl DownloadResponse {
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Declares `names` in a `Trailer` header and, after the body's last
+    /// chunk, sends the fields `trailers` returns; only those named are
+    /// delivered. Clients that did not send `TE: trailers` get the body
+    /// alone and `trailers` is never called.
+    pub fn with_trailers<F>(mut self, request: &SilcrowRequest, names: &[&str], trailers: F) -> Self
+    where
+        F: FnOnce() -> HeaderMap + Send + 'static,
+    {
+        if !request.accepts_trailers || names.is_empty() {
+            return self;
+        }
+        if let Ok(value) = HeaderValue::from_str(&names.join(", ")) {
+            self.base.headers.insert(axum::http::header::TRAILER, value);
+        }
+        let body = std::mem::take(&mut self.body);
+        self.body = axum::body::Body::new(body.with_trailers(async move { Some(Ok(trailers())) }));
+        self
+    }
+}
+
+/// `attachment` with an ASCII `filename` for old clients and the exact name
+/// in RFC 5987 `filename*`.
+fn content_disposition(filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect();
+    format!(
+        "attachment; filename=\"{fallback}\"; filename*=UTF-8''{}",
+        urlencoding::encode(filename)
+    )
+}
+
+fn content_type_for(filename: &str) -> &'static str {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "csv" => "text/csv; charset=utf-8",
+        "txt" => "text/plain; charset=utf-8",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+impl IntoResponse for DownloadResponse {
+    fn into_response(self) -> Response {
+        let content_type = self
+            .content_type
+            .unwrap_or_else(|| content_type_for(&self.filename).to_owned());
+        let mut response = Response::new(self.body);
+        let headers = response.headers_mut();
+        if let Ok(value) = HeaderValue::from_str(&content_type) {
+            headers.insert(axum::http::header::CONTENT_TYPE, value);
+        }
+        if let Ok(value) = HeaderValue::from_str(&content_disposition(&self.filename)) {
+            headers.insert(axum::http::header::CONTENT_DISPOSITION, value);
+        }
+        self.base.apply_to_response(&mut response);
+        response
+    }
+}
+
+/// `body` — bytes, a `Vec<u8>`, a `String` — as a file named `filename`.
+///
+/// ```ignore
+/// download(report.to_pdf(), "report.pdf").with_toast("Report ready", ToastLevel::Success)
+/// ```
+pub fn download(
+    body: impl Into<axum::body::Body>,
+    filename: impl Into<String>,
+) -> DownloadResponse {
+    DownloadResponse {
+        body: body.into(),
+        filename: filename.into(),
+        content_type: None,
+        base: BaseResponse::default(),
+    }
+}
+
+/// Like [`download`], streaming the file as `stream` produces it.
+pub fn download_stream<S, B, E>(stream: S, filename: impl Into<String>) -> DownloadResponse
+where
+    S: futures_core::Stream<Item = Result<B, E>> + Send + 'static,
+    B: Into<axum::body::Bytes> + 'static,
+    E: Into<axum::BoxError> + 'static,
+{
+    download(axum::body::Body::from_stream(stream), filename)
+}
+
+/// RFC 6902 operations as the whole body, sent as
+/// `application/json-patch+json`. silcrow.js applies them to the data last
+/// patched into the request's target.
+pub struct JsonPatchResponse {
+    pub ops: Vec<JsonPatchOp>,
+    pub base: BaseResponse,
+}
+
+impl IntoResponse for JsonPatchResponse {
+    fn into_response(self) -> Response {
+        match serde_json::to_string(&self.ops) {
+            Ok(body) => text_response(body, "application/json-patch+json", &self.base),
+            Err(e) => serialization_failure_response("JsonPatchResponse", "JsonPatchResponse", &e),
+        }
+    }
+}
+
+/// Sends `ops` instead of the whole object, for large bound datasets where
+/// only a few fields changed.
+///
+/// ```ignore
+/// json_patch([JsonPatchOp::replace("/rows/3/status", "shipped")])
+/// ```
+pub fn json_patch(ops: impl IntoIterator<Item = JsonPatchOp>) -> JsonPatchResponse {
+    JsonPatchResponse {
+        ops: ops.into_iter().collect(),
+        base: BaseResponse::default(),
+    }
+}
+
+/// A response with no body, only a status and whatever `ResponseExt`
+/// modifiers add — patches, invalidations, toasts. silcrow.js applies those
+/// and leaves the target as it was.
+pub struct EmptyResponse {
+    pub base: BaseResponse,
+}
+
+impl IntoResponse for EmptyResponse {
+    fn into_response(self) -> Response {
+        let mut response = Response::default();
+        self.base.apply_to_response(&mut response);
+        response
+    }
+}
+
+fn empty(status: StatusCode) -> EmptyResponse {
+    EmptyResponse {
+        base: BaseResponse {
+            status: Some(status),
+            ..BaseResponse::default()
+        },
+    }
+}
+
+/// `204 No Content`, e.g. for a DELETE that only invalidates a list.
+pub fn no_content() -> EmptyResponse {
+    empty(StatusCode::NO_CONTENT)
+}
+
+/// `304 Not Modified` carrying `etag`, for a conditional GET whose
+/// `If-None-Match` is still current:
+///
+/// ```ignore
+/// if req.fresh(&etag) {
+///     return not_modified(&etag).into_response();
+/// }
+/// ```
+pub fn not_modified(etag: &str) -> EmptyResponse {
+    empty(StatusCode::NOT_MODIFIED).with_etag(etag)
+}
+
+/// `202 Accepted`, for work that finishes later.
+pub fn accepted() -> EmptyResponse {
+    empty(StatusCode::ACCEPTED)
+}
+
+/// A response built elsewhere — another crate's responder, a `Redirect`, a
+/// `(StatusCode, Json(..))` tuple — with Pilcrow modifiers chained on.
+/// Made with [`IntoPilcrow::pilcrow`]; toasts go by cookie or header since
+/// the body is left alone.
+pub struct AdaptedResponse {
+    pub response: Response,
+    pub base: BaseResponse,
+}
+
+impl IntoResponse for AdaptedResponse {
+    fn into_response(self) -> Response {
+        let mut response = self.response;
+        self.base.apply_to_response(&mut response);
+        response
+    }
+}
+
+/// Adapts any responder so `ResponseExt` modifiers can be chained on it:
+///
+/// ```ignore
+/// Redirect::to("/login").pilcrow().with_toast("Please sign in", ToastLevel::Info)
+/// ```
+pub trait IntoPilcrow {
+    fn pilcrow(self) -> AdaptedResponse;
+}
+
+impl<R: IntoResponse> IntoPilcrow for R {
+    fn pilcrow(self) -> AdaptedResponse {
+        AdaptedResponse {
+            response: self.into_response(),
+            base: BaseResponse::default(),
+        }
+    }
+}
+
+pub fn html(data: impl Into<Cow<'static, str>>) -> HtmlResponse {
     HtmlResponse {
         data: data.into(),
         base: BaseResponse::default(),
     }
 }
+/// Markup meant for one element: the client swaps it into `target`
+/// regardless of which element made the request. Chain `.swap(..)` to
+/// append or replace instead.
+///
+/// ```ignore
+/// fragment(render_sidebar(&user), "#sidebar")
+/// ```
+pub fn fragment(markup: impl Into<Cow<'static, str>>, target: &str) -> HtmlResponse {
+    html(markup).retarget(target).swap(SwapMode::InnerHtml)
+}
 pub fn status(code: StatusCode) -> Response {
     code.into_response()
 }
@@ -276,6 +1193,38 @@ impl<T> ResponseExt for JsonResponse<T> {
         &mut self.base
     }
 }
+#[cfg(feature = "csv")]
+impl<T> ResponseExt for CsvResponse<T> {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+#[cfg(feature = "xml")]
+impl<T> ResponseExt for XmlResponse<T> {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+impl ResponseExt for DownloadResponse {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+impl ResponseExt for JsonPatchResponse {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+impl ResponseExt for EmptyResponse {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+impl ResponseExt for AdaptedResponse {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
 impl ResponseExt for NavigateResponse {
     fn base_mut(&mut self) -> &mut BaseResponse {
         &mut self.base
@@ -0,0 +1,61 @@
+// ./src/response/templates.rs
+//
+// Template engine integrations, each behind its own feature, so handlers
+// hand the engine's output straight to an `HtmlResponse`.
+
+use crate::response::PilcrowError;
+use crate::response::response::{HtmlResponse, html};
+
+/// ```ignore
+/// async fn profile() -> HtmlResponse {
+///     maud::html! { h1 { "Profile" } }.into()
+/// }
+/// ```
+#[cfg(feature = "maud")]
+impl From<maud::Markup> for HtmlResponse {
+    fn from(markup: maud::Markup) -> Self {
+        html(markup.into_string())
+    }
+}
+
+impl HtmlResponse {
+    /// Renders an Askama template. A failing render is logged and becomes
+    /// a 500.
+    ///
+    /// ```ignore
+    /// #[derive(askama::Template)]
+    /// #[template(path = "order.html")]
+    /// struct OrderPage { order: Order }
+    ///
+    /// async fn show(Path(id): Path<u64>) -> Result<HtmlResponse, PilcrowError> {
+    ///     HtmlResponse::askama(&OrderPage { order: find(id)? })
+    /// }
+    /// ```
+    #[cfg(feature = "askama")]
+    pub fn askama(template: &impl askama::Template) -> Result<Self, PilcrowError> {
+        template.render().map(html).map_err(|error| {
+            tracing::error!(%error, "askama template failed to render");
+            PilcrowError::internal("Template rendering failed")
+        })
+    }
+
+    /// Renders a MiniJinja template with `context`. A failing render is
+    /// logged and becomes a 500.
+    ///
+    /// ```ignore
+    /// async fn show(State(env): State<Environment<'static>>) -> Result<HtmlResponse, PilcrowError> {
+    ///     let template = env.get_template("order.html")?;
+    ///     HtmlResponse::minijinja(&template, context! { order => find(id)? })
+    /// }
+    /// ```
+    #[cfg(feature = "minijinja")]
+    pub fn minijinja(
+        template: &minijinja::Template<'_, '_>,
+        context: impl serde::Serialize,
+    ) -> Result<Self, PilcrowError> {
+        template.render(context).map(html).map_err(|error| {
+            tracing::error!(%error, "minijinja template failed to render");
+            PilcrowError::internal("Template rendering failed")
+        })
+    }
+}
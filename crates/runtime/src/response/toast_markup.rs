@@ -0,0 +1,166 @@
+// ./src/response/toast_markup.rs
+//
+// The markup a rich toast may carry: a few inline tags, checked on the server
+// so the client can insert it without trusting whatever users typed into it.
+
+const INLINE_TAGS: &[&str] = &["a", "b", "code", "em", "i", "strong", "u"];
+
+/// `markup` reduced to bold, italic, underline, code, links and `<br>`.
+/// Attributes are dropped except an `href` on `<a>`, and only `http`,
+/// `https`, `mailto` and site-relative links keep theirs. Any other tag is
+/// shown as text; tags left open are closed at the end.
+///
+/// Character references already in `markup` are kept, so sanitizing its own
+/// output changes nothing: markup read back from a client can be passed
+/// through again.
+pub(crate) fn sanitize(markup: &str) -> String {
+    let mut out = String::with_capacity(markup.len());
+    let mut open: Vec<&'static str> = Vec::new();
+    let mut rest = markup;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&escape_text(&rest[..start]));
+        let tail = &rest[start..];
+        let Some(end) = tail.find('>') else {
+            rest = tail;
+            break;
+        };
+        if !push_tag(&tail[1..end], &mut open, &mut out) {
+            out.push_str(&escape_text(&tail[..=end]));
+        }
+        rest = &tail[end + 1..];
+    }
+    out.push_str(&escape_text(rest));
+    while let Some(tag) = open.pop() {
+        out.push_str(&format!("</{tag}>"));
+    }
+    out
+}
+
+/// `markup` with every tag removed, for handlers that only show text.
+pub(crate) fn plain_text(markup: &str) -> String {
+    let mut text = String::with_capacity(markup.len());
+    let mut rest = markup;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Writes the allowed form of `tag` (the text between `<` and `>`), or
+/// returns false to have it shown as text. Stray closing tags are dropped.
+fn push_tag(tag: &str, open: &mut Vec<&'static str>, out: &mut String) -> bool {
+    if let Some(closing) = tag.strip_prefix('/') {
+        let Some(name) = allowed(closing.trim()) else {
+            return false;
+        };
+        if let Some(depth) = open.iter().rposition(|&tag| tag == name) {
+            for tag in open.drain(depth..).rev() {
+                out.push_str(&format!("</{tag}>"));
+            }
+        }
+        return true;
+    }
+
+    let name_end = tag
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(tag.len());
+    let (name, attributes) = tag.split_at(name_end);
+    if name.eq_ignore_ascii_case("br") {
+        out.push_str("<br>");
+        return true;
+    }
+    let Some(name) = allowed(name) else {
+        return false;
+    };
+    match href(attributes).filter(|href| is_safe_href(href)) {
+        Some(href) if name == "a" => out.push_str(&format!(
+            "<a href=\"{}\" rel=\"noopener noreferrer\">",
+            escape_text(&href)
+        )),
+        _ => out.push_str(&format!("<{name}>")),
+    }
+    open.push(name);
+    true
+}
+
+fn allowed(name: &str) -> Option<&'static str> {
+    INLINE_TAGS
+        .iter()
+        .copied()
+        .find(|tag| tag.eq_ignore_ascii_case(name))
+}
+
+fn href(attributes: &str) -> Option<String> {
+    let mut rest = attributes.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+        let mut value = "";
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (quoted, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let close = after[1..].find(quote)?;
+                    (&after[1..=close], &after[close + 2..])
+                }
+                _ => after.split_at(after.find(char::is_whitespace).unwrap_or(after.len())),
+            };
+            value = quoted;
+            rest = remaining.trim_start();
+        }
+        if name.eq_ignore_ascii_case("href") {
+            return Some(value.to_owned());
+        }
+    }
+    None
+}
+
+fn is_safe_href(href: &str) -> bool {
+    let lower = href.trim().to_ascii_lowercase();
+    ["http://", "https://", "mailto:", "/", "#", "?"]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+}
+
+/// Escapes `<`, `>`, `"` and any `&` that does not start a character
+/// reference (`&amp;`, `&#39;`, `&#x2F;`).
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (index, c) in value.char_indices() {
+        match c {
+            '&' if is_reference(&value[index + 1..]) => escaped.push('&'),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether `rest` (the text after a `&`) is a named or numeric reference.
+fn is_reference(rest: &str) -> bool {
+    let Some(end) = rest.find(';') else {
+        return false;
+    };
+    let name = &rest[..end];
+    if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+        return !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit());
+    }
+    if let Some(decimal) = name.strip_prefix('#') {
+        return !decimal.is_empty() && decimal.bytes().all(|b| b.is_ascii_digit());
+    }
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric())
+}
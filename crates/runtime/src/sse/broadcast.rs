@@ -0,0 +1,800 @@
+// ./src/sse/broadcast.rs
+//
+// Fan-out for SSE: any task publishes a SilcrowEvent, every open subscriber
+// stream receives it. Published events are numbered and the most recent ones
+// kept, so a reconnecting client can catch up from its `Last-Event-ID`.
+// Events may be tagged with a topic; subscribers may ask for only some topics.
+// Each event is rendered to its SSE frame once, at publish, and every
+// subscriber's response shares those bytes — never re-serialized or copied
+// per client.
+// A subscriber that falls a full buffer behind is handled per SseLagPolicy.
+
+use crate::extract::extract::SilcrowRequest;
+use crate::live::{ConnectionLimiter, ConnectionPermit};
+use crate::sse::coalesce::coalesce;
+use crate::sse::{LastEventId, SilcrowEvent};
+use axum::body::{Body, Bytes};
+use axum::http::header;
+use axum::http::request::Parts;
+use axum::response::sse::{Event, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_core::Stream;
+use futures_util::FutureExt;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::{Notify, broadcast, watch};
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+
+/// Events a subscriber may fall behind by before it starts missing them.
+const DEFAULT_CAPACITY: usize = 64;
+
+/// Targets `SseReplay::LatestPerTarget` remembers before it forgets the one
+/// updated longest ago.
+const MAX_REPLAY_TARGETS: usize = 1024;
+
+/// Idle time before a keep-alive comment is sent, as with axum's `KeepAlive`.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+#[derive(Debug)]
+struct Published {
+    id: u64,
+    topic: Option<Arc<str>>,
+    event: SilcrowEvent,
+    /// `event` already rendered for the wire.
+    frame: Bytes,
+}
+
+/// The wire bytes of one SSE frame. axum only renders an `Event` inside an
+/// `Sse` body, so run it through one; a one-item body is ready at once.
+fn render(event: Event) -> Bytes {
+    let body = Sse::new(tokio_stream::iter([Ok::<_, Infallible>(event)]))
+        .into_response()
+        .into_body();
+    axum::body::to_bytes(body, usize::MAX)
+        .now_or_never()
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+/// Sends a keep-alive comment whenever `frames` has been idle for [`KEEP_ALIVE`].
+fn keep_alive<S>(frames: S) -> impl Stream<Item = Bytes> + Send
+where
+    S: Stream<Item = Bytes> + Send + 'static,
+{
+    futures_util::stream::unfold(Box::pin(frames), |mut frames| async move {
+        match tokio::time::timeout(KEEP_ALIVE, frames.next()).await {
+            Ok(frame) => Some((frame?, frames)),
+            Err(_) => Some((Bytes::from_static(b":\n\n"), frames)),
+        }
+    })
+}
+
+/// Holds `permit` while `events` runs; once a newer connection from the same
+/// identity takes its slot, sends a close event and ends.
+fn close_when_superseded<S>(
+    events: S,
+    permit: ConnectionPermit,
+) -> impl Stream<Item = Arc<Published>> + Send
+where
+    S: Stream<Item = Arc<Published>> + Send + 'static,
+{
+    futures_util::stream::unfold(Some((Box::pin(events), permit)), |state| async move {
+        let (mut events, mut permit) = state?;
+        tokio::select! {
+            published = events.next() => Some((published?, Some((events, permit)))),
+            _ = permit.superseded() => {
+                let event = SilcrowEvent::close();
+                let close = Published {
+                    id: 0,
+                    topic: None,
+                    frame: render(Event::from(event.clone())),
+                    event,
+                };
+                Some((Arc::new(close), None))
+            }
+        }
+    })
+}
+
+/// Topics a subscriber asked for; `None` takes everything.
+type TopicFilter = Option<Arc<HashSet<String>>>;
+
+fn topic_filter(topics: &[&str]) -> TopicFilter {
+    Some(Arc::new(
+        topics.iter().map(|&topic| topic.to_owned()).collect(),
+    ))
+}
+
+fn patch_target(published: &Arc<Published>) -> Option<&str> {
+    published.event.patch_target()
+}
+
+fn wants(filter: &TopicFilter, published: &Published) -> bool {
+    match (filter, &published.topic) {
+        (Some(topics), Some(topic)) => topics.contains(&**topic),
+        _ => true,
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    /// Id of the most recently published event; 0 before the first.
+    last_id: u64,
+    recent: VecDeque<Arc<Published>>,
+    capacity: usize,
+    /// Filtered subscribers, counted per topic they asked for.
+    topics: HashMap<String, usize>,
+    filtered: usize,
+    subscribers: usize,
+    next_subscriber: u64,
+    replay: Option<SseReplay>,
+    /// Newest event per (topic, target), kept for `SseReplay::LatestPerTarget`.
+    latest: HashMap<TargetKey, Arc<Published>>,
+    /// The keys of `latest` by the id of their event, oldest first.
+    latest_by_id: BTreeSet<(u64, TargetKey)>,
+}
+
+type TargetKey = (Option<Arc<str>>, String);
+
+impl State {
+    /// Buffered events the client has not seen. An id that is unparseable,
+    /// from before the buffer, or from a previous server run gets everything kept.
+    fn missed_since(&self, last_event_id: &str, filter: &TopicFilter) -> Vec<Arc<Published>> {
+        let oldest = self.recent.front().map_or(0, |published| published.id);
+        let since = match last_event_id.parse::<u64>() {
+            Ok(seen) if seen <= self.last_id && seen + 1 >= oldest => seen,
+            _ => 0,
+        };
+        self.recent
+            .iter()
+            .filter(|published| published.id > since && wants(filter, published))
+            .cloned()
+            .collect()
+    }
+
+    /// What a subscriber connecting without a `Last-Event-ID` is shown first.
+    fn replay_for(&self, filter: &TopicFilter) -> Vec<Arc<Published>> {
+        match self.replay {
+            None => Vec::new(),
+            Some(SseReplay::Last(n)) => {
+                let mut replay: Vec<_> = self
+                    .recent
+                    .iter()
+                    .rev()
+                    .filter(|published| wants(filter, published))
+                    .take(n)
+                    .cloned()
+                    .collect();
+                replay.reverse();
+                replay
+            }
+            Some(SseReplay::LatestPerTarget) => {
+                let mut replay: Vec<_> = self
+                    .latest
+                    .values()
+                    .filter(|published| wants(filter, published))
+                    .cloned()
+                    .collect();
+                // One event may be the newest for several targets.
+                replay.sort_unstable_by_key(|published| published.id);
+                replay.dedup_by_key(|published| published.id);
+                replay
+            }
+        }
+    }
+
+    fn remember(&mut self, published: &Arc<Published>) {
+        if self.recent.len() == self.capacity {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(published.clone());
+        if self.replay == Some(SseReplay::LatestPerTarget) {
+            for target in published.event.targets() {
+                let key = (published.topic.clone(), target.to_owned());
+                if let Some(previous) = self.latest.insert(key.clone(), published.clone()) {
+                    self.latest_by_id.remove(&(previous.id, key.clone()));
+                }
+                self.latest_by_id.insert((published.id, key));
+            }
+            while self.latest.len() > MAX_REPLAY_TARGETS {
+                let Some((_, key)) = self.latest_by_id.pop_first() else {
+                    break;
+                };
+                self.latest.remove(&key);
+            }
+        }
+    }
+
+    /// Subscribers an event tagged `topic` reaches, out of `open` in total.
+    fn audience(&self, topic: &str, open: usize) -> usize {
+        open.saturating_sub(self.filtered) + self.topics.get(topic).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    tx: broadcast::Sender<Arc<Published>>,
+    /// Signalled whenever a subscriber takes an event or goes away, for
+    /// publishers waiting under `SseLagPolicy::Block`.
+    room: Notify,
+    /// Open subscriber count, updated under the state lock.
+    subscribers: watch::Sender<usize>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Whether the slowest subscriber has a full buffer of events unread.
+    fn is_full(&self, state: &State) -> bool {
+        self.tx.len() >= state.capacity
+    }
+}
+
+/// What happens to a subscriber that falls a full buffer of events behind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SseLagPolicy {
+    /// The subscriber skips the oldest events it has not read and carries on.
+    #[default]
+    DropOldest,
+    /// The subscriber's stream ends. Its browser reconnects with its
+    /// `Last-Event-ID` and catches up from the resume buffer.
+    Disconnect,
+    /// [`SseBroadcaster::publish_wait`] and [`SseBroadcaster::publish_to_wait`]
+    /// wait until every subscriber has room. One stalled client stalls those
+    /// publishers; `publish` and `publish_to` cannot wait and drop the oldest.
+    Block,
+}
+
+/// How many events were skipped for a subscriber that fell behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lagged(pub u64);
+
+/// What [`SseBroadcaster::with_replay`] shows a new subscriber before live events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SseReplay {
+    /// The last `n` published events, up to the broadcaster's capacity.
+    Last(usize),
+    /// The newest event for each DOM target (per topic), so the page starts
+    /// from current values. Events without a target are not replayed. Up to
+    /// 1024 targets are remembered; past that, the one updated longest ago
+    /// is forgotten.
+    LatestPerTarget,
+}
+
+/// A subscriber as seen by [`SseBroadcaster::on_connect`] and
+/// [`SseBroadcaster::on_disconnect`] hooks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseConnection {
+    /// Unique per subscriber for the broadcaster's lifetime.
+    pub id: u64,
+    /// Topics it subscribed to, sorted; empty for an unfiltered subscriber.
+    pub topics: Vec<String>,
+    /// Subscribers open once this one has connected or disconnected.
+    pub subscribers: usize,
+}
+
+type Hook = Arc<dyn Fn(&SseConnection) + Send + Sync>;
+
+type LagHook = Arc<dyn Fn(&SseConnection, Lagged) + Send + Sync>;
+
+/// Builds each heartbeat event from the app's current status.
+type HeartbeatFn = Arc<dyn Fn() -> SilcrowEvent + Send + Sync>;
+
+#[derive(Clone, Default)]
+struct Hooks {
+    on_connect: Option<Hook>,
+    on_disconnect: Option<Hook>,
+    on_lag: Option<LagHook>,
+}
+
+/// Keeps a subscriber counted, under its topics if it has any, until its
+/// stream is dropped.
+struct Registration {
+    shared: Arc<Shared>,
+    connection: SseConnection,
+    filter: TopicFilter,
+    on_disconnect: Option<Hook>,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        {
+            let mut state = self.shared.lock();
+            state.subscribers -= 1;
+            self.shared.subscribers.send_replace(state.subscribers);
+            if let Some(topics) = &self.filter {
+                state.filtered -= 1;
+                for topic in topics.iter() {
+                    if let Some(count) = state.topics.get_mut(topic) {
+                        *count -= 1;
+                        if *count == 0 {
+                            state.topics.remove(topic);
+                        }
+                    }
+                }
+            }
+            self.connection.subscribers = state.subscribers;
+        }
+        // The receiver is dropped before this guard, so a blocked publisher
+        // woken here already sees the room it left.
+        self.shared.room.notify_waiters();
+        // Outside the lock, so hooks may publish or subscribe.
+        if let Some(hook) = &self.on_disconnect {
+            hook(&self.connection);
+        }
+    }
+}
+
+/// Publishes `SilcrowEvent`s to every connected SSE client.
+///
+/// Cheap to clone; keep one in app state and publish from handlers or
+/// background tasks. Each published event gets the next id (`1`, `2`, …),
+/// replacing any id set with [`SilcrowEvent::with_id`].
+///
+/// Events published with [`SseBroadcaster::publish_to`] carry a topic and only
+/// reach subscribers that asked for it — or that asked for no topics at all.
+///
+/// ```ignore
+/// async fn feed(State(app): State<App>, last: LastEventId) -> impl IntoResponse {
+///     app.broadcaster.handler_from(last)
+/// }
+///
+/// async fn my_orders(State(app): State<App>, user: User, last: LastEventId) -> impl IntoResponse {
+///     app.broadcaster.handler_filtered(&[&format!("orders:{}", user.id)], last)
+/// }
+///
+/// app.broadcaster.publish(SilcrowEvent::patch(&stats, "#stats"));
+/// app.broadcaster.publish_to("orders:42", SilcrowEvent::patch(&order, "#order"));
+/// ```
+#[derive(Clone)]
+pub struct SseBroadcaster {
+    shared: Arc<Shared>,
+    retry: Option<Duration>,
+    heartbeat: Option<(Duration, HeartbeatFn)>,
+    lag_policy: SseLagPolicy,
+    coalesce: Option<Duration>,
+    hooks: Hooks,
+    limiter: Option<ConnectionLimiter>,
+}
+
+impl SseBroadcaster {
+    /// A broadcaster whose subscribers may lag `capacity` events behind
+    /// before the oldest are skipped for them. The last `capacity` events are
+    /// also kept for clients resuming with a `Last-Event-ID`.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (tx, _) = broadcast::channel(capacity);
+        let state = State {
+            last_id: 0,
+            recent: VecDeque::with_capacity(capacity),
+            capacity,
+            topics: HashMap::new(),
+            filtered: 0,
+            subscribers: 0,
+            next_subscriber: 0,
+            replay: None,
+            latest: HashMap::new(),
+            latest_by_id: BTreeSet::new(),
+        };
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(state),
+                tx,
+                room: Notify::new(),
+                subscribers: watch::Sender::new(0),
+            }),
+            retry: None,
+            heartbeat: None,
+            lag_policy: SseLagPolicy::default(),
+            coalesce: None,
+            hooks: Hooks::default(),
+            limiter: None,
+        }
+    }
+
+    /// Tells every subscriber's browser, when its stream opens, to wait
+    /// `after` before reconnecting. Events carrying their own
+    /// [`SilcrowEvent::with_retry`] override it.
+    pub fn with_retry(mut self, after: Duration) -> Self {
+        self.retry = Some(after);
+        self
+    }
+
+    /// Shows each new subscriber recent events as soon as it connects, so a
+    /// freshly loaded page starts from current values instead of waiting for
+    /// the next publish. Clients resuming with a `Last-Event-ID` get what
+    /// they missed instead.
+    pub fn with_replay(self, replay: SseReplay) -> Self {
+        self.shared.lock().replay = Some(replay);
+        self
+    }
+
+    /// Sends every subscriber a [`SilcrowEvent::heartbeat`] each `period`,
+    /// carrying whatever `status` returns at the time. Heartbeats are not
+    /// numbered or buffered for resume. A zero `period` turns them off.
+    pub fn with_heartbeat<F, T>(mut self, period: Duration, status: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: serde::Serialize,
+    {
+        let beat: HeartbeatFn = Arc::new(move || SilcrowEvent::heartbeat(status()));
+        self.heartbeat = (!period.is_zero()).then_some((period, beat));
+        self
+    }
+
+    /// Sets what happens to subscribers that fall `capacity` events behind.
+    pub fn with_lag_policy(mut self, policy: SseLagPolicy) -> Self {
+        self.lag_policy = policy;
+        self
+    }
+
+    /// Collapses, per subscriber, patches to the same target published within
+    /// `window` of the first into the latest. Protects clients from chatty
+    /// producers such as tick-level metrics; other events are delayed by up
+    /// to `window` but never dropped.
+    pub fn with_coalescing(mut self, window: Duration) -> Self {
+        self.coalesce = Some(window);
+        self
+    }
+
+    /// Runs `hook` each time a subscriber falls behind and events are
+    /// skipped for it, before the lag policy disconnects it if it does.
+    ///
+    /// Hooks run on the subscriber's task and should return quickly.
+    pub fn on_lag<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SseConnection, Lagged) + Send + Sync + 'static,
+    {
+        self.hooks.on_lag = Some(Arc::new(hook));
+        self
+    }
+
+    /// Runs `hook` each time a subscriber stream opens, e.g. to start
+    /// producing data once someone is listening.
+    ///
+    /// Hooks run on the subscribing task and should return quickly.
+    pub fn on_connect<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SseConnection) + Send + Sync + 'static,
+    {
+        self.hooks.on_connect = Some(Arc::new(hook));
+        self
+    }
+
+    /// Runs `hook` each time a subscriber stream is dropped — the client
+    /// disconnected or the response was discarded. When
+    /// [`SseConnection::subscribers`] reaches 0, nobody is listening.
+    pub fn on_disconnect<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SseConnection) + Send + Sync + 'static,
+    {
+        self.hooks.on_disconnect = Some(Arc::new(hook));
+        self
+    }
+
+    /// How many subscriber streams are open right now.
+    /// Caps subscribers per identity for [`SseBroadcaster::handler_for`]: a
+    /// subscribe over the limit is refused with a 429, or, under
+    /// [`LimitPolicy::SupersedeOldest`](crate::live::LimitPolicy::SupersedeOldest),
+    /// the identity's oldest stream is sent a `close` event and ended.
+    pub fn with_limiter(mut self, limiter: ConnectionLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        *self.shared.subscribers.borrow()
+    }
+
+    /// Whether anyone is subscribed.
+    pub fn is_active(&self) -> bool {
+        self.subscriber_count() > 0
+    }
+
+    /// The subscriber count, updated as streams open and close — lets a
+    /// producer pause expensive upstream work while nobody is listening:
+    ///
+    /// ```ignore
+    /// let mut listeners = broadcaster.watch_subscribers();
+    /// loop {
+    ///     listeners.wait_for(|&n| n > 0).await?;
+    ///     broadcaster.publish(SilcrowEvent::patch(&db.stats().await?, "#stats"));
+    ///     tokio::time::sleep(Duration::from_secs(1)).await;
+    /// }
+    /// ```
+    pub fn watch_subscribers(&self) -> watch::Receiver<usize> {
+        self.shared.subscribers.subscribe()
+    }
+
+    /// Sends `event` to every current subscriber. Returns how many there were.
+    ///
+    /// Events whose payload failed to serialize are dropped with a warning.
+    pub fn publish(&self, event: impl Into<SilcrowEvent>) -> usize {
+        self.send(None, event.into())
+    }
+
+    /// Sends `event` to the subscribers of `topic` and to unfiltered
+    /// subscribers. Returns how many that was.
+    pub fn publish_to(&self, topic: &str, event: impl Into<SilcrowEvent>) -> usize {
+        self.send(Some(topic), event.into())
+    }
+
+    /// Like [`SseBroadcaster::publish`]; under [`SseLagPolicy::Block`] first
+    /// waits until no subscriber is a full buffer behind.
+    pub async fn publish_wait(&self, event: impl Into<SilcrowEvent>) -> usize {
+        self.send_wait(None, event.into()).await
+    }
+
+    /// Like [`SseBroadcaster::publish_to`]; under [`SseLagPolicy::Block`]
+    /// first waits until no subscriber is a full buffer behind.
+    pub async fn publish_to_wait(&self, topic: &str, event: impl Into<SilcrowEvent>) -> usize {
+        self.send_wait(Some(topic), event.into()).await
+    }
+
+    async fn send_wait(&self, topic: Option<&str>, event: SilcrowEvent) -> usize {
+        if self.lag_policy != SseLagPolicy::Block {
+            return self.send(topic, event);
+        }
+        loop {
+            let room = self.shared.room.notified();
+            tokio::pin!(room);
+            // Registered before checking, so a subscriber reading in between
+            // still wakes us.
+            room.as_mut().enable();
+            {
+                let state = self.shared.lock();
+                if !self.shared.is_full(&state) {
+                    return self.send_locked(state, topic, event);
+                }
+            }
+            room.await;
+        }
+    }
+
+    fn send(&self, topic: Option<&str>, event: SilcrowEvent) -> usize {
+        let state = self.shared.lock();
+        self.send_locked(state, topic, event)
+    }
+
+    fn send_locked(
+        &self,
+        mut state: MutexGuard<'_, State>,
+        topic: Option<&str>,
+        event: SilcrowEvent,
+    ) -> usize {
+        if let Err(e) = event.serialize_check() {
+            tracing::warn!("SseBroadcaster::publish dropped an event — serialization failed: {e}");
+            return 0;
+        }
+        // Numbering, buffering and sending under one lock keeps ids in send
+        // order and lets `subscribe_from` replay without gaps or duplicates.
+        state.last_id += 1;
+        let id = state.last_id;
+        let event = event.with_id(id.to_string());
+        let published = Arc::new(Published {
+            id,
+            topic: topic.map(Arc::from),
+            frame: render(Event::from(event.clone())),
+            event,
+        });
+        // A close is for whoever is connected now, never replayed later.
+        if !published.event.is_close() {
+            state.remember(&published);
+        }
+        let open = self.shared.tx.send(published).unwrap_or(0);
+        match topic {
+            Some(topic) => state.audience(topic, open),
+            None => open,
+        }
+    }
+
+    /// Sends [`SilcrowEvent::close`] to every subscriber and ends their
+    /// streams, so browsers stop reconnecting. Returns how many there were.
+    ///
+    /// The broadcaster stays usable; clients subscribing afterwards are served
+    /// as usual.
+    pub fn close_all(&self) -> usize {
+        self.publish(SilcrowEvent::close())
+    }
+
+    /// Events published from now on. A subscriber that falls too far behind
+    /// skips the events it missed and carries on.
+    pub fn subscribe(&self) -> impl Stream<Item = SilcrowEvent> + Send + use<> {
+        self.subscribe_from(None)
+    }
+
+    /// Like [`SseBroadcaster::subscribe`], first replaying the buffered events
+    /// published after `last_event_id`.
+    pub fn subscribe_from(
+        &self,
+        last_event_id: Option<&str>,
+    ) -> impl Stream<Item = SilcrowEvent> + Send + use<> {
+        self.published(None, last_event_id)
+            .map(|published| published.event.clone())
+    }
+
+    /// Like [`SseBroadcaster::subscribe`], skipping events published to
+    /// topics other than `topics`. Untagged events are still received.
+    pub fn subscribe_filtered(
+        &self,
+        topics: &[&str],
+    ) -> impl Stream<Item = SilcrowEvent> + Send + use<> {
+        self.published(topic_filter(topics), None)
+            .map(|published| published.event.clone())
+    }
+
+    fn published(
+        &self,
+        filter: TopicFilter,
+        last_event_id: Option<&str>,
+    ) -> impl Stream<Item = Arc<Published>> + Send + use<> {
+        let (missed, rx, registration) = {
+            let mut state = self.shared.lock();
+            let missed = match last_event_id {
+                Some(seen) => state.missed_since(seen, &filter),
+                None => state.replay_for(&filter),
+            };
+            let mut topics = Vec::new();
+            if let Some(filter) = &filter {
+                state.filtered += 1;
+                for topic in filter.iter() {
+                    *state.topics.entry(topic.clone()).or_default() += 1;
+                    topics.push(topic.clone());
+                }
+                topics.sort_unstable();
+            }
+            state.subscribers += 1;
+            self.shared.subscribers.send_replace(state.subscribers);
+            state.next_subscriber += 1;
+            let registration = Registration {
+                shared: self.shared.clone(),
+                connection: SseConnection {
+                    id: state.next_subscriber,
+                    topics,
+                    subscribers: state.subscribers,
+                },
+                filter: filter.clone(),
+                on_disconnect: self.hooks.on_disconnect.clone(),
+            };
+            (missed, self.shared.tx.subscribe(), registration)
+        };
+        if let Some(hook) = &self.hooks.on_connect {
+            hook(&registration.connection);
+        }
+        let policy = self.lag_policy;
+        let on_lag = self.hooks.on_lag.clone();
+        let live = BroadcastStream::new(rx).filter_map(move |received| match received {
+            Ok(published) => {
+                if policy == SseLagPolicy::Block {
+                    registration.shared.room.notify_waiters();
+                }
+                wants(&filter, &published).then_some(Ok(published))
+            }
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                tracing::debug!("SSE subscriber lagging — skipped {skipped} events");
+                if let Some(hook) = &on_lag {
+                    hook(&registration.connection, Lagged(skipped));
+                }
+                (policy == SseLagPolicy::Disconnect).then_some(Err(Lagged(skipped)))
+            }
+        });
+        let events = tokio_stream::iter(missed).map(Ok).chain(live);
+        // A close event is the last thing a subscriber receives; a lag the
+        // policy disconnects for ends the stream without one.
+        let events = futures_util::stream::unfold(Some(Box::pin(events)), |events| async move {
+            let mut events = events?;
+            let published = events.next().await?.ok()?;
+            let rest = (!published.event.is_close()).then_some(events);
+            Some((published, rest))
+        });
+        coalesce(events, self.coalesce, patch_target)
+    }
+
+    /// The SSE response for one subscriber, with keep-alive comments.
+    pub fn handler(&self) -> Response {
+        self.handler_from(LastEventId::default())
+    }
+
+    /// Like [`SseBroadcaster::handler`], replaying what a reconnecting client
+    /// missed since the `Last-Event-ID` it sent.
+    pub fn handler_from(&self, last_event_id: LastEventId) -> Response {
+        self.respond(self.published(None, last_event_id.as_deref()))
+    }
+
+    /// Like [`SseBroadcaster::handler_from`], for a subscriber that only wants
+    /// events published to `topics` (plus untagged ones).
+    pub fn handler_filtered(&self, topics: &[&str], last_event_id: LastEventId) -> Response {
+        self.respond(self.published(topic_filter(topics), last_event_id.as_deref()))
+    }
+
+    /// Like [`SseBroadcaster::handler_from`], counting the subscriber against
+    /// the broadcaster's [limiter](SseBroadcaster::with_limiter) for whoever
+    /// sent `request`. Over the limit, it is refused with a negotiated 429.
+    pub fn handler_for(&self, request: &Parts, last_event_id: LastEventId) -> Response {
+        let permit = match self.limiter.as_ref().map(|limiter| limiter.admit(request)) {
+            Some(Ok(permit)) => permit,
+            Some(Err(exceeded)) => {
+                return exceeded.into_negotiated_response(&SilcrowRequest::parse(request));
+            }
+            None => None,
+        };
+        let events = self.published(None, last_event_id.as_deref());
+        match permit {
+            Some(permit) => self.respond(close_when_superseded(events, permit)),
+            None => self.respond(events),
+        }
+    }
+
+    fn respond<S>(&self, events: S) -> Response
+    where
+        S: Stream<Item = Arc<Published>> + Send + 'static,
+    {
+        let retry = self
+            .retry
+            .map(|after| render(Event::default().retry(after)));
+        let beats = self.heartbeat.clone().map(|(period, beat)| {
+            let mut ticks = tokio::time::interval_at(Instant::now() + period, period);
+            ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            (ticks, beat)
+        });
+        let heartbeats = futures_util::stream::unfold(beats, |beats| async move {
+            let (mut ticks, beat) = beats?;
+            ticks.tick().await;
+            Some((render(Event::from(beat())), Some((ticks, beat))))
+        });
+        // Heartbeats never end on their own; the stream ends with `events`.
+        let events = events
+            .map(|published| Some(published.frame.clone()))
+            .chain(tokio_stream::iter([None]));
+        let stream = tokio_stream::iter(retry)
+            .map(Some)
+            .chain(events.merge(heartbeats.map(Some)))
+            .take_while(Option::is_some)
+            .filter_map(|frame| frame);
+        let body = Body::from_stream(keep_alive(stream).map(Ok::<Bytes, Infallible>));
+        (
+            [
+                (header::CONTENT_TYPE, "text/event-stream"),
+                (header::CACHE_CONTROL, "no-cache"),
+            ],
+            body,
+        )
+            .into_response()
+    }
+}
+
+impl Default for SseBroadcaster {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl std::fmt::Debug for SseBroadcaster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SseBroadcaster")
+            .field("subscribers", &self.subscriber_count())
+            .field("last_id", &self.shared.lock().last_id)
+            .field("retry", &self.retry)
+            .field("lag_policy", &self.lag_policy)
+            .field("coalesce", &self.coalesce)
+            .field(
+                "heartbeat",
+                &self.heartbeat.as_ref().map(|(period, _)| period),
+            )
+            .field("on_connect", &self.hooks.on_connect.is_some())
+            .field("on_disconnect", &self.hooks.on_disconnect.is_some())
+            .field("on_lag", &self.hooks.on_lag.is_some())
+            .field("limiter", &self.limiter)
+            .finish()
+    }
+}
@@ -0,0 +1,96 @@
+// ./src/sse/channel.rs
+//
+// A broadcaster pinned to one SSE route and one payload type, so the route
+// constant, what gets published on it and the stream serving it cannot drift.
+
+use crate::sse::{LastEventId, SilcrowEvent, SseBroadcaster, SseRoute};
+use axum::routing::{MethodRouter, get};
+use std::marker::PhantomData;
+
+/// Typed SSE channel for an [`SseRoute`].
+///
+/// Every event published through the channel carries a `T`, and the route
+/// handler it hands out serves exactly that channel's subscribers.
+///
+/// ```ignore
+/// const ORDERS: SseRoute = SseRoute::new("/events/orders");
+///
+/// let orders: SseChannel<Order> = SseChannel::new(ORDERS);
+/// let app = Router::new().route(ORDERS.path(), orders.route_handler());
+///
+/// orders.patch(&order, "#order");
+/// ```
+pub struct SseChannel<T> {
+    route: SseRoute,
+    broadcaster: SseBroadcaster,
+    _payload: PhantomData<fn(&T)>,
+}
+
+impl<T: serde::Serialize> SseChannel<T> {
+    /// A channel for `route` with the default broadcaster.
+    pub fn new(route: SseRoute) -> Self {
+        Self::with_broadcaster(route, SseBroadcaster::default())
+    }
+
+    /// A channel for `route` publishing through `broadcaster`, e.g. one built
+    /// with a larger capacity or a `retry` hint.
+    pub fn with_broadcaster(route: SseRoute, broadcaster: SseBroadcaster) -> Self {
+        Self {
+            route,
+            broadcaster,
+            _payload: PhantomData,
+        }
+    }
+
+    pub fn route(&self) -> SseRoute {
+        self.route
+    }
+
+    pub fn broadcaster(&self) -> &SseBroadcaster {
+        &self.broadcaster
+    }
+
+    /// Publishes `data` to `Silcrow.patch(data, target)` on every subscriber.
+    /// Returns how many subscribers there were.
+    pub fn patch(&self, data: &T, target: &str) -> usize {
+        self.broadcaster.publish(SilcrowEvent::patch(data, target))
+    }
+
+    /// Like [`SseChannel::patch`], only for subscribers of `topic`.
+    pub fn patch_to(&self, topic: &str, data: &T, target: &str) -> usize {
+        self.broadcaster
+            .publish_to(topic, SilcrowEvent::patch(data, target))
+    }
+
+    /// A `GET` handler streaming this channel, resuming from the client's
+    /// `Last-Event-ID`. Mount it at [`SseChannel::route`].
+    pub fn route_handler<S>(&self) -> MethodRouter<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let broadcaster = self.broadcaster.clone();
+        get(
+            move |last_event_id: LastEventId| async move { broadcaster.handler_from(last_event_id) },
+        )
+    }
+}
+
+impl<T> Clone for SseChannel<T> {
+    fn clone(&self) -> Self {
+        Self {
+            route: self.route,
+            broadcaster: self.broadcaster.clone(),
+            _payload: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SseChannel<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SseChannel")
+            .field("route", &self.route.path())
+            .field("payload", &std::any::type_name::<T>())
+            .field("broadcaster", &self.broadcaster)
+            .finish()
+    }
+}
@@ -0,0 +1,72 @@
+// ./src/sse/coalesce.rs
+//
+// Collapses bursts of patches to the same target. A patch opens a short
+// window; whatever else arrives during it is batched, and a later patch to a
+// target already in the batch replaces the earlier one. The batch is then
+// flushed in arrival order, so event ids still increase.
+
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_stream::StreamExt;
+
+struct Coalescer<S, T> {
+    upstream: Pin<Box<S>>,
+    ready: VecDeque<T>,
+    ended: bool,
+}
+
+/// `events`, with patches to the same target inside `window` reduced to the
+/// latest. `key` names what an item patches; items without one are never
+/// dropped. No window passes everything straight through.
+pub(crate) fn coalesce<S, T>(
+    events: S,
+    window: Option<Duration>,
+    key: fn(&T) -> Option<&str>,
+) -> impl Stream<Item = T> + Send + use<S, T>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let state = Coalescer {
+        upstream: Box::pin(events),
+        ready: VecDeque::new(),
+        ended: false,
+    };
+    futures_util::stream::unfold(state, move |mut state| async move {
+        if let Some(item) = state.ready.pop_front() {
+            return Some((item, state));
+        }
+        if state.ended {
+            return None;
+        }
+        let first = state.upstream.next().await?;
+        let Some(window) = window.filter(|_| key(&first).is_some()) else {
+            return Some((first, state));
+        };
+
+        state.ready.push_back(first);
+        let deadline = Instant::now() + window;
+        loop {
+            tokio::select! {
+                next = state.upstream.next() => match next {
+                    Some(item) => {
+                        if let Some(target) = key(&item) {
+                            state.ready.retain(|pending| key(pending) != Some(target));
+                        }
+                        state.ready.push_back(item);
+                    }
+                    None => {
+                        state.ended = true;
+                        break;
+                    }
+                },
+                _ = tokio::time::sleep_until(deadline) => break,
+            }
+        }
+        let item = state.ready.pop_front()?;
+        Some((item, state))
+    })
+}
@@ -0,0 +1,159 @@
+// ./src/sse/compression.rs
+//
+// Streaming gzip/deflate for SSE responses. Every chunk the stream yields is
+// compressed and sync-flushed on its own, so events reach the browser as soon
+// as they are sent instead of sitting in a compressor buffer — which is why a
+// general-purpose compression layer does not suit event streams.
+
+use axum::body::{Body, Bytes};
+use axum::extract::Request;
+use axum::http::{HeaderMap, HeaderValue, header};
+use axum::middleware::Next;
+use axum::response::Response;
+use flate2::Compression;
+use flate2::write::{GzEncoder, ZlibEncoder};
+use std::io::Write;
+use tokio_stream::StreamExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    /// The preferred encoding `Accept-Encoding` allows; gzip wins ties.
+    fn negotiate(headers: &HeaderMap) -> Option<Self> {
+        let accepted: Vec<&str> = headers
+            .get_all(header::ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|item| {
+                let mut parts = item.split(';').map(str::trim);
+                let coding = parts.next()?;
+                let refused = parts.any(|param| {
+                    param
+                        .strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .is_some_and(|q| q <= 0.0)
+                });
+                (!refused).then_some(coding)
+            })
+            .collect();
+        let accepts = |coding: &str| {
+            accepted
+                .iter()
+                .any(|accepted| accepted.eq_ignore_ascii_case(coding))
+        };
+        if accepts("gzip") || accepts("*") {
+            Some(Self::Gzip)
+        } else if accepts("deflate") {
+            Some(Self::Deflate)
+        } else {
+            None
+        }
+    }
+
+    fn header_value(self) -> HeaderValue {
+        HeaderValue::from_static(match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        })
+    }
+}
+
+enum Encoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Deflate(ZlibEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+    fn new(encoding: Encoding) -> Self {
+        match encoding {
+            Encoding::Gzip => Self::Gzip(GzEncoder::new(Vec::new(), Compression::default())),
+            Encoding::Deflate => {
+                Self::Deflate(ZlibEncoder::new(Vec::new(), Compression::default()))
+            }
+        }
+    }
+
+    /// Compresses `chunk` and flushes, returning everything the client needs
+    /// to decode it. The dictionary is kept, so repeated keys across events
+    /// still compress well.
+    fn compress(&mut self, chunk: &[u8]) -> std::io::Result<Bytes> {
+        let output = match self {
+            Self::Gzip(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            Self::Deflate(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+        };
+        Ok(Bytes::from(std::mem::take(output)))
+    }
+
+    fn finish(self) -> std::io::Result<Bytes> {
+        match self {
+            Self::Gzip(encoder) => encoder.finish(),
+            Self::Deflate(encoder) => encoder.finish(),
+        }
+        .map(Bytes::from)
+    }
+}
+
+/// Middleware compressing `text/event-stream` responses with gzip or deflate
+/// when the client's `Accept-Encoding` allows it. Other responses pass through.
+///
+/// ```ignore
+/// let app = Router::new()
+///     .route(FEED.path(), get(feed))
+///     .layer(axum::middleware::from_fn(compress_sse));
+/// ```
+pub async fn compress_sse(request: Request, next: Next) -> Response {
+    let encoding = Encoding::negotiate(request.headers());
+    let response = next.run(request).await;
+    match encoding {
+        Some(encoding) if is_uncompressed_event_stream(&response) => compress(response, encoding),
+        _ => response,
+    }
+}
+
+fn is_uncompressed_event_stream(response: &Response) -> bool {
+    let headers = response.headers();
+    !headers.contains_key(header::CONTENT_ENCODING)
+        && headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"))
+}
+
+fn compress(response: Response, encoding: Encoding) -> Response {
+    let (mut parts, body) = response.into_parts();
+    parts
+        .headers
+        .insert(header::CONTENT_ENCODING, encoding.header_value());
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+    let chunks = body.into_data_stream();
+    let compressed =
+        futures_util::stream::unfold(Some((chunks, Encoder::new(encoding))), |state| async move {
+            let (mut chunks, mut encoder) = state?;
+            match chunks.next().await {
+                Some(Ok(chunk)) => {
+                    let compressed = encoder.compress(&chunk).map_err(axum::Error::new);
+                    Some((compressed, Some((chunks, encoder))))
+                }
+                Some(Err(e)) => Some((Err(e), None)),
+                None => Some((encoder.finish().map_err(axum::Error::new), None)),
+            }
+        });
+    Response::from_parts(parts, Body::from_stream(compressed))
+}
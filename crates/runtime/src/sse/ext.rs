@@ -1,6 +1,9 @@
 use crate::sse::{EmitError, SseEmitter};
 use futures_core::Stream;
 use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use tokio_stream::StreamExt as _;
 
 pub trait PilcrowStreamExt: Stream + Sized {
@@ -12,6 +15,12 @@ pub trait PilcrowStreamExt: Stream + Sized {
     where
         Self: Stream<Item = T> + Unpin + Send + 'a,
         T: Serialize + Send + Sync + 'a;
+
+    /// Runs `cleanup` when the stream is dropped — normally or because the client
+    /// disconnected mid-stream. Wrap the stream before handing it to `sse_raw`.
+    fn on_close<F>(self, cleanup: F) -> OnClose<Self>
+    where
+        F: Future<Output = ()> + Send + 'static;
 }
 
 impl<S: Stream + Send> PilcrowStreamExt for S {
@@ -25,4 +34,54 @@ impl<S: Stream + Send> PilcrowStreamExt for S {
         }
         Ok(())
     }
+
+    fn on_close<F>(self, cleanup: F) -> OnClose<Self>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        OnClose {
+            stream: Box::pin(self),
+            cleanup: Some(Box::pin(cleanup)),
+        }
+    }
+}
+
+/// Stream wrapper returned by [`PilcrowStreamExt::on_close`].
+pub struct OnClose<S> {
+    stream: Pin<Box<S>>,
+    cleanup: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl<S: Stream> Stream for OnClose<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<S> Drop for OnClose<S> {
+    fn drop(&mut self) {
+        let Some(cleanup) = self.cleanup.take() else {
+            return;
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(cleanup);
+            }
+            Err(_) => tracing::warn!("SSE on_close cleanup skipped — no Tokio runtime"),
+        }
+    }
+}
+
+impl<S> std::fmt::Debug for OnClose<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnClose")
+            .field("pending_cleanup", &self.cleanup.is_some())
+            .finish_non_exhaustive()
+    }
 }
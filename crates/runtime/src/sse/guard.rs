@@ -0,0 +1,39 @@
+// ./src/sse/guard.rs
+//
+// Authorization before an SSE stream opens. Once the 200 and the
+// `text/event-stream` header are sent the request can no longer be refused,
+// so the check has to run first — and a refused EventSource does not retry.
+
+use axum::http::request::Parts;
+use axum::response::{IntoResponse, Response};
+use std::future::Future;
+
+/// Runs `guard` on the request and only opens the stream `stream` builds if
+/// it passes. A refusal is returned as-is — a 401, a redirect to the login
+/// page, whatever `E` renders — and no stream is started.
+///
+/// What the guard yields (the user, the session, …) is handed to `stream`.
+///
+/// ```ignore
+/// async fn notifications(State(app): State<App>, request: Parts) -> Response {
+///     sse_guarded(
+///         request,
+///         |parts| async move { current_user(&parts).await.ok_or(StatusCode::UNAUTHORIZED) },
+///         |user| app.notifications.handler_filtered(&[&user.topic()], LastEventId::default()),
+///     )
+///     .await
+/// }
+/// ```
+pub async fn sse_guarded<G, GFut, P, E, F, R>(request: Parts, guard: G, stream: F) -> Response
+where
+    G: FnOnce(Parts) -> GFut,
+    GFut: Future<Output = Result<P, E>>,
+    E: IntoResponse,
+    F: FnOnce(P) -> R,
+    R: IntoResponse,
+{
+    match guard(request).await {
+        Ok(principal) => stream(principal).into_response(),
+        Err(refusal) => refusal.into_response(),
+    }
+}
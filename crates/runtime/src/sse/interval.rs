@@ -1,5 +1,10 @@
 // ./src/sse/interval.rs
+use crate::sse::{SilcrowEvent, sse_stream};
+use axum::response::sse::{Event, Sse};
+use std::convert::Infallible;
+use std::future::Future;
 use std::time::Duration;
+use tokio::time::MissedTickBehavior;
 use tokio_stream::wrappers::IntervalStream;
 use tokio_stream::{Stream, StreamExt};
 
@@ -7,3 +12,40 @@ pub fn interval(duration: Duration) -> impl Stream<Item = ()> + Send + 'static {
     let interval = tokio::time::interval(duration);
     IntervalStream::new(interval).map(|_| ())
 }
+
+/// Polls `poll` every `period` and streams the returned events to the client.
+///
+/// Ticks that yield `None`, or an event identical to the last one sent, emit nothing.
+/// The poller stops as soon as the client disconnects. A zero `period` turns
+/// polling off: the stream stays open and `poll` is never called.
+pub fn sse_interval<F, Fut>(
+    period: Duration,
+    mut poll: F,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>> + Send + 'static>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Option<SilcrowEvent>> + Send + 'static,
+{
+    sse_stream(move |emit| async move {
+        if period.is_zero() {
+            tracing::warn!("sse_interval called with a zero period; not polling");
+            emit.closed().await;
+            return Ok(());
+        }
+        let mut ticks = tokio::time::interval(period);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last: Option<SilcrowEvent> = None;
+        while !emit.is_closed() {
+            ticks.tick().await;
+            let Some(event) = poll().await else {
+                continue;
+            };
+            if last.as_ref() == Some(&event) {
+                continue;
+            }
+            last = Some(event.clone());
+            emit.send(event).await?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,37 @@
+// ./src/sse/last_event_id.rs
+//
+// The `Last-Event-ID` header browsers send when an EventSource reconnects.
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use std::convert::Infallible;
+
+/// Id of the last event a reconnecting `EventSource` received, if it sent one.
+///
+/// Pass it to [`SseBroadcaster::handler_from`](crate::SseBroadcaster::handler_from)
+/// to replay what the client missed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LastEventId(pub Option<String>);
+
+impl LastEventId {
+    pub fn as_deref(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for LastEventId
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let id = parts
+            .headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty());
+        Ok(Self(id))
+    }
+}
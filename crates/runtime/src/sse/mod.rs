@@ -1,12 +1,30 @@
 // src/sse/mod.rs
+mod broadcast;
+mod channel;
+mod coalesce;
+#[cfg(feature = "compression")]
+mod compression;
 mod ext;
+mod guard;
 mod macros;
+mod options;
 mod server_sent_events;
 mod watch;
 
 mod interval;
-pub use ext::PilcrowStreamExt;
-pub use interval::interval;
+mod last_event_id;
+pub use broadcast::{Lagged, SseBroadcaster, SseConnection, SseLagPolicy, SseReplay};
+pub use channel::SseChannel;
+#[cfg(feature = "compression")]
+pub use compression::compress_sse;
+pub use ext::{OnClose, PilcrowStreamExt};
+pub use guard::sse_guarded;
+pub use interval::{interval, sse_interval};
+pub use last_event_id::LastEventId;
 pub(crate) use macros::serialize_or_null;
-pub use server_sent_events::{EmitError, SilcrowEvent, SseEmitter, SseRoute, sse_raw, sse_stream};
+pub use options::{SseEventIds, SseOptions};
+pub use server_sent_events::{
+    EmitError, SilcrowEvent, SseEmitter, SseRoute, SseSender, sse_channel, sse_patch_stream,
+    sse_raw, sse_stream, sse_with,
+};
 pub use watch::watch;
@@ -0,0 +1,96 @@
+// ./src/sse/options.rs
+//
+// Per-stream knobs for `sse_with`, gathered in one builder.
+
+use axum::response::sse::KeepAlive;
+use std::time::Duration;
+
+/// How [`sse_with`](crate::sse::sse_with) fills in SSE event ids.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SseEventIds {
+    /// Only ids set with [`SilcrowEvent::with_id`](crate::SilcrowEvent::with_id) are sent.
+    #[default]
+    Manual,
+    /// Events without an id of their own are numbered `1`, `2`, … in send order.
+    Sequential,
+}
+
+/// Stream options for [`sse_with`](crate::sse::sse_with).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseOptions {
+    /// How often a keep-alive comment is sent while the stream is idle.
+    /// `None` sends none.
+    pub keep_alive: Option<Duration>,
+    /// Events that may queue for a slow client before senders wait (or,
+    /// when [prioritized](Self::prioritized), lower-priority ones are shed).
+    pub buffer: usize,
+    /// Reconnect delay sent to the browser when the stream opens.
+    pub retry: Option<Duration>,
+    pub event_ids: SseEventIds,
+    /// Window in which patches to the same target collapse into the latest
+    /// before being sent. `None` sends every patch.
+    pub coalesce: Option<Duration>,
+    /// Whether a full buffer reorders and sheds events by priority instead
+    /// of making senders wait. Off by default: events go out in send order.
+    pub prioritized: bool,
+}
+
+impl Default for SseOptions {
+    fn default() -> Self {
+        Self {
+            keep_alive: Some(Duration::from_secs(15)),
+            buffer: 32,
+            retry: None,
+            event_ids: SseEventIds::default(),
+            coalesce: None,
+            prioritized: false,
+        }
+    }
+}
+
+impl SseOptions {
+    pub fn keep_alive(mut self, every: Duration) -> Self {
+        self.keep_alive = Some(every);
+        self
+    }
+
+    pub fn no_keep_alive(mut self) -> Self {
+        self.keep_alive = None;
+        self
+    }
+
+    pub fn buffer(mut self, events: usize) -> Self {
+        self.buffer = events;
+        self
+    }
+
+    pub fn retry(mut self, after: Duration) -> Self {
+        self.retry = Some(after);
+        self
+    }
+
+    pub fn event_ids(mut self, strategy: SseEventIds) -> Self {
+        self.event_ids = strategy;
+        self
+    }
+
+    /// Collapses patches to the same target sent within `window` of the
+    /// first into the latest, so chatty producers don't flood the client.
+    pub fn coalesce(mut self, window: Duration) -> Self {
+        self.coalesce = Some(window);
+        self
+    }
+
+    /// Under backpressure, delivers navigation and toasts ahead of patches
+    /// and sheds queued HTML frames and heartbeats first, so a lagging client
+    /// sees what matters instead of waiting behind bulky frames.
+    pub fn prioritized(mut self) -> Self {
+        self.prioritized = true;
+        self
+    }
+
+    pub(crate) fn keep_alive_config(&self) -> Option<KeepAlive> {
+        self.keep_alive
+            .map(|every| KeepAlive::new().interval(every))
+    }
+}
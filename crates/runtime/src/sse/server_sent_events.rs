@@ -1,25 +1,39 @@
+use crate::live::{
+    EventPriority, Prioritized, PrioritySender, PushOutcome, fifo_channel, priority_channel,
+};
+use crate::response::{JsonPatchOp, ToastLevel};
+use crate::sse::coalesce::coalesce;
+use crate::sse::{SseEventIds, SseOptions};
+use crate::ws::WsEvent;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use futures_core::Stream;
 use std::convert::Infallible;
 use std::future::Future;
-use tokio::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio_stream::StreamExt;
-use tokio_stream::wrappers::ReceiverStream;
 
 crate::define_route!(SseRoute, "SSE", "/events/feed", "FEED");
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SilcrowEvent {
     kind: EventKind,
     id: Option<String>,
+    retry: Option<Duration>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum EventKind {
     Patch {
         data: Result<serde_json::Value, String>,
         target: String,
     },
+    PatchMany {
+        patches: Result<Vec<(String, serde_json::Value)>, String>,
+    },
+    JsonPatch {
+        ops: Vec<JsonPatchOp>,
+        target: String,
+    },
     Html {
         markup: String,
         target: String,
@@ -33,7 +47,26 @@ pub(crate) enum EventKind {
     Custom {
         event: String,
         data: Result<serde_json::Value, String>,
+        /// Element the client dispatches the DOM event on instead of `document`.
+        target: Option<String>,
+    },
+    Toast {
+        message: String,
+        level: ToastLevel,
+    },
+    Trigger {
+        event: String,
+        data: Result<serde_json::Value, String>,
+    },
+    PushHistory {
+        url: String,
     },
+    Heartbeat {
+        /// Server time in milliseconds since the Unix epoch.
+        time: u64,
+        status: Result<serde_json::Value, String>,
+    },
+    Close,
 }
 
 impl SilcrowEvent {
@@ -45,6 +78,42 @@ impl SilcrowEvent {
                 target: target.to_owned(),
             },
             id: None,
+            retry: None,
+        }
+    }
+
+    /// Patches several targets from one event. The client applies them all in
+    /// the same task, so the page never renders with only some of them done.
+    pub fn patch_many<T, D>(patches: impl IntoIterator<Item = (T, D)>) -> Self
+    where
+        T: AsRef<str>,
+        D: serde::Serialize,
+    {
+        let patches = patches
+            .into_iter()
+            .map(|(target, data)| {
+                serde_json::to_value(data)
+                    .map(|data| (target.as_ref().to_owned(), data))
+                    .map_err(|e| e.to_string())
+            })
+            .collect();
+        Self {
+            kind: EventKind::PatchMany { patches },
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Applies RFC 6902 operations to the data last patched into `target`,
+    /// so a large bound dataset can change a row at a time.
+    pub fn json_patch(ops: impl IntoIterator<Item = JsonPatchOp>, target: &str) -> Self {
+        Self {
+            kind: EventKind::JsonPatch {
+                ops: ops.into_iter().collect(),
+                target: target.to_owned(),
+            },
+            id: None,
+            retry: None,
         }
     }
 
@@ -56,6 +125,7 @@ impl SilcrowEvent {
                 target: target.to_owned(),
             },
             id: None,
+            retry: None,
         }
     }
 
@@ -71,6 +141,7 @@ impl SilcrowEvent {
                 target: target.to_owned(),
             },
             id: None,
+            retry: None,
         }
     }
 
@@ -79,90 +150,393 @@ impl SilcrowEvent {
         Self {
             kind: EventKind::Navigate { path: path.into() },
             id: None,
+            retry: None,
         }
     }
 
-    /// Dispatches a named custom event on the client as `silcrow:sse:custom`.
+    /// An application-defined event named `event`. The client runs every
+    /// handler registered with `Silcrow.on(event, handler)` and dispatches
+    /// `silcrow:sse:<event>` on `document`, so apps can layer their own
+    /// protocol over the stream.
     pub fn custom(event: impl Into<String>, data: impl serde::Serialize) -> Self {
         Self {
             kind: EventKind::Custom {
                 event: event.into(),
                 data: serde_json::to_value(data).map_err(|e| e.to_string()),
+                target: None,
+            },
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Like [`SilcrowEvent::custom`], dispatching the DOM event on the element
+    /// matching `target` (it still bubbles) and handing that element to
+    /// `Silcrow.on` handlers.
+    pub fn custom_at(event: impl Into<String>, data: impl serde::Serialize, target: &str) -> Self {
+        Self {
+            kind: EventKind::Custom {
+                event: event.into(),
+                data: serde_json::to_value(data).map_err(|e| e.to_string()),
+                target: Some(target.to_owned()),
+            },
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Shows a toast, same as `ResponseExt::with_toast`.
+    pub fn toast(message: impl Into<String>, level: ToastLevel) -> Self {
+        Self {
+            kind: EventKind::Toast {
+                message: message.into(),
+                level,
             },
             id: None,
+            retry: None,
         }
     }
 
+    /// Dispatches DOM event `event` on `document` with `data` as its detail,
+    /// same as `ResponseExt::trigger_event`.
+    pub fn trigger(event: impl Into<String>, data: impl serde::Serialize) -> Self {
+        Self {
+            kind: EventKind::Trigger {
+                event: event.into(),
+                data: serde_json::to_value(data).map_err(|e| e.to_string()),
+            },
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Updates the URL without navigating, same as `ResponseExt::push_history`.
+    pub fn push_history(url: impl Into<String>) -> Self {
+        Self {
+            kind: EventKind::PushHistory { url: url.into() },
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// A liveness signal carrying the server's current time and `status`,
+    /// dispatched on the client as `silcrow:sse:heartbeat`. Lets pages show
+    /// "last updated" and notice a stream that has gone quiet.
+    pub fn heartbeat(status: impl serde::Serialize) -> Self {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_millis() as u64);
+        Self {
+            kind: EventKind::Heartbeat {
+                time,
+                status: serde_json::to_value(status).map_err(|e| e.to_string()),
+            },
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Tells the client to close the stream and stop reconnecting — after
+    /// logout, when a job has finished, or for a retired endpoint. The client
+    /// dispatches `silcrow:live:close`; `Silcrow.reconnect` opens it again.
+    ///
+    /// Sent through an [`SseEmitter`], it goes out after everything queued
+    /// before it and ends the stream; later sends fail with
+    /// [`EmitError::Disconnected`]. On a stream of your own, end it after
+    /// sending; otherwise the browser simply closes its side.
+    pub fn close() -> Self {
+        Self {
+            kind: EventKind::Close,
+            id: None,
+            retry: None,
+        }
+    }
+
+    pub(crate) fn is_close(&self) -> bool {
+        matches!(self.kind, EventKind::Close)
+    }
+
     /// Attach a `Last-Event-ID` so reconnecting clients can resume from this event.
     pub fn with_id(mut self, id: impl Into<String>) -> Self {
         self.id = Some(id.into());
         self
     }
 
-    fn serialize_check(&self) -> Result<(), String> {
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Sets the SSE `retry:` field: how long the browser waits before
+    /// reconnecting if the stream drops. Useful to spread reconnects during
+    /// deploys or shed load when overloaded.
+    pub fn with_retry(mut self, after: Duration) -> Self {
+        self.retry = Some(after);
+        self
+    }
+
+    /// The target of a single-target `patch`, which a newer patch to the same
+    /// target makes redundant.
+    pub(crate) fn patch_target(&self) -> Option<&str> {
         match &self.kind {
-            EventKind::Patch { data, .. } | EventKind::Custom { data, .. } => {
+            EventKind::Patch { target, .. } => Some(target),
+            _ => None,
+        }
+    }
+
+    /// DOM targets the event updates; empty for page-wide events.
+    pub(crate) fn targets(&self) -> Vec<&str> {
+        match &self.kind {
+            EventKind::Patch { target, .. }
+            | EventKind::JsonPatch { target, .. }
+            | EventKind::Html { target, .. }
+            | EventKind::Invalidate { target } => vec![target.as_str()],
+            EventKind::PatchMany {
+                patches: Ok(patches),
+            } => patches.iter().map(|(target, _)| target.as_str()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub(crate) fn serialize_check(&self) -> Result<(), String> {
+        match &self.kind {
+            EventKind::Patch { data, .. }
+            | EventKind::Custom { data, .. }
+            | EventKind::Trigger { data, .. }
+            | EventKind::Heartbeat { status: data, .. } => {
                 data.as_ref().map(|_| ()).map_err(Clone::clone)
             }
+            EventKind::PatchMany { patches } => patches.as_ref().map(|_| ()).map_err(Clone::clone),
             _ => Ok(()),
         }
     }
 }
 
-fn apply_id(event: Event, id: Option<String>) -> Event {
-    match id {
+impl Prioritized for SilcrowEvent {
+    fn priority(&self) -> EventPriority {
+        match self.kind {
+            EventKind::Navigate { .. } | EventKind::Toast { .. } => EventPriority::High,
+            EventKind::Html { .. } | EventKind::Heartbeat { .. } | EventKind::Close => {
+                EventPriority::Low
+            }
+            EventKind::Patch { .. }
+            | EventKind::PatchMany { .. }
+            | EventKind::JsonPatch { .. }
+            | EventKind::Invalidate { .. }
+            | EventKind::Custom { .. }
+            | EventKind::Trigger { .. }
+            | EventKind::PushHistory { .. } => EventPriority::Normal,
+        }
+    }
+
+    fn is_final(&self) -> bool {
+        self.is_close()
+    }
+}
+
+fn apply_meta(event: Event, id: Option<String>, retry: Option<Duration>) -> Event {
+    let event = match id {
         Some(id) => event.id(id),
         None => event,
+    };
+    match retry {
+        Some(retry) => event.retry(retry),
+        None => event,
     }
 }
 
 impl From<SilcrowEvent> for Event {
     fn from(evt: SilcrowEvent) -> Event {
-        let id = evt.id;
+        let (id, retry) = (evt.id, evt.retry);
         match evt.kind {
             EventKind::Patch { data, target } => match data {
                 Err(e) => {
                     tracing::warn!("SilcrowEvent::patch dropped — serialization failed: {e}");
                     Event::default().comment("pilcrow:serialize_error")
                 }
-                Ok(data) => apply_id(
+                Ok(data) => apply_meta(
                     Event::default()
                         .event("patch")
                         .json_data(serde_json::json!({ "target": target, "data": data }))
                         .unwrap_or_else(|_| Event::default().comment("pilcrow:encode_error")),
                     id,
+                    retry,
                 ),
             },
-            EventKind::Html { markup, target } => apply_id(
+            EventKind::PatchMany { patches } => match patches {
+                Err(e) => {
+                    tracing::warn!("SilcrowEvent::patch_many dropped — serialization failed: {e}");
+                    Event::default().comment("pilcrow:serialize_error")
+                }
+                Ok(patches) => {
+                    let patches: Vec<_> = patches
+                        .into_iter()
+                        .map(|(target, data)| serde_json::json!({ "target": target, "data": data }))
+                        .collect();
+                    apply_meta(
+                        Event::default()
+                            .event("patches")
+                            .json_data(patches)
+                            .unwrap_or_else(|_| Event::default().comment("pilcrow:encode_error")),
+                        id,
+                        retry,
+                    )
+                }
+            },
+            EventKind::JsonPatch { ops, target } => apply_meta(
+                Event::default()
+                    .event("json_patch")
+                    .json_data(serde_json::json!({ "target": target, "ops": ops }))
+                    .unwrap_or_else(|_| Event::default().comment("pilcrow:encode_error")),
+                id,
+                retry,
+            ),
+            EventKind::Html { markup, target } => apply_meta(
                 Event::default()
                     .event("html")
                     .json_data(serde_json::json!({ "target": target, "html": markup }))
                     .unwrap_or_else(|_| Event::default().comment("pilcrow:encode_error")),
                 id,
+                retry,
             ),
             EventKind::Invalidate { target } => {
-                apply_id(Event::default().event("invalidate").data(target), id)
+                apply_meta(Event::default().event("invalidate").data(target), id, retry)
             }
             EventKind::Navigate { path } => {
-                apply_id(Event::default().event("navigate").data(path), id)
+                apply_meta(Event::default().event("navigate").data(path), id, retry)
             }
-            EventKind::Custom { event, data } => match data {
+            EventKind::Custom {
+                event,
+                data,
+                target,
+            } => match data {
                 Err(e) => {
                     tracing::warn!("SilcrowEvent::custom dropped — serialization failed: {e}");
                     Event::default().comment("pilcrow:serialize_error")
                 }
-                Ok(data) => apply_id(
+                Ok(data) => apply_meta(
                     Event::default()
                         .event("custom")
+                        .json_data(match target {
+                            Some(target) => {
+                                serde_json::json!({ "event": event, "data": data, "target": target })
+                            }
+                            None => serde_json::json!({ "event": event, "data": data }),
+                        })
+                        .unwrap_or_else(|_| Event::default().comment("pilcrow:encode_error")),
+                    id,
+                    retry,
+                ),
+            },
+            EventKind::Toast { message, level } => apply_meta(
+                Event::default()
+                    .event("toast")
+                    .json_data(serde_json::json!({ "message": message, "level": level }))
+                    .unwrap_or_else(|_| Event::default().comment("pilcrow:encode_error")),
+                id,
+                retry,
+            ),
+            EventKind::Trigger { event, data } => match data {
+                Err(e) => {
+                    tracing::warn!("SilcrowEvent::trigger dropped — serialization failed: {e}");
+                    Event::default().comment("pilcrow:serialize_error")
+                }
+                Ok(data) => apply_meta(
+                    Event::default()
+                        .event("trigger")
                         .json_data(serde_json::json!({ "event": event, "data": data }))
                         .unwrap_or_else(|_| Event::default().comment("pilcrow:encode_error")),
                     id,
+                    retry,
                 ),
             },
+            EventKind::PushHistory { url } => {
+                apply_meta(Event::default().event("push_history").data(url), id, retry)
+            }
+            EventKind::Close => apply_meta(Event::default().event("close").data("{}"), id, retry),
+            EventKind::Heartbeat { time, status } => match status {
+                Err(e) => {
+                    tracing::warn!("SilcrowEvent::heartbeat dropped — serialization failed: {e}");
+                    Event::default().comment("pilcrow:serialize_error")
+                }
+                Ok(status) => apply_meta(
+                    Event::default()
+                        .event("heartbeat")
+                        .json_data(serde_json::json!({ "time": time, "status": status }))
+                        .unwrap_or_else(|_| Event::default().comment("pilcrow:encode_error")),
+                    id,
+                    retry,
+                ),
+            },
+        }
+    }
+}
+
+/// Every WebSocket event has an SSE equivalent, so one event can feed a
+/// [`WsHub`](crate::WsHub) and an SSE fallback route alike.
+impl From<WsEvent> for SilcrowEvent {
+    fn from(event: WsEvent) -> Self {
+        let kind = match event {
+            WsEvent::Patch { target, data } => EventKind::Patch {
+                data: Ok(data),
+                target,
+            },
+            WsEvent::JsonPatch { target, ops } => EventKind::JsonPatch { ops, target },
+            WsEvent::Html { target, markup } => EventKind::Html { markup, target },
+            WsEvent::Invalidate { target } => EventKind::Invalidate { target },
+            WsEvent::Navigate { path } => EventKind::Navigate { path },
+            WsEvent::Custom { event, data } => EventKind::Custom {
+                event,
+                data: Ok(data),
+                target: None,
+            },
+            WsEvent::Toast { message, level } => EventKind::Toast { message, level },
+            WsEvent::Trigger { event, data } => EventKind::Trigger {
+                event,
+                data: Ok(data),
+            },
+            WsEvent::PushHistory { url } => EventKind::PushHistory { url },
+        };
+        Self {
+            kind,
+            id: None,
+            retry: None,
         }
     }
 }
 
+/// Fails, handing the event back, for SSE-only events — `patch_many`,
+/// `heartbeat` and `close` — and for payloads that failed to serialize.
+/// The SSE id and retry hint have no WebSocket counterpart and are dropped.
+impl TryFrom<SilcrowEvent> for WsEvent {
+    type Error = SilcrowEvent;
+
+    fn try_from(event: SilcrowEvent) -> Result<Self, Self::Error> {
+        let SilcrowEvent { kind, id, retry } = event;
+        Ok(match kind {
+            EventKind::Patch {
+                data: Ok(data),
+                target,
+            } => WsEvent::Patch { target, data },
+            EventKind::JsonPatch { ops, target } => WsEvent::JsonPatch { target, ops },
+            EventKind::Html { markup, target } => WsEvent::Html { target, markup },
+            EventKind::Invalidate { target } => WsEvent::Invalidate { target },
+            EventKind::Navigate { path } => WsEvent::Navigate { path },
+            EventKind::Custom {
+                event,
+                data: Ok(data),
+                target: None,
+            } => WsEvent::Custom { event, data },
+            EventKind::Toast { message, level } => WsEvent::Toast { message, level },
+            EventKind::Trigger {
+                event,
+                data: Ok(data),
+            } => WsEvent::Trigger { event, data },
+            EventKind::PushHistory { url } => WsEvent::PushHistory { url },
+            kind => return Err(SilcrowEvent { kind, id, retry }),
+        })
+    }
+}
+
 #[must_use = "SSE errors must be handled — use ? to propagate"]
 #[derive(Debug)]
 pub enum EmitError {
@@ -183,9 +557,14 @@ impl std::fmt::Display for EmitError {
 
 impl std::error::Error for EmitError {}
 
-#[derive(Clone)]
+/// Handle for pushing events into an SSE stream.
+///
+/// Events are buffered and delivered in send order; `send` waits while the
+/// buffer is full. With [`SseOptions::prioritized`], navigation is delivered
+/// ahead of patches instead, and queued HTML frames are shed first.
+#[derive(Clone, Debug)]
 pub struct SseEmitter {
-    tx: mpsc::Sender<SilcrowEvent>,
+    tx: PrioritySender<SilcrowEvent>,
 }
 
 impl SseEmitter {
@@ -194,17 +573,52 @@ impl SseEmitter {
             tracing::warn!("SilcrowEvent dropped — serialization failed: {e}");
             return Err(EmitError::Serialize(e));
         }
-        self.tx
-            .send(event)
-            .await
-            .map_err(|_| EmitError::Disconnected)
+        match self.tx.send(event).await {
+            Ok(PushOutcome::Queued) => Ok(()),
+            Ok(PushOutcome::Displaced(dropped)) => {
+                tracing::debug!(
+                    "SSE client lagging — dropped queued {:?} event",
+                    dropped.priority()
+                );
+                Ok(())
+            }
+            Err(_) => Err(EmitError::Disconnected),
+        }
+    }
+    /// Returns `true` once the client has disconnected and the stream was dropped.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Resolves once the client has disconnected and the stream was dropped.
+    pub async fn closed(&self) {
+        self.tx.closed().await
     }
+
+    /// Runs `cleanup` once the stream closes — on client disconnect or after the
+    /// handler finishes. Use it to release subscriptions and presence entries.
+    pub fn on_close<F>(&self, cleanup: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        // A weak handle, so the watcher doesn't keep the stream open itself.
+        let watcher = self.tx.downgrade();
+        tokio::spawn(async move {
+            watcher.closed().await;
+            cleanup.await;
+        });
+    }
+
     /// Convenience for sending serializable data to a DOM target.
     pub async fn json(&self, target: &str, data: &impl serde::Serialize) -> Result<(), EmitError> {
         self.send(SilcrowEvent::json(data, target)).await
     }
 }
 
+/// Cloneable handle returned by [`sse_channel`]; the same type `sse_stream`
+/// hands its closure.
+pub type SseSender = SseEmitter;
+
 pub fn sse_stream<F, Fut>(
     handler: F,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>> + Send + 'static>
@@ -212,16 +626,97 @@ where
     F: FnOnce(SseEmitter) -> Fut + Send + 'static,
     Fut: Future<Output = Result<(), EmitError>> + Send + 'static,
 {
-    let (tx, rx) = mpsc::channel::<SilcrowEvent>(32);
-    let emitter = SseEmitter { tx };
+    sse_with(SseOptions::default(), handler)
+}
+
+/// Like [`sse_stream`], with the keep-alive, buffering, retry and event id
+/// settings from `options`.
+///
+/// ```ignore
+/// let options = SseOptions::default()
+///     .retry(Duration::from_secs(10))
+///     .event_ids(SseEventIds::Sequential);
+/// sse_with(options, |emit| async move { ... })
+/// ```
+pub fn sse_with<F, Fut>(
+    options: SseOptions,
+    handler: F,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>> + Send + 'static>
+where
+    F: FnOnce(SseEmitter) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), EmitError>> + Send + 'static,
+{
+    let (emitter, response) = channel_with(&options);
 
     tokio::spawn(async move {
         let _ = handler(emitter).await;
     });
 
-    let stream = ReceiverStream::new(rx).map(|event| Ok::<Event, Infallible>(event.into()));
+    response
+}
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+/// An SSE response fed by a sender you keep, instead of a closure.
+///
+/// Clone the sender into background tasks; the stream ends once every clone
+/// is dropped, and `send` fails with [`EmitError::Disconnected`] once the
+/// client has gone. Up to `buffer` events queue in order; beyond that,
+/// senders wait.
+///
+/// ```ignore
+/// async fn progress(State(jobs): State<Jobs>) -> impl IntoResponse {
+///     let (tx, response) = sse_channel(16);
+///     jobs.spawn(move |step| {
+///         let tx = tx.clone();
+///         async move { tx.send(SilcrowEvent::patch(step, "#progress")).await }
+///     });
+///     response
+/// }
+/// ```
+pub fn sse_channel(
+    buffer: usize,
+) -> (
+    SseSender,
+    Sse<impl Stream<Item = Result<Event, Infallible>> + Send + 'static>,
+) {
+    channel_with(&SseOptions::default().buffer(buffer))
+}
+
+fn channel_with(
+    options: &SseOptions,
+) -> (
+    SseSender,
+    Sse<impl Stream<Item = Result<Event, Infallible>> + Send + 'static + use<>>,
+) {
+    let (tx, rx) = match options.prioritized {
+        true => priority_channel::<SilcrowEvent>(options.buffer),
+        false => fifo_channel::<SilcrowEvent>(options.buffer),
+    };
+
+    let sequential = options.event_ids == SseEventIds::Sequential;
+    let mut sent: u64 = 0;
+    let events = coalesce(
+        rx.into_stream(),
+        options.coalesce,
+        SilcrowEvent::patch_target,
+    )
+    .map(move |event| {
+        sent += 1;
+        match event.id {
+            None if sequential => event.with_id(sent.to_string()),
+            _ => event,
+        }
+    });
+    let retry = options.retry.map(|after| Event::default().retry(after));
+    let stream = tokio_stream::iter(retry)
+        .chain(events.map(Event::from))
+        .map(Ok::<Event, Infallible>);
+
+    let sse = Sse::new(stream);
+    let sse = match options.keep_alive_config() {
+        Some(keep_alive) => sse.keep_alive(keep_alive),
+        None => sse,
+    };
+    (SseEmitter { tx }, sse)
 }
 
 pub fn sse_raw<S>(stream: S) -> Sse<S>
@@ -230,3 +725,23 @@ where
 {
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
+
+/// Streams every item `stream` yields to `Silcrow.patch(item, target)`, ending
+/// when the stream does — the whole body of most data-feed handlers.
+///
+/// ```ignore
+/// async fn prices(State(app): State<App>) -> impl IntoResponse {
+///     sse_patch_stream(app.prices.subscribe(), "#prices")
+/// }
+/// ```
+pub fn sse_patch_stream<S, T>(
+    stream: S,
+    target: &str,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>> + Send + use<S, T>>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: serde::Serialize,
+{
+    let target = target.to_owned();
+    sse_raw(stream.map(move |item| Ok(Event::from(SilcrowEvent::patch(item, &target)))))
+}
@@ -0,0 +1,4 @@
+// src/test/mod.rs
+mod sse_collector;
+
+pub use sse_collector::{CollectedEvent, SseCollectError, SseCollector, SseFrame};
@@ -0,0 +1,382 @@
+// ./src/test/sse_collector.rs
+//
+// Reads an SSE response body the way a browser's EventSource would and hands
+// each frame back as a typed event, so tests assert on targets and payloads
+// instead of searching raw wire text.
+
+use axum::body::{Body, BodyDataStream};
+use axum::response::Response;
+use serde_json::Value;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+/// One SSE frame as the browser sees it: `event:` (defaulting to `message`),
+/// the joined `data:` lines and the optional `id:`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseFrame {
+    pub event: String,
+    pub data: String,
+    pub id: Option<String>,
+}
+
+impl SseFrame {
+    /// The data parsed as JSON.
+    pub fn json(&self) -> Result<Value, SseCollectError> {
+        serde_json::from_str(&self.data)
+            .map_err(|e| SseCollectError::Malformed(format!("{} data: {e}", self.event)))
+    }
+
+    /// The frame decoded as the [`SilcrowEvent`](crate::SilcrowEvent) that
+    /// produced it.
+    pub fn to_event(&self) -> Result<CollectedEvent, SseCollectError> {
+        let field = |json: &Value, name: &str| -> Result<Value, SseCollectError> {
+            json.get(name).cloned().ok_or_else(|| {
+                SseCollectError::Malformed(format!("{} data has no `{name}`", self.event))
+            })
+        };
+        let text = |json: &Value, name: &str| -> Result<String, SseCollectError> {
+            match field(json, name)? {
+                Value::String(s) => Ok(s),
+                other => Err(SseCollectError::Malformed(format!(
+                    "{} `{name}` is not a string: {other}",
+                    self.event
+                ))),
+            }
+        };
+
+        Ok(match self.event.as_str() {
+            "patch" => {
+                let json = self.json()?;
+                CollectedEvent::Patch {
+                    target: text(&json, "target")?,
+                    data: field(&json, "data")?,
+                }
+            }
+            "patches" => {
+                let json = self.json()?;
+                let entries = json.as_array().ok_or_else(|| {
+                    SseCollectError::Malformed("patches data is not an array".into())
+                })?;
+                let patches = entries
+                    .iter()
+                    .map(|entry| Ok((text(entry, "target")?, field(entry, "data")?)))
+                    .collect::<Result<_, SseCollectError>>()?;
+                CollectedEvent::PatchMany(patches)
+            }
+            "json_patch" => {
+                let json = self.json()?;
+                CollectedEvent::JsonPatch {
+                    target: text(&json, "target")?,
+                    ops: serde_json::from_value(field(&json, "ops")?)
+                        .map_err(|e| SseCollectError::Malformed(e.to_string()))?,
+                }
+            }
+            "html" => {
+                let json = self.json()?;
+                CollectedEvent::Html {
+                    target: text(&json, "target")?,
+                    html: text(&json, "html")?,
+                }
+            }
+            "invalidate" => CollectedEvent::Invalidate {
+                target: self.data.clone(),
+            },
+            "navigate" => CollectedEvent::Navigate {
+                path: self.data.clone(),
+            },
+            "custom" => {
+                let json = self.json()?;
+                CollectedEvent::Custom {
+                    event: text(&json, "event")?,
+                    data: field(&json, "data")?,
+                    target: match json.get("target") {
+                        Some(_) => Some(text(&json, "target")?),
+                        None => None,
+                    },
+                }
+            }
+            "toast" => {
+                let json = self.json()?;
+                CollectedEvent::Toast {
+                    message: text(&json, "message")?,
+                    level: text(&json, "level")?,
+                }
+            }
+            "trigger" => {
+                let json = self.json()?;
+                CollectedEvent::Trigger {
+                    event: text(&json, "event")?,
+                    data: field(&json, "data")?,
+                }
+            }
+            "heartbeat" => {
+                let json = self.json()?;
+                CollectedEvent::Heartbeat {
+                    time: field(&json, "time")?.as_u64().ok_or_else(|| {
+                        SseCollectError::Malformed("heartbeat `time` is not a number".into())
+                    })?,
+                    status: field(&json, "status")?,
+                }
+            }
+            "push_history" => CollectedEvent::PushHistory {
+                url: self.data.clone(),
+            },
+            "close" => CollectedEvent::Close,
+            _ => CollectedEvent::Other(self.clone()),
+        })
+    }
+}
+
+/// A decoded [`SilcrowEvent`](crate::SilcrowEvent). Frames with an event name
+/// Silcrow does not send arrive as [`CollectedEvent::Other`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollectedEvent {
+    Patch {
+        target: String,
+        data: Value,
+    },
+    PatchMany(Vec<(String, Value)>),
+    JsonPatch {
+        target: String,
+        ops: Vec<crate::response::JsonPatchOp>,
+    },
+    Html {
+        target: String,
+        html: String,
+    },
+    Invalidate {
+        target: String,
+    },
+    Navigate {
+        path: String,
+    },
+    Custom {
+        event: String,
+        data: Value,
+        target: Option<String>,
+    },
+    Toast {
+        message: String,
+        level: String,
+    },
+    Trigger {
+        event: String,
+        data: Value,
+    },
+    PushHistory {
+        url: String,
+    },
+    Heartbeat {
+        time: u64,
+        status: Value,
+    },
+    Close,
+    Other(SseFrame),
+}
+
+#[derive(Debug)]
+pub enum SseCollectError {
+    /// Nothing arrived within the collector's timeout.
+    Timeout,
+    /// The stream ended.
+    Ended,
+    /// The body failed while streaming.
+    Body(axum::Error),
+    /// The stream carried something that is not a valid frame or event.
+    Malformed(String),
+}
+
+impl std::fmt::Display for SseCollectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "SseCollectError::Timeout"),
+            Self::Ended => write!(f, "SseCollectError::Ended"),
+            Self::Body(e) => write!(f, "SseCollectError::Body: {e}"),
+            Self::Malformed(reason) => write!(f, "SseCollectError::Malformed: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SseCollectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Body(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Drives an SSE body and parses it frame by frame.
+///
+/// Keep-alive comments are skipped and a `retry:` hint is remembered rather
+/// than returned. Every read waits at most the collector's timeout
+/// (2 seconds unless changed with [`SseCollector::with_timeout`]).
+///
+/// ```ignore
+/// let response = app.oneshot(Request::get(FEED.path()).body(Body::empty())?).await?;
+/// let mut events = SseCollector::new(response);
+///
+/// broadcaster.publish(SilcrowEvent::patch(&order, "#order"));
+/// assert_eq!(
+///     events.next_event().await?,
+///     CollectedEvent::Patch { target: "#order".into(), data: json!({ "id": 7 }) },
+/// );
+/// ```
+pub struct SseCollector {
+    body: BodyDataStream,
+    buffer: Vec<u8>,
+    timeout: Duration,
+    retry: Option<Duration>,
+    ended: bool,
+}
+
+impl SseCollector {
+    pub fn new(response: Response) -> Self {
+        Self::from_body(response.into_body())
+    }
+
+    pub fn from_body(body: Body) -> Self {
+        Self {
+            body: body.into_data_stream(),
+            buffer: Vec::new(),
+            timeout: Duration::from_secs(2),
+            retry: None,
+            ended: false,
+        }
+    }
+
+    /// How long each read waits for the next frame.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The last `retry:` reconnect hint the stream sent.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    /// The next frame carrying an event or data.
+    pub async fn next_frame(&mut self) -> Result<SseFrame, SseCollectError> {
+        let timeout = self.timeout;
+        tokio::time::timeout(timeout, self.read_frame())
+            .await
+            .map_err(|_| SseCollectError::Timeout)?
+    }
+
+    /// The next frame, decoded.
+    pub async fn next_event(&mut self) -> Result<CollectedEvent, SseCollectError> {
+        self.next_frame().await?.to_event()
+    }
+
+    /// The next `count` events, failing if any of them does not arrive in time.
+    pub async fn take(&mut self, count: usize) -> Result<Vec<CollectedEvent>, SseCollectError> {
+        let mut events = Vec::with_capacity(count);
+        for _ in 0..count {
+            events.push(self.next_event().await?);
+        }
+        Ok(events)
+    }
+
+    /// Every event sent until the stream ends.
+    pub async fn collect_to_end(&mut self) -> Result<Vec<CollectedEvent>, SseCollectError> {
+        let mut events = Vec::new();
+        loop {
+            match self.next_event().await {
+                Ok(event) => events.push(event),
+                Err(SseCollectError::Ended) => return Ok(events),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Waits `within` and returns the frame if one arrived, `None` otherwise.
+    /// Useful for asserting that nothing was sent.
+    pub async fn try_next_frame(
+        &mut self,
+        within: Duration,
+    ) -> Result<Option<SseFrame>, SseCollectError> {
+        match tokio::time::timeout(within, self.read_frame()).await {
+            Ok(Ok(frame)) => Ok(Some(frame)),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Ok(None),
+        }
+    }
+
+    async fn read_frame(&mut self) -> Result<SseFrame, SseCollectError> {
+        loop {
+            while let Some(block) = self.take_block() {
+                if let Some(frame) = self.parse(&block?) {
+                    return Ok(frame);
+                }
+            }
+            if self.ended {
+                return Err(SseCollectError::Ended);
+            }
+            match self.body.next().await {
+                Some(Ok(chunk)) => self
+                    .buffer
+                    .extend(chunk.iter().filter(|&&byte| byte != b'\r')),
+                Some(Err(e)) => return Err(SseCollectError::Body(e)),
+                // Like EventSource, a trailing frame without its blank line is dropped.
+                None => self.ended = true,
+            }
+        }
+    }
+
+    /// Removes the next complete, blank-line terminated block from the buffer.
+    fn take_block(&mut self) -> Option<Result<String, SseCollectError>> {
+        let end = self.buffer.windows(2).position(|pair| pair == b"\n\n")?;
+        let block: Vec<u8> = self.buffer.drain(..end + 2).take(end).collect();
+        Some(String::from_utf8(block).map_err(|e| SseCollectError::Malformed(e.to_string())))
+    }
+
+    fn parse(&mut self, block: &str) -> Option<SseFrame> {
+        let mut event = None;
+        let mut data: Option<String> = None;
+        let mut id = None;
+        for line in block.lines() {
+            if line.starts_with(':') {
+                continue;
+            }
+            let (name, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match name {
+                "event" => event = Some(value.to_owned()),
+                "data" => match &mut data {
+                    Some(data) => {
+                        data.push('\n');
+                        data.push_str(value);
+                    }
+                    None => data = Some(value.to_owned()),
+                },
+                "id" => id = Some(value.to_owned()),
+                "retry" => {
+                    if let Ok(ms) = value.parse() {
+                        self.retry = Some(Duration::from_millis(ms));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if event.is_none() && data.is_none() {
+            return None;
+        }
+        Some(SseFrame {
+            event: event.unwrap_or_else(|| "message".to_owned()),
+            data: data.unwrap_or_default(),
+            id,
+        })
+    }
+}
+
+impl std::fmt::Debug for SseCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SseCollector")
+            .field("buffered", &self.buffer.len())
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("ended", &self.ended)
+            .finish()
+    }
+}
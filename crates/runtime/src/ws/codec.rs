@@ -0,0 +1,116 @@
+// ./src/ws/codec.rs
+//
+// Wire formats for WsEvent frames, negotiated per connection through
+// `Sec-WebSocket-Protocol`. JSON text frames are the default; with the
+// `msgpack` feature a client may ask for MessagePack binary frames instead.
+// silcrow.js only decodes JSON and asks for `silcrow.json`, so MessagePack
+// is for native and custom clients.
+
+use crate::live::{EventPriority, Prioritized};
+use crate::ws::ws::WsEvent;
+use axum::extract::ws::{Message, WebSocketUpgrade};
+use axum::http::HeaderValue;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Subprotocol a client offers to receive JSON text frames.
+pub const JSON_PROTOCOL: &str = "silcrow.json";
+
+/// Subprotocol a client offers to receive MessagePack binary frames.
+#[cfg(feature = "msgpack")]
+pub const MSGPACK_PROTOCOL: &str = "silcrow.msgpack";
+
+/// How `WsEvent`s are encoded on a single connection.
+///
+/// Non-exhaustive: formats come and go with features, so matches need a
+/// wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum WsFormat {
+    #[default]
+    Json,
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl WsFormat {
+    /// The subprotocol name that selects this format.
+    pub fn protocol(self) -> &'static str {
+        match self {
+            Self::Json => JSON_PROTOCOL,
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => MSGPACK_PROTOCOL,
+        }
+    }
+
+    /// Format for the subprotocol the handshake settled on. No protocol means JSON.
+    pub(crate) fn from_protocol(protocol: Option<&HeaderValue>) -> Self {
+        match protocol.and_then(|value| value.to_str().ok()) {
+            #[cfg(feature = "msgpack")]
+            Some(MSGPACK_PROTOCOL) => Self::MessagePack,
+            _ => Self::Json,
+        }
+    }
+
+    /// Encodes an event, a batch of events, or an event wrapped with its channel.
+    pub(crate) fn encode<T>(self, value: &T) -> Result<Message, axum::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        match self {
+            Self::Json => serde_json::to_string(value)
+                .map(Message::Text)
+                .map_err(axum::Error::new),
+            // Named encoding keeps the `type` tag readable by any MessagePack decoder.
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => rmp_serde::to_vec_named(value)
+                .map(Message::Binary)
+                .map_err(axum::Error::new),
+        }
+    }
+}
+
+/// Offers the Silcrow subprotocols (binary preferred when the client accepts
+/// it), followed by any app-defined ones.
+pub(crate) fn negotiate(upgrade: WebSocketUpgrade, extra: &[String]) -> WebSocketUpgrade {
+    #[cfg(feature = "msgpack")]
+    let silcrow = [MSGPACK_PROTOCOL, JSON_PROTOCOL];
+    #[cfg(not(feature = "msgpack"))]
+    let silcrow = [JSON_PROTOCOL];
+    let extra = extra.iter().map(|protocol| Cow::Owned(protocol.clone()));
+    upgrade.protocols(silcrow.into_iter().map(Cow::Borrowed).chain(extra))
+}
+
+/// Encodes one event at most once per wire format during a fan-out.
+pub(crate) struct FrameCache<'a> {
+    event: &'a WsEvent,
+    context: &'static str,
+    frames: HashMap<WsFormat, Option<Message>>,
+}
+
+impl<'a> FrameCache<'a> {
+    pub(crate) fn new(event: &'a WsEvent, context: &'static str) -> Self {
+        Self {
+            event,
+            context,
+            frames: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn priority(&self) -> EventPriority {
+        self.event.priority()
+    }
+
+    pub(crate) fn frame(&mut self, format: WsFormat) -> Option<Message> {
+        let (event, context) = (self.event, self.context);
+        self.frames
+            .entry(format)
+            .or_insert_with(|| {
+                format
+                    .encode(event)
+                    .map_err(|e| tracing::warn!("{context} serialization failed: {e}"))
+                    .ok()
+            })
+            .clone()
+    }
+}
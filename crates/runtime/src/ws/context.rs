@@ -0,0 +1,152 @@
+// ./src/ws/context.rs
+//
+// Per-connection details captured from the upgrade request and handed to the
+// WebSocket handler next to its `WsStream`.
+
+use crate::ws::codec;
+use crate::ws::ws::{ConnectionId, WsOptions, WsStream, configure, run_with_heartbeat};
+use axum::{
+    async_trait,
+    extract::{
+        ConnectInfo, FromRequestParts, Query, WebSocketUpgrade,
+        ws::rejection::WebSocketUpgradeRejection,
+    },
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+
+/// Who is on the other end of a WebSocket, for identifying and logging connections.
+#[derive(Debug, Clone)]
+pub struct WsContext {
+    id: ConnectionId,
+    remote_addr: Option<SocketAddr>,
+    query: HashMap<String, String>,
+    protocol: Option<String>,
+}
+
+impl WsContext {
+    pub fn id(&self) -> ConnectionId {
+        self.id
+    }
+
+    /// Peer address. Only known when the app is served with
+    /// `into_make_service_with_connect_info::<SocketAddr>()`.
+    pub fn remote_addr(&self) -> Option<SocketAddr> {
+        self.remote_addr
+    }
+
+    /// A query parameter from the upgrade request URL.
+    pub fn query(&self, key: &str) -> Option<&str> {
+        self.query.get(key).map(String::as_str)
+    }
+
+    pub fn query_params(&self) -> &HashMap<String, String> {
+        &self.query
+    }
+
+    /// Subprotocol selected during the handshake, if any.
+    pub fn protocol(&self) -> Option<&str> {
+        self.protocol.as_deref()
+    }
+}
+
+/// `WebSocketUpgrade` that also remembers the request details a [`WsContext`] needs.
+///
+/// ```ignore
+/// async fn chat(upgrade: WsUpgrade) -> Response {
+///     upgrade.on_connect(|mut stream, ctx| async move {
+///         tracing::info!("{} joined from {:?}", ctx.id(), ctx.remote_addr());
+///         while let Some(Ok(event)) = stream.recv().await { /* ... */ }
+///     })
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WsUpgrade {
+    upgrade: WebSocketUpgrade,
+    remote_addr: Option<SocketAddr>,
+    query: HashMap<String, String>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for WsUpgrade
+where
+    S: Send + Sync,
+{
+    type Rejection = WebSocketUpgradeRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let upgrade = WebSocketUpgrade::from_request_parts(parts, state).await?;
+        let remote_addr = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr);
+        let query = Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+            .map(|Query(query)| query)
+            .unwrap_or_default();
+        Ok(Self {
+            upgrade,
+            remote_addr,
+            query,
+        })
+    }
+}
+
+impl WsUpgrade {
+    /// Completes the upgrade and runs `handler` with the stream and its context.
+    pub fn on_connect<F, Fut>(self, handler: F) -> Response
+    where
+        F: FnOnce(WsStream, WsContext) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.connect(None, handler)
+    }
+
+    /// Like [`WsUpgrade::on_connect`], with heartbeat pings, dead-connection
+    /// detection, and the outbound queue sized from `options`.
+    pub fn on_connect_with<F, Fut>(self, options: WsOptions, handler: F) -> Response
+    where
+        F: FnOnce(WsStream, WsContext) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.connect(Some(options), handler)
+    }
+
+    fn connect<F, Fut>(self, options: Option<WsOptions>, handler: F) -> Response
+    where
+        F: FnOnce(WsStream, WsContext) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (upgrade, remote_addr, query) = (self.upgrade, self.remote_addr, self.query);
+        let upgrade = match &options {
+            Some(options) => configure(upgrade, options),
+            None => codec::negotiate(upgrade, &[]),
+        };
+        upgrade
+            .on_upgrade(move |socket| async move {
+                let protocol = socket
+                    .protocol()
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_owned);
+                let stream = WsStream::with_options(
+                    socket,
+                    options.as_ref().unwrap_or(&WsOptions::default()),
+                );
+                let context = WsContext {
+                    id: stream.id(),
+                    remote_addr,
+                    query,
+                    protocol,
+                };
+                match options {
+                    Some(options) => {
+                        run_with_heartbeat(stream, options, |stream| handler(stream, context)).await
+                    }
+                    None => handler(stream, context).await,
+                }
+            })
+            .into_response()
+    }
+}
@@ -0,0 +1,122 @@
+// ./src/ws/event_router.rs
+//
+// Dispatches `Custom` events to async handlers by name, decoding each payload
+// into the type its handler asks for.
+
+use crate::ws::sender::WsSender;
+use crate::ws::ws::{WsEvent, WsRecvError, WsStream};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type HandlerFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+type EventHandler = Arc<
+    dyn Fn(serde_json::Value, WsSender) -> Result<HandlerFuture, serde_json::Error> + Send + Sync,
+>;
+type FallbackHandler = Arc<dyn Fn(WsEvent, WsSender) -> HandlerFuture + Send + Sync>;
+
+/// Maps `Custom` event names to handlers, replacing a hand-written `match`.
+///
+/// Build one router and share it across connections; cloning is cheap.
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct ChatMessage { room: String, text: String }
+///
+/// let router = WsEventRouter::new()
+///     .on("chat.message", |msg: ChatMessage, tx: WsSender| async move {
+///         let _ = tx.send(&WsEvent::custom("chat.ack", msg.room)).await;
+///     })
+///     .on("typing", |_: serde_json::Value, _| async {});
+///
+/// async fn chat(upgrade: WebSocketUpgrade, State(router): State<WsEventRouter>) -> Response {
+///     ws(upgrade, move |stream| async move { router.run(stream).await })
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct WsEventRouter {
+    handlers: HashMap<String, EventHandler>,
+    fallback: Option<FallbackHandler>,
+}
+
+impl WsEventRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Routes `Custom` events named `event` to `handler`, with the payload
+    /// decoded as `T`. A later registration for the same name wins.
+    pub fn on<T, F, Fut>(mut self, event: impl Into<String>, handler: F) -> Self
+    where
+        T: serde::de::DeserializeOwned,
+        F: Fn(T, WsSender) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler: EventHandler = Arc::new(move |data, sender| {
+            let payload = serde_json::from_value(data)?;
+            Ok(Box::pin(handler(payload, sender)))
+        });
+        self.handlers.insert(event.into(), handler);
+        self
+    }
+
+    /// Receives every event no `on` handler claims, including non-`Custom` ones.
+    pub fn fallback<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(WsEvent, WsSender) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.fallback = Some(Arc::new(move |event, sender| {
+            Box::pin(handler(event, sender))
+        }));
+        self
+    }
+
+    /// Runs the handler for `event`, replying through `sender`.
+    ///
+    /// Unclaimed events without a fallback are ignored. A payload that does not
+    /// decode into the handler's type yields [`WsRecvError::Deserialize`].
+    pub async fn dispatch(&self, event: WsEvent, sender: &WsSender) -> Result<(), WsRecvError> {
+        let handler = event.custom_name().and_then(|name| self.handlers.get(name));
+        match (event, handler) {
+            (WsEvent::Custom { data, .. }, Some(handler)) => {
+                handler(data, sender.clone())
+                    .map_err(WsRecvError::Deserialize)?
+                    .await;
+            }
+            (event, _) => match &self.fallback {
+                Some(fallback) => fallback(event, sender.clone()).await,
+                None => tracing::debug!("WsEventRouter ignored an unrouted {event:?}"),
+            },
+        }
+        Ok(())
+    }
+
+    /// Dispatches every event from `stream` until the client disconnects.
+    /// Frames and payloads that fail to decode are logged and skipped.
+    pub async fn run(&self, mut stream: WsStream) {
+        let sender = stream.sender();
+        loop {
+            let dispatched = match stream.recv().await {
+                Some(Ok(event)) => self.dispatch(event, &sender).await,
+                Some(Err(WsRecvError::Closed { .. })) | None => break,
+                Some(Err(e)) => Err(e),
+            };
+            if let Err(e) = dispatched {
+                tracing::debug!("WsEventRouter on {} skipped an event: {e}", stream.id());
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for WsEventRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut events: Vec<&str> = self.handlers.keys().map(String::as_str).collect();
+        events.sort_unstable();
+        f.debug_struct("WsEventRouter")
+            .field("events", &events)
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,482 @@
+// ./src/ws/hub.rs
+//
+// Fan-out of WsEvents to every connected client, or to the members of a room.
+
+use crate::extract::extract::SilcrowRequest;
+use crate::live::{ConnectionLimiter, ConnectionPermit, SUPERSEDED_CLOSE_CODE};
+use crate::ws::codec::{self, FrameCache};
+use crate::ws::context::{WsContext, WsUpgrade};
+use crate::ws::metrics::WsMetrics;
+use crate::ws::resume::{RESUME_EVENT, RESUME_PARAM, Sessions};
+use crate::ws::sender::{WsSendError, WsSender};
+use crate::ws::ws::{ConnectionId, WsEvent, WsStream};
+use axum::extract::ws::{CloseFrame, WebSocketUpgrade};
+use axum::http::request::Parts;
+use axum::response::{IntoResponse, Response};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// How long [`WsHub::handle`] waits for a handler to finish after shutdown.
+const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Default)]
+struct HubState {
+    connections: HashMap<ConnectionId, WsSender>,
+    rooms: HashMap<String, HashSet<ConnectionId>>,
+    /// Set once the hub has shut down; late registrations are closed with it.
+    closed_with: Option<CloseFrame<'static>>,
+    sessions: Sessions,
+    metrics: Option<WsMetrics>,
+    shutdown_grace: Option<Duration>,
+    limiter: Option<ConnectionLimiter>,
+}
+
+impl HubState {
+    fn remove_connection(&mut self, id: ConnectionId) {
+        self.connections.remove(&id);
+        self.sessions.detach(id);
+        self.rooms.retain(|_, members| {
+            members.remove(&id);
+            !members.is_empty()
+        });
+    }
+
+    /// Queues the event for each of `ids` in that connection's wire format,
+    /// pruning connections that are closed. A full queue never blocks the
+    /// fan-out; its overflow policy decides what is dropped.
+    fn deliver(&mut self, ids: Vec<ConnectionId>, frames: &mut FrameCache<'_>) -> usize {
+        let mut delivered = 0;
+        let mut closed = Vec::new();
+        for id in ids {
+            let Some(sender) = self.connections.get(&id) else {
+                continue;
+            };
+            let Some(frame) = frames.frame(sender.format()) else {
+                continue;
+            };
+            match sender.try_send_frame(frame, frames.priority()) {
+                Ok(()) => delivered += 1,
+                Err(WsSendError::Closed) => closed.push(id),
+                Err(_) => {}
+            }
+        }
+        closed.into_iter().for_each(|id| self.remove_connection(id));
+        delivered
+    }
+}
+
+#[derive(Debug)]
+struct HubInner {
+    state: Mutex<HubState>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl Default for HubInner {
+    fn default() -> Self {
+        Self::with_state(HubState::default())
+    }
+}
+
+impl HubInner {
+    fn with_state(state: HubState) -> Self {
+        Self {
+            state: Mutex::new(state),
+            shutdown: watch::channel(false).0,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HubState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Registry of live WebSocket connections. Cheap to clone; share it via router state.
+///
+/// Any handler or background task can `publish` to every registered client,
+/// or `publish_to` the members of a named room.
+#[derive(Debug, Clone, Default)]
+pub struct WsHub {
+    inner: Arc<HubInner>,
+}
+
+impl WsHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A hub that keeps sessions alive across reconnects.
+    ///
+    /// Connections accepted through [`WsHub::handle_resumable`] receive a
+    /// token first (as the `silcrow:resume` custom event). For `ttl` after a
+    /// disconnect, up to `buffer` events published to that session are kept;
+    /// reconnecting with `?resume=<token>` restores its rooms and replays them.
+    /// silcrow.js does this automatically.
+    ///
+    /// The token is a bearer credential: whoever presents it gets the rooms
+    /// back *without* the handler re-checking access, and it travels in the
+    /// URL, where proxies and access logs may record it. Keep `ttl` short,
+    /// and where room access can be revoked, re-authorize at the top of the
+    /// handler:
+    ///
+    /// ```ignore
+    /// hub.handle_resumable(upgrade, move |stream, _ctx| async move {
+    ///     for room in hub.rooms_of(stream.id()) {
+    ///         if !user.may_see(&room) {
+    ///             hub.leave(&room, stream.id());
+    ///         }
+    ///     }
+    ///     // ...
+    /// })
+    /// ```
+    pub fn resumable(buffer: usize, ttl: Duration) -> Self {
+        let state = HubState {
+            sessions: Sessions::new(buffer, ttl),
+            ..HubState::default()
+        };
+        Self {
+            inner: Arc::new(HubInner::with_state(state)),
+        }
+    }
+
+    /// Reports every connection registered from now on into `metrics`,
+    /// unless its stream already reports elsewhere through [`WsOptions::metrics`](crate::WsOptions::metrics).
+    pub fn with_metrics(self, metrics: WsMetrics) -> Self {
+        self.inner.lock().metrics = Some(metrics);
+        self
+    }
+
+    /// How long handlers get to finish after [`WsHub::shutdown`] before
+    /// [`WsHub::handle`] drops them. Defaults to 5 seconds.
+    pub fn with_shutdown_grace(self, grace: Duration) -> Self {
+        self.inner.lock().shutdown_grace = Some(grace);
+        self
+    }
+
+    /// Caps connections per identity for [`WsHub::handle_for`]: an upgrade
+    /// over the limit is refused with a 429, or, under
+    /// [`LimitPolicy::SupersedeOldest`](crate::live::LimitPolicy::SupersedeOldest),
+    /// the identity's oldest socket is closed to make room.
+    pub fn with_limiter(self, limiter: ConnectionLimiter) -> Self {
+        self.inner.lock().limiter = Some(limiter);
+        self
+    }
+
+    /// Adds `stream` to the hub. Pair with [`WsHub::unregister`] when the handler ends,
+    /// or use [`WsHub::handle`] which does both.
+    pub fn register(&self, stream: &WsStream) -> ConnectionId {
+        let id = stream.id();
+        let sender = stream.sender();
+        let mut state = self.inner.lock();
+        if let Some(metrics) = &state.metrics {
+            sender.attach_metrics(metrics);
+        }
+        match &state.closed_with {
+            Some(frame) => {
+                sender.close(Some(frame.clone()));
+            }
+            None => {
+                state.connections.insert(id, sender);
+            }
+        }
+        id
+    }
+
+    /// Removes the connection from the hub and from every room it joined.
+    pub fn unregister(&self, id: ConnectionId) {
+        self.inner.lock().remove_connection(id);
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.inner.lock().connections.len()
+    }
+
+    /// Adds a registered connection to `room`. Returns `false` if `conn` is not registered.
+    pub fn join(&self, room: impl Into<String>, conn: ConnectionId) -> bool {
+        let mut state = self.inner.lock();
+        if !state.connections.contains_key(&conn) {
+            return false;
+        }
+        let room = room.into();
+        state.sessions.joined(&room, conn);
+        state.rooms.entry(room).or_default().insert(conn);
+        true
+    }
+
+    /// Removes `conn` from `room`. Empty rooms are dropped.
+    pub fn leave(&self, room: &str, conn: ConnectionId) {
+        let mut state = self.inner.lock();
+        state.sessions.left(room, conn);
+        if let Some(members) = state.rooms.get_mut(room) {
+            members.remove(&conn);
+            if members.is_empty() {
+                state.rooms.remove(room);
+            }
+        }
+    }
+
+    /// The rooms `conn` is in, e.g. to re-check access after a resumed
+    /// session restored them.
+    pub fn rooms_of(&self, conn: ConnectionId) -> Vec<String> {
+        self.inner
+            .lock()
+            .rooms
+            .iter()
+            .filter(|(_, members)| members.contains(&conn))
+            .map(|(room, _)| room.clone())
+            .collect()
+    }
+
+    /// Number of connections currently in `room`.
+    pub fn room_size(&self, room: &str) -> usize {
+        self.inner.lock().rooms.get(room).map_or(0, HashSet::len)
+    }
+
+    /// Sends `event` to every connected client, serializing it once per wire format.
+    /// Returns how many connections it was queued for; closed ones are pruned.
+    pub fn publish(&self, event: impl Into<WsEvent>) -> usize {
+        let event = event.into();
+        let mut frames = FrameCache::new(&event, "WsHub::publish");
+        let mut state = self.inner.lock();
+        let ids = state.connections.keys().copied().collect();
+        let delivered = state.deliver(ids, &mut frames);
+        state.sessions.record_missed(None, &event);
+        delivered
+    }
+
+    /// Sends `event` to the members of `room` only.
+    pub fn publish_to(&self, room: &str, event: impl Into<WsEvent>) -> usize {
+        let event = event.into();
+        let mut frames = FrameCache::new(&event, "WsHub::publish_to");
+        let mut state = self.inner.lock();
+        let ids = state
+            .rooms
+            .get(room)
+            .map(|members| members.iter().copied().collect())
+            .unwrap_or_default();
+        let delivered = state.deliver(ids, &mut frames);
+        state.sessions.record_missed(Some(room), &event);
+        delivered
+    }
+
+    /// Sends a Close frame with `code` and `reason` to every connection, drops
+    /// all rooms, and fires [`WsHub::cancelled`]. Connections registered
+    /// afterwards are closed immediately. Returns how many sockets were closed.
+    pub fn shutdown(&self, code: u16, reason: impl Into<Cow<'static, str>>) -> usize {
+        let frame = CloseFrame {
+            code,
+            reason: reason.into(),
+        };
+        let senders = {
+            let mut state = self.inner.lock();
+            state.closed_with = Some(frame.clone());
+            state.rooms.clear();
+            std::mem::take(&mut state.connections)
+        };
+        let closed = senders
+            .into_values()
+            .filter(|sender| sender.close(Some(frame.clone())))
+            .count();
+        self.inner.shutdown.send_replace(true);
+        closed
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        *self.inner.shutdown.borrow()
+    }
+
+    /// Resolves once [`WsHub::shutdown`] has been called. Handlers can `select!`
+    /// on it to flush state before the socket goes away.
+    pub async fn cancelled(&self) {
+        let mut shutdown = self.inner.shutdown.subscribe();
+        let _ = shutdown.wait_for(|down| *down).await;
+    }
+
+    /// Wraps `signal` for `axum::serve(..).with_graceful_shutdown(..)`: when it
+    /// resolves, every socket is closed with `code` / `reason` before the
+    /// server stops accepting connections.
+    ///
+    /// ```ignore
+    /// axum::serve(listener, app)
+    ///     .with_graceful_shutdown(hub.shutdown_on(ctrl_c(), 1001, "server restarting"))
+    ///     .await?;
+    /// ```
+    pub fn shutdown_on<F>(
+        &self,
+        signal: F,
+        code: u16,
+        reason: impl Into<Cow<'static, str>>,
+    ) -> impl Future<Output = ()> + Send + 'static
+    where
+        F: Future + Send + 'static,
+    {
+        let hub = self.clone();
+        let reason = reason.into();
+        async move {
+            signal.await;
+            let closed = hub.shutdown(code, reason);
+            tracing::debug!("WsHub shut down, closed {closed} connection(s)");
+        }
+    }
+
+    /// Upgrades the request, registers the stream for the handler's lifetime,
+    /// and unregisters it once the handler returns.
+    ///
+    /// On [`WsHub::shutdown`] the handler keeps running for the
+    /// [shutdown grace](WsHub::with_shutdown_grace) and is dropped only if it
+    /// has not returned by then. Handlers that need to flush state select on
+    /// [`WsHub::cancelled`]:
+    ///
+    /// ```ignore
+    /// let hub2 = hub.clone();
+    /// hub.handle(upgrade, move |mut stream| async move {
+    ///     tokio::select! {
+    ///         _ = serve(&mut stream) => {}
+    ///         _ = hub2.cancelled() => save_drafts().await,
+    ///     }
+    /// })
+    /// ```
+    pub fn handle<F, Fut>(&self, upgrade: WebSocketUpgrade, handler: F) -> Response
+    where
+        F: FnOnce(WsStream) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.upgrade(upgrade, None, handler)
+    }
+
+    /// Like [`WsHub::handle`], counting the connection against the hub's
+    /// [limiter](WsHub::with_limiter) for whoever sent `request`. Over the
+    /// limit, the upgrade is refused with a negotiated 429. A socket
+    /// superseded by a newer one from the same identity is sent a Close
+    /// frame and its handler gets the shutdown grace to return.
+    ///
+    /// ```ignore
+    /// async fn chat(State(app): State<App>, request: Parts, upgrade: WebSocketUpgrade) -> Response {
+    ///     app.hub.handle_for(&request, upgrade, |stream| serve(stream))
+    /// }
+    /// ```
+    pub fn handle_for<F, Fut>(
+        &self,
+        request: &Parts,
+        upgrade: WebSocketUpgrade,
+        handler: F,
+    ) -> Response
+    where
+        F: FnOnce(WsStream) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let limiter = self.inner.lock().limiter.clone();
+        let permit = match limiter.map(|limiter| limiter.admit(request)) {
+            Some(Ok(permit)) => permit,
+            Some(Err(exceeded)) => {
+                return exceeded.into_negotiated_response(&SilcrowRequest::parse(request));
+            }
+            None => None,
+        };
+        self.upgrade(upgrade, permit, handler)
+    }
+
+    fn upgrade<F, Fut>(
+        &self,
+        upgrade: WebSocketUpgrade,
+        permit: Option<ConnectionPermit>,
+        handler: F,
+    ) -> Response
+    where
+        F: FnOnce(WsStream) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hub = self.clone();
+        codec::negotiate(upgrade, &[])
+            .on_upgrade(|socket| async move {
+                let stream = WsStream::new(socket);
+                let id = hub.register(&stream);
+                let superseded = close_when_superseded(permit, stream.sender());
+                hub.run_until_shutdown(handler(stream), superseded).await;
+                hub.unregister(id);
+            })
+            .into_response()
+    }
+
+    /// Like [`WsHub::handle`], resuming the client's session when it reconnects
+    /// with a token. On a hub not built with [`WsHub::resumable`] this is
+    /// `handle` with a [`WsContext`].
+    pub fn handle_resumable<F, Fut>(&self, upgrade: WsUpgrade, handler: F) -> Response
+    where
+        F: FnOnce(WsStream, WsContext) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hub = self.clone();
+        upgrade.on_connect(move |stream, context| async move {
+            let id = hub.register_resumable(&stream, context.query(RESUME_PARAM));
+            hub.run_until_shutdown(handler(stream, context), std::future::pending())
+                .await;
+            hub.unregister(id);
+        })
+    }
+
+    /// Runs `handler` to completion, or, once the hub shuts down or
+    /// `superseded` resolves, for at most the shutdown grace.
+    async fn run_until_shutdown(
+        &self,
+        handler: impl Future<Output = ()>,
+        superseded: impl Future<Output = ()>,
+    ) {
+        tokio::pin!(handler);
+        tokio::select! {
+            _ = &mut handler => return,
+            _ = self.cancelled() => {}
+            _ = superseded => {}
+        }
+        let grace = self
+            .inner
+            .lock()
+            .shutdown_grace
+            .unwrap_or(DEFAULT_SHUTDOWN_GRACE);
+        if tokio::time::timeout(grace, handler).await.is_err() {
+            tracing::debug!("WsHub handler outlived the shutdown grace and was dropped");
+        }
+    }
+
+    /// Registers `stream`, then — under the same lock, so nothing published
+    /// meanwhile can overtake them — queues its token, rejoins its rooms and
+    /// replays what it missed.
+    fn register_resumable(&self, stream: &WsStream, token: Option<&str>) -> ConnectionId {
+        let id = self.register(stream);
+        let mut state = self.inner.lock();
+        if !state.connections.contains_key(&id) {
+            return id;
+        }
+        let Some(resumed) = state.sessions.attach(token, id) else {
+            return id;
+        };
+        let sender = stream.sender();
+        let greeting = WsEvent::custom(RESUME_EVENT, serde_json::json!({ "token": resumed.token }));
+        let _ = sender.try_send(&greeting);
+        for room in resumed.rooms {
+            state.rooms.entry(room).or_default().insert(id);
+        }
+        for event in &resumed.missed {
+            let _ = sender.try_send(event);
+        }
+        id
+    }
+}
+
+/// Holds `permit` until a newer connection from the same identity takes its
+/// slot, then closes the socket. Never resolves without a permit.
+async fn close_when_superseded(permit: Option<ConnectionPermit>, sender: WsSender) {
+    let Some(mut permit) = permit else {
+        return std::future::pending().await;
+    };
+    permit.superseded().await;
+    sender.close(Some(CloseFrame {
+        code: SUPERSEDED_CLOSE_CODE,
+        reason: Cow::Borrowed("superseded by a newer connection"),
+    }));
+}
@@ -0,0 +1,48 @@
+// ./src/ws/intercept.rs
+//
+// Cross-cutting hooks around a WsStream's send and recv: logging, metrics,
+// schema validation, auth refresh.
+
+use crate::ws::ws::{WsEvent, WsRecvError};
+
+/// Which way an event is travelling through an interceptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsDirection {
+    /// Received from the client.
+    Inbound,
+    /// About to be sent to the client.
+    Outbound,
+}
+
+/// Sees every event a `WsStream` receives or sends.
+///
+/// Interceptors run in registration order. They wrap the handler's own
+/// `recv` / `send`; frames pushed through a `WsHub` or `WsSender` bypass them.
+pub trait WsInterceptor: Send + Sync + 'static {
+    /// Inspect or rewrite a received event. An `Err` is handed to the handler
+    /// from `recv` in place of the event.
+    fn inbound(&self, event: WsEvent) -> Result<WsEvent, WsRecvError> {
+        Ok(event)
+    }
+
+    /// Inspect or rewrite an outgoing event. `None` drops it silently.
+    fn outbound(&self, event: WsEvent) -> Option<WsEvent> {
+        Some(event)
+    }
+}
+
+/// Observe-only interceptor: any `Fn(WsDirection, &WsEvent)` closure.
+impl<F> WsInterceptor for F
+where
+    F: Fn(WsDirection, &WsEvent) + Send + Sync + 'static,
+{
+    fn inbound(&self, event: WsEvent) -> Result<WsEvent, WsRecvError> {
+        self(WsDirection::Inbound, &event);
+        Ok(event)
+    }
+
+    fn outbound(&self, event: WsEvent) -> Option<WsEvent> {
+        self(WsDirection::Outbound, &event);
+        Some(event)
+    }
+}
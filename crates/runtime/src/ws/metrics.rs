@@ -0,0 +1,137 @@
+// ./src/ws/metrics.rs
+//
+// Counters for the WebSocket layer. Streams and hubs given a WsMetrics handle
+// update it as they work; operators read it from wherever they export metrics.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+#[derive(Debug, Default)]
+struct Counters {
+    active: AtomicUsize,
+    sent: AtomicU64,
+    received: AtomicU64,
+    send_errors: AtomicU64,
+    depth_total: AtomicU64,
+    depth_samples: AtomicU64,
+}
+
+/// Shared, cloneable WebSocket counters.
+///
+/// Attach one with [`WsOptions::metrics`](crate::WsOptions::metrics) or
+/// [`WsHub::with_metrics`](crate::WsHub::with_metrics); every connection using
+/// it reports into the same counters.
+///
+/// ```ignore
+/// let metrics = WsMetrics::new();
+/// let hub = WsHub::new().with_metrics(metrics.clone());
+/// // later, in a scrape handler:
+/// let snapshot = metrics.snapshot();
+/// gauge!("ws_active").set(snapshot.active_connections as f64);
+/// ```
+#[derive(Clone, Default)]
+pub struct WsMetrics {
+    counters: Arc<Counters>,
+}
+
+/// Point-in-time copy of [`WsMetrics`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WsMetricsSnapshot {
+    pub active_connections: usize,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub send_errors: u64,
+    pub average_queue_depth: f64,
+}
+
+impl WsMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connections whose socket is still open.
+    pub fn active_connections(&self) -> usize {
+        self.counters.active.load(Ordering::Relaxed)
+    }
+
+    /// Data frames written to sockets.
+    pub fn messages_sent(&self) -> u64 {
+        self.counters.sent.load(Ordering::Relaxed)
+    }
+
+    /// Data frames handed to handlers.
+    pub fn messages_received(&self) -> u64 {
+        self.counters.received.load(Ordering::Relaxed)
+    }
+
+    /// Frames that were dropped by an overflow policy, hit a closed
+    /// connection, failed to encode, or failed to write.
+    pub fn send_errors(&self) -> u64 {
+        self.counters.send_errors.load(Ordering::Relaxed)
+    }
+
+    /// Mean outbound queue length seen when frames are queued. A value
+    /// creeping towards the queue capacity means clients are falling behind.
+    pub fn average_queue_depth(&self) -> f64 {
+        let samples = self.counters.depth_samples.load(Ordering::Relaxed);
+        if samples == 0 {
+            return 0.0;
+        }
+        self.counters.depth_total.load(Ordering::Relaxed) as f64 / samples as f64
+    }
+
+    pub fn snapshot(&self) -> WsMetricsSnapshot {
+        WsMetricsSnapshot {
+            active_connections: self.active_connections(),
+            messages_sent: self.messages_sent(),
+            messages_received: self.messages_received(),
+            send_errors: self.send_errors(),
+            average_queue_depth: self.average_queue_depth(),
+        }
+    }
+
+    pub(crate) fn connection_opened(&self) {
+        self.counters.active.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn connection_closed(&self) {
+        self.counters.active.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_sent(&self) {
+        self.counters.sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_received(&self) {
+        self.counters.received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_send_error(&self) {
+        self.counters.send_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_queue_depth(&self, depth: usize) {
+        self.counters
+            .depth_total
+            .fetch_add(depth as u64, Ordering::Relaxed);
+        self.counters.depth_samples.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Handles are equal when they share counters.
+impl PartialEq for WsMetrics {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.counters, &other.counters)
+    }
+}
+
+impl std::fmt::Debug for WsMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WsMetrics")
+            .field("active_connections", &self.active_connections())
+            .field("messages_sent", &self.messages_sent())
+            .field("messages_received", &self.messages_received())
+            .field("send_errors", &self.send_errors())
+            .finish_non_exhaustive()
+    }
+}
@@ -1 +1,26 @@
+mod codec;
+mod context;
+mod event_router;
+mod hub;
+mod intercept;
+mod metrics;
+mod mux;
+mod rate_limit;
+mod resume;
+mod sender;
+#[allow(clippy::module_inception)]
 pub mod ws;
+
+#[cfg(feature = "msgpack")]
+pub use codec::MSGPACK_PROTOCOL;
+pub use codec::{JSON_PROTOCOL, WsFormat};
+pub use context::{WsContext, WsUpgrade};
+pub use event_router::WsEventRouter;
+pub use hub::WsHub;
+pub use intercept::{WsDirection, WsInterceptor};
+pub use metrics::{WsMetrics, WsMetricsSnapshot};
+pub use mux::{WsChannel, WsMux};
+pub use rate_limit::{RateLimit, RateLimitAction};
+pub use resume::{RESUME_EVENT, RESUME_PARAM};
+pub use sender::{OverflowPolicy, WsSendError, WsSender};
+pub use ws::{ConnectionId, WsEvent, WsOptions, WsRecvError, WsRoute, WsStream, ws, ws_with};
@@ -0,0 +1,178 @@
+// ./src/ws/mux.rs
+//
+// Sub-channels over one WebSocket. Frames may carry a `channel` field next to
+// the event; WsMux hands each channel's events to its own handler task.
+
+use crate::live::Prioritized;
+use crate::ws::sender::{WsSendError, WsSender};
+use crate::ws::ws::{WsEvent, WsRecvError, WsStream};
+use std::future::Future;
+use std::pin::Pin;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+/// Events buffered per channel before the mux stops reading from the socket.
+const CHANNEL_BUFFER: usize = 32;
+
+/// Wire shape of an inbound frame: a `WsEvent` plus the optional `channel` tag.
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct ChannelFrame {
+    #[serde(default)]
+    pub(crate) channel: Option<String>,
+    #[serde(flatten)]
+    pub(crate) event: WsEvent,
+}
+
+/// Wire shape of an outbound frame sent through a [`WsChannel`].
+#[derive(serde::Serialize)]
+struct OnChannel<'a> {
+    channel: &'a str,
+    #[serde(flatten)]
+    event: &'a WsEvent,
+}
+
+type ChannelHandler = Box<dyn FnOnce(WsChannel) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
+/// Routes the events of one connection to per-channel handlers, so chat,
+/// notifications and presence can share a socket.
+///
+/// Each handler runs in its own task with a [`WsChannel`]; events without a
+/// channel, or for one nobody handles, are dropped.
+///
+/// ```ignore
+/// ws(upgrade, |stream| async move {
+///     WsMux::new()
+///         .channel("chat", |mut chat| async move {
+///             while let Some(event) = chat.recv().await { /* ... */ }
+///         })
+///         .channel("presence", presence)
+///         .run(stream)
+///         .await
+/// })
+/// ```
+#[derive(Default)]
+pub struct WsMux {
+    handlers: Vec<(String, ChannelHandler)>,
+}
+
+impl WsMux {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the handler for `name`. A later registration for the same name wins.
+    pub fn channel<F, Fut>(mut self, name: impl Into<String>, handler: F) -> Self
+    where
+        F: FnOnce(WsChannel) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        self.handlers.retain(|(existing, _)| *existing != name);
+        self.handlers
+            .push((name, Box::new(move |channel| Box::pin(handler(channel)))));
+        self
+    }
+
+    /// Reads `stream` until the client disconnects, then waits for every
+    /// channel handler to finish.
+    pub async fn run(self, mut stream: WsStream) {
+        let mut routes = Vec::with_capacity(self.handlers.len());
+        let mut tasks = JoinSet::new();
+        for (name, handler) in self.handlers {
+            let (tx, rx) = mpsc::channel(CHANNEL_BUFFER);
+            let channel = WsChannel {
+                name: name.clone(),
+                sender: stream.sender(),
+                inbound: rx,
+            };
+            tasks.spawn(handler(channel));
+            routes.push((name, tx));
+        }
+
+        loop {
+            let (channel, event) = match stream.recv_channel().await {
+                Some(Ok(received)) => received,
+                Some(Err(WsRecvError::Closed { .. })) | None => break,
+                Some(Err(e)) => {
+                    tracing::debug!("WsMux on {} skipped a frame: {e}", stream.id());
+                    continue;
+                }
+            };
+            let route = channel
+                .as_deref()
+                .and_then(|channel| routes.iter().find(|(name, _)| name == channel));
+            match route {
+                // A handler that has returned no longer receives; that is not an error.
+                Some((_, tx)) => {
+                    let _ = tx.send(event).await;
+                }
+                None => tracing::debug!(
+                    "WsMux on {} dropped an event for channel {channel:?}",
+                    stream.id()
+                ),
+            }
+        }
+
+        drop(routes);
+        while let Some(joined) = tasks.join_next().await {
+            if let Err(e) = joined {
+                tracing::warn!("WsMux channel handler failed: {e}");
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for WsMux {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let channels: Vec<&str> = self
+            .handlers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        f.debug_struct("WsMux")
+            .field("channels", &channels)
+            .finish()
+    }
+}
+
+/// One channel of a [`WsMux`]: its inbound events and a way to answer on it.
+///
+/// Sends go straight to the connection's queue, tagged with the channel name;
+/// the stream's interceptors do not see them.
+#[derive(Debug)]
+pub struct WsChannel {
+    name: String,
+    sender: WsSender,
+    inbound: mpsc::Receiver<WsEvent>,
+}
+
+impl WsChannel {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Next event the client sent on this channel. `None` once the connection is gone.
+    pub async fn recv(&mut self) -> Option<WsEvent> {
+        self.inbound.recv().await
+    }
+
+    /// Queues `event` on this channel, waiting for space in the outbound queue.
+    pub async fn send(&self, event: WsEvent) -> Result<(), WsSendError> {
+        let frame = self.encode(&event)?;
+        self.sender.send_frame(frame, event.priority()).await
+    }
+
+    /// Queues `event` on this channel without waiting, applying the overflow policy when full.
+    pub fn try_send(&self, event: &WsEvent) -> Result<(), WsSendError> {
+        let frame = self.encode(event)?;
+        self.sender.try_send_frame(frame, event.priority())
+    }
+
+    fn encode(&self, event: &WsEvent) -> Result<axum::extract::ws::Message, WsSendError> {
+        let tagged = OnChannel {
+            channel: &self.name,
+            event,
+        };
+        self.sender.encode(&tagged)
+    }
+}
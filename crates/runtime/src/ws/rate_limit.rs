@@ -0,0 +1,84 @@
+// ./src/ws/rate_limit.rs
+//
+// Inbound message rate limiting for a single WebSocket connection.
+
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Close code sent when [`RateLimitAction::Close`] drops a client (RFC 6455 "Policy Violation").
+pub(crate) const RATE_LIMIT_CLOSE_CODE: u16 = 1008;
+
+/// What happens to a message that arrives over the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitAction {
+    /// Drop the message and report [`WsRecvError::RateLimited`](crate::ws::WsRecvError::RateLimited).
+    #[default]
+    Reject,
+    /// Report `RateLimited` once, then close the connection.
+    Close,
+}
+
+/// Token bucket applied to inbound data frames: `burst` messages at once,
+/// refilled at `per_second`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_second: f64,
+    pub burst: u32,
+    pub action: RateLimitAction,
+}
+
+impl RateLimit {
+    pub fn new(per_second: f64, burst: u32) -> Self {
+        Self {
+            per_second,
+            burst,
+            action: RateLimitAction::default(),
+        }
+    }
+
+    /// Close abusive connections instead of only rejecting their messages.
+    pub fn close_on_exceed(mut self) -> Self {
+        self.action = RateLimitAction::Close;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: f64::from(limit.burst),
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Takes a token for one message. `false` means the message is over the limit.
+    pub(crate) fn take(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.refilled_at = now;
+        self.tokens = (self.tokens + refill(elapsed, self.limit.per_second))
+            .min(f64::from(self.limit.burst.max(1)));
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn action(&self) -> RateLimitAction {
+        self.limit.action
+    }
+}
+
+fn refill(elapsed: Duration, per_second: f64) -> f64 {
+    elapsed.as_secs_f64() * per_second.max(0.0)
+}
@@ -0,0 +1,148 @@
+// ./src/ws/resume.rs
+//
+// Resumable sessions for WsHub: a client that drops and reconnects with its
+// token gets back its rooms and the events published while it was away.
+
+use crate::ws::ws::{ConnectionId, WsEvent};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Custom event carrying the session token, sent first on every resumable connection.
+pub const RESUME_EVENT: &str = "silcrow:resume";
+
+/// Query parameter a reconnecting client passes its token in.
+pub const RESUME_PARAM: &str = "resume";
+
+#[derive(Debug, Clone, Copy)]
+struct ResumeConfig {
+    buffer: usize,
+    ttl: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Session {
+    rooms: HashSet<String>,
+    missed: VecDeque<WsEvent>,
+    attached: Option<ConnectionId>,
+    detached_at: Option<Instant>,
+}
+
+impl Session {
+    fn expired(&self, ttl: Duration) -> bool {
+        self.detached_at.is_some_and(|at| at.elapsed() > ttl)
+    }
+}
+
+/// What a (re)attached connection must be given before anything else.
+#[derive(Debug)]
+pub(crate) struct Resumed {
+    pub(crate) token: String,
+    pub(crate) rooms: HashSet<String>,
+    pub(crate) missed: Vec<WsEvent>,
+}
+
+/// Session bookkeeping. Inert unless the hub was built with [`WsHub::resumable`](crate::WsHub::resumable).
+#[derive(Debug, Default)]
+pub(crate) struct Sessions {
+    config: Option<ResumeConfig>,
+    by_token: HashMap<String, Session>,
+    token_of: HashMap<ConnectionId, String>,
+}
+
+impl Sessions {
+    pub(crate) fn new(buffer: usize, ttl: Duration) -> Self {
+        Self {
+            config: Some(ResumeConfig { buffer, ttl }),
+            ..Self::default()
+        }
+    }
+
+    /// Binds `conn` to the session for `token` if it is still resumable,
+    /// otherwise to a fresh session. `None` if the hub is not resumable or
+    /// no token could be generated.
+    pub(crate) fn attach(&mut self, token: Option<&str>, conn: ConnectionId) -> Option<Resumed> {
+        let config = self.config?;
+        self.by_token
+            .retain(|_, session| !session.expired(config.ttl));
+
+        let resumable = token.filter(|token| {
+            self.by_token
+                .get(*token)
+                .is_some_and(|session| session.attached.is_none())
+        });
+        let token = match resumable {
+            Some(token) => token.to_owned(),
+            None => new_token()?,
+        };
+        let session = self.by_token.entry(token.clone()).or_default();
+        session.attached = Some(conn);
+        session.detached_at = None;
+        self.token_of.insert(conn, token.clone());
+
+        Some(Resumed {
+            token,
+            rooms: session.rooms.clone(),
+            missed: session.missed.drain(..).collect(),
+        })
+    }
+
+    /// Starts the grace period for the connection's session.
+    pub(crate) fn detach(&mut self, conn: ConnectionId) {
+        let Some(token) = self.token_of.remove(&conn) else {
+            return;
+        };
+        if let Some(session) = self.by_token.get_mut(&token) {
+            session.attached = None;
+            session.detached_at = Some(Instant::now());
+        }
+    }
+
+    pub(crate) fn joined(&mut self, room: &str, conn: ConnectionId) {
+        if let Some(session) = self.session_of(conn) {
+            session.rooms.insert(room.to_owned());
+        }
+    }
+
+    pub(crate) fn left(&mut self, room: &str, conn: ConnectionId) {
+        if let Some(session) = self.session_of(conn) {
+            session.rooms.remove(room);
+        }
+    }
+
+    /// Keeps `event` for detached sessions in its audience: everyone when
+    /// `room` is `None`, otherwise the sessions that had joined `room`.
+    pub(crate) fn record_missed(&mut self, room: Option<&str>, event: &WsEvent) {
+        let Some(config) = self.config else {
+            return;
+        };
+        for session in self.by_token.values_mut() {
+            let in_audience = room.is_none_or(|room| session.rooms.contains(room));
+            if session.attached.is_some() || !in_audience || session.expired(config.ttl) {
+                continue;
+            }
+            if session.missed.len() >= config.buffer.max(1) {
+                session.missed.pop_front();
+            }
+            session.missed.push_back(event.clone());
+        }
+    }
+
+    fn session_of(&mut self, conn: ConnectionId) -> Option<&mut Session> {
+        let token = self.token_of.get(&conn)?;
+        self.by_token.get_mut(token)
+    }
+}
+
+/// 128 bits from the OS CSPRNG, hex-encoded. The token alone resumes a
+/// session, so it must not be guessable. `None` when the OS has no
+/// randomness to give; the connection then goes without a session rather
+/// than with a weak token.
+fn new_token() -> Option<String> {
+    let mut bytes = [0u8; 16];
+    if let Err(error) = getrandom::getrandom(&mut bytes) {
+        tracing::error!(%error, "OS randomness unavailable; connection is not resumable");
+        return None;
+    }
+    Some(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
@@ -0,0 +1,425 @@
+// ./src/ws/sender.rs
+//
+// Bounded outbound queue for one WebSocket connection. A slow client fills
+// only its own queue; the overflow policy decides what gives way.
+
+use crate::live::{EventPriority, Prioritized};
+use crate::ws::codec::WsFormat;
+use crate::ws::metrics::WsMetrics;
+use crate::ws::ws::WsEvent;
+use axum::extract::ws::{CloseFrame, Message};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Close code sent when [`OverflowPolicy::Disconnect`] drops a client (RFC 6455 "Try Again Later").
+const OVERFLOW_CLOSE_CODE: u16 = 1013;
+
+/// What happens when a frame arrives for a connection whose queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Discard the oldest queued frame to make room.
+    DropOldest,
+    /// Discard the incoming frame; [`WsSendError::Full`] is returned.
+    DropNewest,
+    /// Close the connection; the client is expected to reconnect and resync.
+    #[default]
+    Disconnect,
+    /// Write navigation and toasts ahead of patches, and patches ahead of
+    /// HTML frames. When full, the oldest queued frame of lower priority than
+    /// the incoming one is discarded; if there is none, the incoming frame is
+    /// dropped and [`WsSendError::Full`] is returned.
+    ByPriority,
+}
+
+/// Why a frame was not queued.
+#[derive(Debug)]
+pub enum WsSendError {
+    /// The queue is full and the policy dropped the frame.
+    Full,
+    /// The connection is closed (or was just closed by the overflow policy).
+    Closed,
+    /// The event could not be encoded in the connection's wire format.
+    Encode(axum::Error),
+}
+
+impl std::fmt::Display for WsSendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "WsSendError::Full"),
+            Self::Closed => write!(f, "WsSendError::Closed"),
+            Self::Encode(e) => write!(f, "WsSendError::Encode: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for WsSendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Encode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    frames: VecDeque<(EventPriority, Message)>,
+    /// A Close frame has been queued; nothing may follow it.
+    closing: bool,
+    receiver_alive: bool,
+}
+
+#[derive(Debug)]
+struct Shared {
+    queue: Mutex<Queue>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    format: WsFormat,
+    senders: AtomicUsize,
+    frame_ready: Notify,
+    space_ready: Notify,
+    metrics: OnceLock<WsMetrics>,
+}
+
+impl Queue {
+    /// Next frame to write: the oldest, or under [`OverflowPolicy::ByPriority`]
+    /// the oldest of the highest priority.
+    fn pop(&mut self, policy: OverflowPolicy) -> Option<Message> {
+        let index = match policy {
+            OverflowPolicy::ByPriority => {
+                let highest = self.frames.iter().map(|(priority, _)| *priority).max()?;
+                self.frames
+                    .iter()
+                    .position(|(priority, _)| *priority == highest)?
+            }
+            _ => 0,
+        };
+        self.frames.remove(index).map(|(_, frame)| frame)
+    }
+
+    /// Drops the oldest queued frame below `priority`, if any.
+    fn evict_below(&mut self, priority: EventPriority) -> bool {
+        match self
+            .frames
+            .iter()
+            .position(|(queued, _)| *queued < priority)
+        {
+            Some(index) => self.frames.remove(index).is_some(),
+            None => false,
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record_send_error(&self) {
+        if let Some(metrics) = self.metrics.get() {
+            metrics.record_send_error();
+        }
+    }
+}
+
+/// Creates the queue feeding a connection's writer task.
+pub(crate) fn outbound_queue(
+    capacity: usize,
+    policy: OverflowPolicy,
+    format: WsFormat,
+) -> (WsSender, OutboundFrames) {
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            receiver_alive: true,
+            ..Queue::default()
+        }),
+        capacity: capacity.max(1),
+        policy,
+        format,
+        senders: AtomicUsize::new(1),
+        frame_ready: Notify::new(),
+        space_ready: Notify::new(),
+        metrics: OnceLock::new(),
+    });
+    (
+        WsSender {
+            shared: shared.clone(),
+        },
+        OutboundFrames { shared },
+    )
+}
+
+/// Cloneable handle that queues events for one connection without blocking on
+/// the socket. Get one from [`WsStream::sender`](crate::WsStream::sender).
+pub struct WsSender {
+    shared: Arc<Shared>,
+}
+
+impl WsSender {
+    /// Queues `event` without waiting, applying the overflow policy when full.
+    pub fn try_send(&self, event: &WsEvent) -> Result<(), WsSendError> {
+        let frame = self.encode(event)?;
+        self.try_send_frame(frame, event.priority())
+    }
+
+    /// Waits up to `timeout` for queue space, then applies the overflow policy.
+    pub async fn send_timeout(
+        &self,
+        event: &WsEvent,
+        timeout: Duration,
+    ) -> Result<(), WsSendError> {
+        let frame = self.encode(event)?;
+        let _ = tokio::time::timeout(timeout, self.wait_for_space()).await;
+        self.try_send_frame(frame, event.priority())
+    }
+
+    /// Waits for queue space without limit, then queues `event`.
+    pub async fn send(&self, event: &WsEvent) -> Result<(), WsSendError> {
+        let frame = self.encode(event)?;
+        self.send_frame(frame, event.priority()).await
+    }
+
+    /// Encodes `value` in this connection's wire format.
+    pub(crate) fn encode<T>(&self, value: &T) -> Result<Message, WsSendError>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.shared.format.encode(value).map_err(|e| {
+            self.shared.record_send_error();
+            WsSendError::Encode(e)
+        })
+    }
+
+    /// Waits for queue space without limit, then queues an already-encoded frame.
+    pub(crate) async fn send_frame(
+        &self,
+        frame: Message,
+        priority: EventPriority,
+    ) -> Result<(), WsSendError> {
+        self.wait_for_space().await;
+        self.try_send_frame(frame, priority)
+    }
+
+    /// Wire format frames are encoded in.
+    pub fn format(&self) -> WsFormat {
+        self.shared.format
+    }
+
+    /// Number of frames waiting to be written.
+    pub fn queued(&self) -> usize {
+        self.shared.lock().frames.len()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        let queue = self.shared.lock();
+        queue.closing || !queue.receiver_alive
+    }
+
+    /// Resolves once the writer task has stopped: the Close frame has been
+    /// written, or the socket failed.
+    pub(crate) async fn closed(&self) {
+        loop {
+            let stopped = self.shared.space_ready.notified();
+            if !self.shared.lock().receiver_alive {
+                return;
+            }
+            stopped.await;
+        }
+    }
+
+    async fn wait_for_space(&self) {
+        loop {
+            let space = self.shared.space_ready.notified();
+            {
+                let queue = self.shared.lock();
+                if queue.frames.len() < self.shared.capacity
+                    || queue.closing
+                    || !queue.receiver_alive
+                {
+                    return;
+                }
+            }
+            space.await;
+        }
+    }
+
+    /// Queues an already-encoded frame, applying the overflow policy when full.
+    pub(crate) fn try_send_frame(
+        &self,
+        frame: Message,
+        priority: EventPriority,
+    ) -> Result<(), WsSendError> {
+        let result = self.enqueue(frame, priority);
+        if result.is_err() {
+            self.shared.record_send_error();
+        }
+        result
+    }
+
+    fn enqueue(&self, frame: Message, priority: EventPriority) -> Result<(), WsSendError> {
+        {
+            let mut queue = self.shared.lock();
+            if queue.closing || !queue.receiver_alive {
+                return Err(WsSendError::Closed);
+            }
+            if queue.frames.len() >= self.shared.capacity {
+                match self.shared.policy {
+                    OverflowPolicy::DropOldest => {
+                        queue.frames.pop_front();
+                    }
+                    OverflowPolicy::DropNewest => return Err(WsSendError::Full),
+                    OverflowPolicy::ByPriority => {
+                        if !queue.evict_below(priority) {
+                            return Err(WsSendError::Full);
+                        }
+                    }
+                    OverflowPolicy::Disconnect => {
+                        queue.frames.clear();
+                        queue.frames.push_back((
+                            EventPriority::Low,
+                            Message::Close(Some(CloseFrame {
+                                code: OVERFLOW_CLOSE_CODE,
+                                reason: "outbound queue overflow".into(),
+                            })),
+                        ));
+                        queue.closing = true;
+                        drop(queue);
+                        tracing::debug!("WebSocket outbound queue overflowed — disconnecting");
+                        self.shared.frame_ready.notify_one();
+                        self.shared.space_ready.notify_waiters();
+                        return Err(WsSendError::Closed);
+                    }
+                }
+            }
+            queue.frames.push_back((priority, frame));
+            if let Some(metrics) = self.shared.metrics.get() {
+                metrics.record_queue_depth(queue.frames.len());
+            }
+        }
+        self.shared.frame_ready.notify_one();
+        Ok(())
+    }
+
+    /// Starts reporting this connection to `metrics`. The first handle attached wins.
+    pub(crate) fn attach_metrics(&self, metrics: &WsMetrics) {
+        let queue = self.shared.lock();
+        if queue.receiver_alive && self.shared.metrics.set(metrics.clone()).is_ok() {
+            metrics.connection_opened();
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> Option<&WsMetrics> {
+        self.shared.metrics.get()
+    }
+
+    /// Queues a Ping if there is room. Returns `false` once the connection is closed.
+    pub(crate) fn ping(&self) -> bool {
+        {
+            let mut queue = self.shared.lock();
+            if queue.closing || !queue.receiver_alive {
+                return false;
+            }
+            if queue.frames.len() < self.shared.capacity {
+                queue
+                    .frames
+                    .push_back((EventPriority::High, Message::Ping(Vec::new())));
+            }
+        }
+        self.shared.frame_ready.notify_one();
+        true
+    }
+
+    /// Queues a Close frame behind pending frames, regardless of capacity.
+    /// It has the lowest priority, so it is written last under every policy.
+    /// Later sends fail with [`WsSendError::Closed`].
+    pub(crate) fn close(&self, frame: Option<CloseFrame<'static>>) -> bool {
+        {
+            let mut queue = self.shared.lock();
+            if queue.closing || !queue.receiver_alive {
+                return false;
+            }
+            queue
+                .frames
+                .push_back((EventPriority::Low, Message::Close(frame)));
+            queue.closing = true;
+        }
+        self.shared.frame_ready.notify_one();
+        self.shared.space_ready.notify_waiters();
+        true
+    }
+}
+
+impl Clone for WsSender {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::AcqRel);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for WsSender {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.frame_ready.notify_one();
+        }
+    }
+}
+
+impl std::fmt::Debug for WsSender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WsSender")
+            .field("capacity", &self.shared.capacity)
+            .field("policy", &self.shared.policy)
+            .field("format", &self.shared.format)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Writer-side half of [`outbound_queue`].
+pub(crate) struct OutboundFrames {
+    shared: Arc<Shared>,
+}
+
+impl OutboundFrames {
+    /// Next frame to write. `None` once every sender is gone and the queue is drained.
+    pub(crate) async fn recv(&mut self) -> Option<Message> {
+        loop {
+            let ready = self.shared.frame_ready.notified();
+            let popped = self.shared.lock().pop(self.shared.policy);
+            if let Some(frame) = popped {
+                self.shared.space_ready.notify_waiters();
+                return Some(frame);
+            }
+            if self.shared.senders.load(Ordering::Acquire) == 0 {
+                return None;
+            }
+            ready.await;
+        }
+    }
+
+    /// Records the outcome of writing a data frame to the socket.
+    pub(crate) fn record_written(&self, written: bool) {
+        if let Some(metrics) = self.shared.metrics.get() {
+            match written {
+                true => metrics.record_sent(),
+                false => metrics.record_send_error(),
+            }
+        }
+    }
+}
+
+impl Drop for OutboundFrames {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_alive = false;
+        self.shared.space_ready.notify_waiters();
+        if let Some(metrics) = self.shared.metrics.get() {
+            metrics.connection_closed();
+        }
+    }
+}
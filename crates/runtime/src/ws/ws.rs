@@ -1,18 +1,39 @@
 // ./src/ws.rs
 
-use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use crate::live::{EventPriority, Prioritized};
+use crate::response::{JsonPatchOp, ToastLevel};
+use crate::ws::codec::{self, WsFormat};
+use crate::ws::intercept::WsInterceptor;
+use crate::ws::metrics::WsMetrics;
+use crate::ws::mux::ChannelFrame;
+use crate::ws::rate_limit::{RATE_LIMIT_CLOSE_CODE, RateLimit, RateLimitAction, TokenBucket};
+use crate::ws::sender::{OutboundFrames, OverflowPolicy, WsSendError, WsSender, outbound_queue};
+use axum::extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade};
 use axum::response::{IntoResponse, Response};
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use std::borrow::Cow;
 use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
 
 crate::define_route!(WsRoute, "WebSocket", "/ws/chat", "CHAT");
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum WsEvent {
     Patch {
         target: String,
         data: serde_json::Value,
     },
+    /// RFC 6902 operations applied to the data last patched into `target`.
+    JsonPatch {
+        target: String,
+        ops: Vec<JsonPatchOp>,
+    },
     Html {
         target: String,
         markup: String,
@@ -27,6 +48,20 @@ pub enum WsEvent {
         event: String,
         data: serde_json::Value,
     },
+    /// Same as `ResponseExt::with_toast`.
+    Toast {
+        message: String,
+        level: ToastLevel,
+    },
+    /// Same as `ResponseExt::trigger_event`: a DOM event dispatched on `document`.
+    Trigger {
+        event: String,
+        data: serde_json::Value,
+    },
+    /// Same as `ResponseExt::push_history`: updates the URL without navigating.
+    PushHistory {
+        url: String,
+    },
 }
 
 impl WsEvent {
@@ -38,6 +73,13 @@ impl WsEvent {
         }
     }
 
+    pub fn json_patch(ops: impl IntoIterator<Item = JsonPatchOp>, target: &str) -> Self {
+        Self::JsonPatch {
+            target: target.to_owned(),
+            ops: ops.into_iter().collect(),
+        }
+    }
+
     pub fn html(markup: impl Into<String>, target: &str) -> Self {
         Self::Html {
             target: target.to_owned(),
@@ -62,21 +104,95 @@ impl WsEvent {
             data: value,
         }
     }
+
+    pub fn toast(message: impl Into<String>, level: ToastLevel) -> Self {
+        Self::Toast {
+            message: message.into(),
+            level,
+        }
+    }
+
+    pub fn trigger(event: impl Into<String>, data: impl serde::Serialize) -> Self {
+        let value = crate::serialize_or_null(data, "WsEvent::trigger");
+        Self::Trigger {
+            event: event.into(),
+            data: value,
+        }
+    }
+
+    pub fn push_history(url: impl Into<String>) -> Self {
+        Self::PushHistory { url: url.into() }
+    }
+
+    /// Like `custom`, but surfaces serialization failures instead of sending `null`.
+    pub fn custom_typed<T: serde::Serialize>(
+        event: impl Into<String>,
+        data: &T,
+    ) -> Result<Self, serde_json::Error> {
+        serde_json::to_value(data).map(|data| Self::Custom {
+            event: event.into(),
+            data,
+        })
+    }
+
+    /// Name of a `Custom` event; `None` for every other variant.
+    pub fn custom_name(&self) -> Option<&str> {
+        match self {
+            Self::Custom { event, .. } => Some(event),
+            _ => None,
+        }
+    }
+
+    /// Decodes the payload of a `Custom` event into `T`; `None` for every other variant.
+    pub fn decode_custom<T: serde::de::DeserializeOwned>(
+        &self,
+    ) -> Option<Result<T, serde_json::Error>> {
+        match self {
+            Self::Custom { data, .. } => Some(T::deserialize(data)),
+            _ => None,
+        }
+    }
 }
 
+/// Why [`WsStream::recv`] produced no event.
+///
+/// Non-exhaustive: some variants only exist with a feature enabled, so
+/// matches need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum WsRecvError {
     Deserialize(serde_json::Error),
-    Closed,
+    /// The client closed the connection. `code` is `None` when its Close frame had no payload.
+    Closed {
+        code: Option<u16>,
+        reason: String,
+    },
     NonText,
+    /// The client exceeded the connection's [`RateLimit`]; the message was dropped.
+    RateLimited,
+    /// Nothing arrived before the `recv_timeout` / `recv_until` deadline.
+    Timeout,
+    /// An interceptor refused the event, e.g. because it failed validation.
+    Rejected(String),
+    #[cfg(feature = "msgpack")]
+    MessagePack(rmp_serde::decode::Error),
 }
 
 impl std::fmt::Display for WsRecvError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Deserialize(e) => write!(f, "WsRecvError::Deserialize: {e}"),
-            Self::Closed => write!(f, "WsRecvError::Closed"),
+            Self::Closed {
+                code: Some(code),
+                reason,
+            } => write!(f, "WsRecvError::Closed: {code} {reason}"),
+            Self::Closed { code: None, .. } => write!(f, "WsRecvError::Closed"),
             Self::NonText => write!(f, "WsRecvError::NonText"),
+            Self::RateLimited => write!(f, "WsRecvError::RateLimited"),
+            Self::Timeout => write!(f, "WsRecvError::Timeout"),
+            Self::Rejected(reason) => write!(f, "WsRecvError::Rejected: {reason}"),
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack(e) => write!(f, "WsRecvError::MessagePack: {e}"),
         }
     }
 }
@@ -85,51 +201,495 @@ impl std::error::Error for WsRecvError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::Deserialize(e) => Some(e),
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack(e) => Some(e),
             _ => None,
         }
     }
 }
 
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Process-unique identifier of a WebSocket connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConnectionId(u64);
+
+impl ConnectionId {
+    fn next() -> Self {
+        Self(NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ConnectionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ws-{}", self.0)
+    }
+}
+
+/// Inbound frames buffered by the reader task before the handler picks them up.
+const INBOUND_BUFFER: usize = 64;
+
+/// When the client was last heard from (any frame, including pongs).
 #[derive(Debug)]
+struct Activity(Mutex<Instant>);
+
+impl Activity {
+    fn new() -> Self {
+        Self(Mutex::new(Instant::now()))
+    }
+
+    fn touch(&self) {
+        if let Ok(mut last) = self.0.lock() {
+            *last = Instant::now();
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.0.lock().map(|last| last.elapsed()).unwrap_or_default()
+    }
+}
+
 pub struct WsStream {
-    socket: WebSocket,
+    id: ConnectionId,
+    outbound: WsSender,
+    inbound: mpsc::Receiver<Inbound>,
+    activity: Arc<Activity>,
+    format: WsFormat,
+    interceptors: Vec<Arc<dyn WsInterceptor>>,
+}
+
+/// What the reader task hands to `recv`.
+#[derive(Debug)]
+enum Inbound {
+    Frame(Message),
+    RateLimited(RateLimitAction),
+}
+
+impl std::fmt::Debug for WsStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WsStream")
+            .field("id", &self.id)
+            .field("format", &self.format)
+            .field("interceptors", &self.interceptors.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Drains queued frames into the socket until every sender is gone or a Close is sent.
+async fn write_loop(mut sink: SplitSink<WebSocket, Message>, mut rx: OutboundFrames) {
+    while let Some(message) = rx.recv().await {
+        let closing = matches!(message, Message::Close(_));
+        let data = matches!(message, Message::Text(_) | Message::Binary(_));
+        let written = sink.send(message).await.is_ok();
+        if data {
+            rx.record_written(written);
+        }
+        if !written || closing {
+            break;
+        }
+    }
+    let _ = sink.close().await;
+}
+
+/// Reads frames off the socket, recording liveness and forwarding everything
+/// but control frames to the handler. Stops on the first transport error.
+///
+/// Data frames are rate limited on arrival, so a handler that is slow to call
+/// `recv` does not penalise the client for messages already buffered.
+async fn read_loop(
+    mut stream: SplitStream<WebSocket>,
+    tx: mpsc::Sender<Inbound>,
+    activity: Arc<Activity>,
+    mut limiter: Option<TokenBucket>,
+) {
+    while let Some(frame) = stream.next().await {
+        let message = match frame {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::debug!("WebSocket read failed: {e}");
+                break;
+            }
+        };
+        activity.touch();
+        if matches!(message, Message::Ping(_) | Message::Pong(_)) {
+            continue;
+        }
+        let throttled = match limiter.as_mut() {
+            Some(bucket) if !matches!(message, Message::Close(_)) => {
+                (!bucket.take()).then(|| bucket.action())
+            }
+            _ => None,
+        };
+        let inbound = match throttled {
+            Some(action) => Inbound::RateLimited(action),
+            None => Inbound::Frame(message),
+        };
+        let stop = matches!(inbound, Inbound::RateLimited(RateLimitAction::Close));
+        if tx.send(inbound).await.is_err() || stop {
+            break;
+        }
+    }
 }
 
 impl WsStream {
     /// Wrap an Axum WebSocket in a typed Silcrow stream.
+    ///
+    /// Frames are read and written by background tasks, so hubs can push to
+    /// this connection and heartbeats keep running while the handler is busy.
+    ///
+    /// The wire format follows the subprotocol chosen during the handshake.
+    /// Outbound frames are queued with the default [`WsOptions`] capacity and policy.
     pub fn new(socket: WebSocket) -> Self {
-        Self { socket }
+        Self::with_options(socket, &WsOptions::default())
+    }
+
+    /// Like [`WsStream::new`], sizing the outbound queue from `options`.
+    pub fn with_options(socket: WebSocket, options: &WsOptions) -> Self {
+        let format = WsFormat::from_protocol(socket.protocol());
+        let (sink, stream) = socket.split();
+        let (outbound, outbound_rx) =
+            outbound_queue(options.queue_capacity, options.overflow, format);
+        let (inbound_tx, inbound) = mpsc::channel(INBOUND_BUFFER);
+        let activity = Arc::new(Activity::new());
+        if let Some(metrics) = &options.metrics {
+            outbound.attach_metrics(metrics);
+        }
+        tokio::spawn(write_loop(sink, outbound_rx));
+        let limiter = options.rate_limit.map(TokenBucket::new);
+        tokio::spawn(read_loop(stream, inbound_tx, activity.clone(), limiter));
+        Self {
+            id: ConnectionId::next(),
+            outbound,
+            inbound,
+            activity,
+            format,
+            interceptors: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> ConnectionId {
+        self.id
+    }
+
+    /// Wire format negotiated for this connection.
+    pub fn format(&self) -> WsFormat {
+        self.format
+    }
+
+    /// Cloneable handle for queueing events from other tasks, e.g. a broadcast loop.
+    pub fn sender(&self) -> WsSender {
+        self.outbound.clone()
+    }
+
+    /// Adds an interceptor around this stream's `send` and `recv`.
+    pub fn with_interceptor(mut self, interceptor: impl WsInterceptor) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
     }
 
+    /// Queues `event`, waiting while this connection's bounded outbound queue
+    /// is full.
+    ///
+    /// `Ok` means the frame was queued, not that it reached the socket: a
+    /// background task writes it. A failed write closes the connection, and
+    /// from then on `send` returns an error.
     pub async fn send(&mut self, event: WsEvent) -> Result<(), axum::Error> {
-        match serde_json::to_string(&event) {
-            Ok(json) => self.socket.send(Message::Text(json)).await,
-            Err(e) => {
+        let Some(event) = self
+            .interceptors
+            .iter()
+            .try_fold(event, |event, interceptor| interceptor.outbound(event))
+        else {
+            return Ok(());
+        };
+        match self.outbound.send(&event).await {
+            Ok(()) => Ok(()),
+            Err(WsSendError::Encode(e)) => {
                 tracing::warn!("WsStream::send serialization failed: {e}");
-                Err(axum::Error::new(e))
+                Err(e)
+            }
+            Err(_) => Err(axum::Error::new("WebSocket connection closed")),
+        }
+    }
+
+    /// Sends `events` as a single frame (a JSON array), so a patch, toast and
+    /// trigger arrive together. Interceptors see each event; nothing is sent
+    /// if they drop them all.
+    pub async fn send_all(&mut self, events: Vec<WsEvent>) -> Result<(), axum::Error> {
+        let events: Vec<WsEvent> = events
+            .into_iter()
+            .filter_map(|event| {
+                self.interceptors
+                    .iter()
+                    .try_fold(event, |event, interceptor| interceptor.outbound(event))
+            })
+            .collect();
+        if events.is_empty() {
+            return Ok(());
+        }
+        // The batch goes out as one frame, at the priority of its most urgent event.
+        let priority = events
+            .iter()
+            .map(Prioritized::priority)
+            .max()
+            .unwrap_or(EventPriority::Normal);
+        let result = match self.outbound.encode(&events) {
+            Ok(frame) => self.outbound.send_frame(frame, priority).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(WsSendError::Encode(e)) => {
+                tracing::warn!("WsStream::send_all serialization failed: {e}");
+                Err(e)
             }
+            Err(_) => Err(axum::Error::new("WebSocket connection closed")),
         }
     }
+
     pub async fn recv(&mut self) -> Option<Result<WsEvent, WsRecvError>> {
+        let received = self.recv_channel().await?;
+        Some(received.map(|(_, event)| event))
+    }
+
+    /// Like [`WsStream::recv`], also returning the `channel` the client tagged
+    /// the event with. See [`WsMux`](crate::ws::WsMux).
+    pub async fn recv_channel(&mut self) -> Option<Result<(Option<String>, WsEvent), WsRecvError>> {
+        let received = self.recv_frame().await?;
+        Some(received.and_then(|ChannelFrame { channel, event }| {
+            let event = self
+                .interceptors
+                .iter()
+                .try_fold(event, |event, interceptor| interceptor.inbound(event))?;
+            Ok((channel, event))
+        }))
+    }
+
+    async fn recv_frame(&mut self) -> Option<Result<ChannelFrame, WsRecvError>> {
         loop {
-            match self.socket.recv().await {
-                None => return None,
-                Some(Err(_)) => return None,
-                Some(Ok(msg)) => match msg {
-                    Message::Text(text) => {
-                        return Some(serde_json::from_str(&text).map_err(WsRecvError::Deserialize));
+            let message = match self.inbound.recv().await? {
+                Inbound::Frame(message) => message,
+                Inbound::RateLimited(action) => {
+                    if action == RateLimitAction::Close {
+                        tracing::debug!("WebSocket {} exceeded its rate limit — closing", self.id);
+                        self.outbound.close(Some(CloseFrame {
+                            code: RATE_LIMIT_CLOSE_CODE,
+                            reason: "rate limit exceeded".into(),
+                        }));
                     }
-                    Message::Close(_) => return Some(Err(WsRecvError::Closed)),
-                    Message::Ping(_) | Message::Pong(_) => continue,
-                    Message::Binary(_) => return Some(Err(WsRecvError::NonText)),
-                },
+                    return Some(Err(WsRecvError::RateLimited));
+                }
+            };
+            if let (Message::Text(_) | Message::Binary(_), Some(metrics)) =
+                (&message, self.outbound.metrics())
+            {
+                metrics.record_received();
+            }
+            match message {
+                Message::Text(text) => {
+                    return Some(serde_json::from_str(&text).map_err(WsRecvError::Deserialize));
+                }
+                Message::Close(frame) => {
+                    let (code, reason) = frame
+                        .map(|frame| (Some(frame.code), frame.reason.into_owned()))
+                        .unwrap_or_default();
+                    return Some(Err(WsRecvError::Closed { code, reason }));
+                }
+                Message::Ping(_) | Message::Pong(_) => continue,
+                Message::Binary(bytes) => return Some(self.decode_binary(&bytes)),
+            }
+        }
+    }
+
+    /// Like [`WsStream::recv`], giving up with [`WsRecvError::Timeout`] after `timeout`.
+    ///
+    /// Nothing is lost on timeout, so handlers can loop on this to interleave
+    /// periodic work with receiving.
+    pub async fn recv_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Option<Result<WsEvent, WsRecvError>> {
+        self.recv_until(Instant::now() + timeout).await
+    }
+
+    /// Like [`WsStream::recv`], giving up with [`WsRecvError::Timeout`] at `deadline`.
+    pub async fn recv_until(
+        &mut self,
+        deadline: impl Into<Instant>,
+    ) -> Option<Result<WsEvent, WsRecvError>> {
+        tokio::time::timeout_at(deadline.into(), self.recv())
+            .await
+            .unwrap_or(Some(Err(WsRecvError::Timeout)))
+    }
+
+    fn decode_binary(&self, bytes: &[u8]) -> Result<ChannelFrame, WsRecvError> {
+        match self.format {
+            #[cfg(feature = "msgpack")]
+            WsFormat::MessagePack => rmp_serde::from_slice(bytes).map_err(WsRecvError::MessagePack),
+            _ => {
+                let _ = bytes;
+                Err(WsRecvError::NonText)
             }
         }
     }
 
-    /// Gracefully close the WebSocket connection.
-    pub async fn close(mut self) {
-        let _ = self.socket.send(Message::Close(None)).await;
+    /// Gracefully close the WebSocket connection. Resolves once everything
+    /// queued before it and the Close frame have been written.
+    pub async fn close(self) {
+        self.outbound.close(None);
+        self.outbound.closed().await;
+    }
+
+    /// Close the connection with a status `code` (e.g. 1000 normal, 1008 policy
+    /// violation, 4000–4999 app-defined) and a short `reason`. Resolves like
+    /// [`WsStream::close`].
+    pub async fn close_with(self, code: u16, reason: impl Into<Cow<'static, str>>) {
+        self.outbound.close(Some(CloseFrame {
+            code,
+            reason: reason.into(),
+        }));
+        self.outbound.closed().await;
+    }
+}
+
+/// Connection options for [`ws_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WsOptions {
+    /// How often a Ping is sent to the client. `Duration::ZERO` turns pings,
+    /// and with them dead-connection detection, off.
+    pub heartbeat: Duration,
+    /// How long the client may stay silent (no frames, no pongs) before the
+    /// connection is considered dead and the handler is terminated.
+    pub idle_timeout: Duration,
+    /// Frames that may wait for the socket before the overflow policy applies.
+    pub queue_capacity: usize,
+    pub overflow: OverflowPolicy,
+    /// Largest message accepted from the client; bigger ones end the connection.
+    /// `None` keeps Axum's default (64 MiB).
+    pub max_message_size: Option<usize>,
+    /// Largest single frame accepted from the client. `None` keeps Axum's default (16 MiB).
+    pub max_frame_size: Option<usize>,
+    /// App-defined subprotocols accepted in addition to the Silcrow wire formats.
+    pub protocols: Vec<String>,
+    /// Inbound message rate limit. `None` accepts messages as fast as they arrive.
+    pub rate_limit: Option<RateLimit>,
+    /// Counters this connection reports into.
+    pub metrics: Option<WsMetrics>,
+}
+
+impl Default for WsOptions {
+    fn default() -> Self {
+        Self {
+            heartbeat: Duration::from_secs(30),
+            idle_timeout: Duration::from_secs(90),
+            queue_capacity: 1024,
+            overflow: OverflowPolicy::default(),
+            max_message_size: None,
+            max_frame_size: None,
+            protocols: Vec::new(),
+            rate_limit: None,
+            metrics: None,
+        }
+    }
+}
+
+impl WsOptions {
+    pub fn heartbeat(mut self, every: Duration) -> Self {
+        self.heartbeat = every;
+        self
+    }
+
+    pub fn idle_timeout(mut self, after: Duration) -> Self {
+        self.idle_timeout = after;
+        self
+    }
+
+    pub fn queue(mut self, capacity: usize, overflow: OverflowPolicy) -> Self {
+        self.queue_capacity = capacity;
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
+
+    pub fn max_frame_size(mut self, bytes: usize) -> Self {
+        self.max_frame_size = Some(bytes);
+        self
+    }
+
+    pub fn metrics(mut self, metrics: WsMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    pub fn protocols<I>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.protocols = protocols.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Forwards frame limits and subprotocols from `options` to Axum's upgrade.
+pub(crate) fn configure(upgrade: WebSocketUpgrade, options: &WsOptions) -> WebSocketUpgrade {
+    let upgrade = codec::negotiate(upgrade, &options.protocols);
+    let upgrade = match options.max_message_size {
+        Some(bytes) => upgrade.max_message_size(bytes),
+        None => upgrade,
+    };
+    match options.max_frame_size {
+        Some(bytes) => upgrade.max_frame_size(bytes),
+        None => upgrade,
+    }
+}
+
+/// Pings the client every `heartbeat` and resolves once it has been silent
+/// for longer than `idle_timeout`, or the socket writer is gone.
+async fn watch_liveness(outbound: WsSender, activity: Arc<Activity>, options: &WsOptions) {
+    if options.heartbeat.is_zero() {
+        return std::future::pending().await;
+    }
+    let mut ticks = tokio::time::interval(options.heartbeat);
+    ticks.tick().await;
+    loop {
+        ticks.tick().await;
+        if activity.elapsed() > options.idle_timeout {
+            return;
+        }
+        if !outbound.ping() {
+            return;
+        }
+    }
+}
+
+/// Runs `handler` on `stream`, terminating it when the heartbeat detects a dead peer.
+pub(crate) async fn run_with_heartbeat<F, Fut>(stream: WsStream, options: WsOptions, handler: F)
+where
+    F: FnOnce(WsStream) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let id = stream.id;
+    let outbound = stream.sender();
+    let liveness = watch_liveness(outbound.clone(), stream.activity.clone(), &options);
+    tokio::select! {
+        _ = handler(stream) => {}
+        _ = liveness => {
+            tracing::debug!("WebSocket {id} missed heartbeats — closing");
+            outbound.close(None);
+        }
     }
 }
 
@@ -138,9 +698,26 @@ where
     F: FnOnce(WsStream) -> Fut + Send + 'static,
     Fut: Future<Output = ()> + Send + 'static,
 {
-    upgrade
+    codec::negotiate(upgrade, &[])
         .on_upgrade(|socket| async move {
             handler(WsStream::new(socket)).await;
         })
         .into_response()
 }
+
+/// Like [`ws`], with heartbeat pings, dead-connection detection, and the
+/// frame limits, subprotocols and queue settings from `options`.
+///
+/// When the client stays silent for `idle_timeout`, the socket is closed and
+/// the handler future is dropped.
+pub fn ws_with<F, Fut>(upgrade: WebSocketUpgrade, options: WsOptions, handler: F) -> Response
+where
+    F: FnOnce(WsStream) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    configure(upgrade, &options)
+        .on_upgrade(move |socket| {
+            run_with_heartbeat(WsStream::with_options(socket, &options), options, handler)
+        })
+        .into_response()
+}
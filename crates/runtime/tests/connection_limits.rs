@@ -0,0 +1,221 @@
+// tests/connection_limits.rs
+//
+// Per-identity live connection limits.
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::FromRequestParts;
+use axum::http::{Request, StatusCode};
+use axum::response::IntoResponse;
+use futures_util::StreamExt;
+use runtime::live::{ConnectionLimiter, LimitPolicy, PilcrowLive};
+use runtime::{SilcrowRequest, SseBroadcaster, SseRoute, WsHub, WsRoute};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tower::ServiceExt;
+
+#[test]
+fn reject_policy_refuses_excess() {
+    let limiter = ConnectionLimiter::new(2);
+    let _a = limiter.acquire("10.0.0.1").unwrap();
+    let _b = limiter.acquire("10.0.0.1").unwrap();
+    let err = limiter.acquire("10.0.0.1").unwrap_err();
+    assert_eq!(err.limit, 2);
+    assert_eq!(limiter.active("10.0.0.1"), 2);
+
+    // Other identities are unaffected.
+    assert!(limiter.acquire("10.0.0.2").is_ok());
+}
+
+#[test]
+fn dropping_permit_frees_slot() {
+    let limiter = ConnectionLimiter::new(1);
+    let first = limiter.acquire("session-1").unwrap();
+    assert!(limiter.acquire("session-1").is_err());
+    drop(first);
+    assert_eq!(limiter.active("session-1"), 0);
+    assert!(limiter.acquire("session-1").is_ok());
+}
+
+#[tokio::test]
+async fn supersede_policy_signals_oldest() {
+    let limiter = ConnectionLimiter::with_policy(1, LimitPolicy::SupersedeOldest);
+    let mut oldest = limiter.acquire("user-7").unwrap();
+    let newest = limiter.acquire("user-7").unwrap();
+
+    tokio::time::timeout(std::time::Duration::from_secs(1), oldest.superseded())
+        .await
+        .expect("oldest connection should be superseded");
+    assert!(oldest.is_superseded());
+    assert!(!newest.is_superseded());
+    assert_eq!(limiter.active("user-7"), 1);
+
+    // Dropping the superseded permit must not free the newer slot.
+    drop(oldest);
+    assert_eq!(limiter.active("user-7"), 1);
+}
+
+#[tokio::test]
+async fn rejection_is_negotiated() {
+    let limiter = ConnectionLimiter::new(1);
+    let _held = limiter.acquire("ip").unwrap();
+    let err = limiter.acquire("ip").unwrap_err();
+
+    let (mut parts, _) = Request::get("/ws")
+        .header("accept", "text/html")
+        .body(())
+        .unwrap()
+        .into_parts();
+    let req = SilcrowRequest::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    let response = err.clone().into_negotiated_response(&req);
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .contains("text/html")
+    );
+
+    let response = err.into_response();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .contains("application/json")
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Enforced by the hub and broadcaster
+// ════════════════════════════════════════════════════════════
+
+const FEED: SseRoute = SseRoute::new("/events/feed");
+const CHAT: WsRoute = WsRoute::new("/ws/chat");
+
+/// Serves `live` with `ConnectInfo`, so connections are limited per client IP.
+async fn serve(live: PilcrowLive) -> String {
+    let app = live.attach(Router::new());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap()
+    });
+    format!("ws://{addr}{}", CHAT.path())
+}
+
+/// Every SSE subscriber counts as the same user.
+fn feed(limiter: ConnectionLimiter) -> Router {
+    let limiter = limiter.identify_by(|_| Some("user-7".to_owned()));
+    PilcrowLive::new()
+        .with_sse(FEED, SseBroadcaster::default().with_limiter(limiter))
+        .attach(Router::new())
+}
+
+async fn subscribe(app: &Router) -> axum::response::Response {
+    app.clone()
+        .oneshot(
+            Request::get(FEED.path())
+                .header("accept", "application/json")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn hub_refuses_upgrades_over_the_limit() {
+    let limiter = ConnectionLimiter::new(1);
+    let url =
+        serve(PilcrowLive::new().with_ws(CHAT, WsHub::new().with_limiter(limiter.clone()))).await;
+
+    let (_first, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let refused = tokio_tungstenite::connect_async(&url).await.unwrap_err();
+    let tungstenite::Error::Http(response) = refused else {
+        panic!("expected an HTTP refusal, got {refused:?}");
+    };
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(limiter.active("127.0.0.1"), 1);
+}
+
+#[tokio::test]
+async fn hub_closes_the_superseded_socket() {
+    let limiter = ConnectionLimiter::with_policy(1, LimitPolicy::SupersedeOldest);
+    let hub = WsHub::new().with_limiter(limiter.clone());
+    let url = serve(PilcrowLive::new().with_ws(CHAT, hub.clone())).await;
+
+    let (mut oldest, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (_newest, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let message = tokio::time::timeout(Duration::from_secs(2), oldest.next())
+        .await
+        .expect("timed out waiting for close")
+        .unwrap()
+        .unwrap();
+    let Message::Close(Some(frame)) = message else {
+        panic!("expected a close frame, got {message:?}");
+    };
+    assert_eq!(u16::from(frame.code), 4001);
+    assert_eq!(limiter.active("127.0.0.1"), 1);
+    // Polling again sends the client's reply to the close.
+    let _ = tokio::time::timeout(Duration::from_secs(2), oldest.next()).await;
+
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while hub.connection_count() != 1 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("the superseded socket was not unregistered");
+}
+
+#[tokio::test]
+async fn broadcaster_refuses_subscribers_over_the_limit() {
+    let app = feed(ConnectionLimiter::new(1));
+    let first = subscribe(&app).await;
+    assert_eq!(first.status(), StatusCode::OK);
+
+    let refused = subscribe(&app).await;
+    assert_eq!(refused.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(
+        refused.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .contains("application/json")
+    );
+
+    drop(first);
+    assert_eq!(subscribe(&app).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn broadcaster_sends_the_superseded_stream_a_close_event() {
+    let app = feed(ConnectionLimiter::with_policy(
+        1,
+        LimitPolicy::SupersedeOldest,
+    ));
+    let mut oldest = subscribe(&app).await.into_body().into_data_stream();
+    let newest = subscribe(&app).await;
+    assert_eq!(newest.status(), StatusCode::OK);
+
+    let frame = tokio::time::timeout(Duration::from_secs(2), oldest.next())
+        .await
+        .expect("timed out waiting for close")
+        .expect("stream ended without a close event")
+        .unwrap();
+    let frame = String::from_utf8(frame.to_vec()).unwrap();
+    assert!(frame.contains("event: close"), "got: {frame}");
+    let end = tokio::time::timeout(Duration::from_secs(2), oldest.next())
+        .await
+        .expect("the superseded stream stayed open");
+    assert!(end.is_none());
+}
@@ -0,0 +1,93 @@
+// tests/export_formats.rs
+//
+// csv() and xml(): serialized rows and documents that keep the modifier chain.
+#![cfg(any(feature = "csv", feature = "xml"))]
+
+use axum::response::{IntoResponse, Response};
+use runtime::{ToastLevel, response::ResponseExt};
+use serde::Serialize;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn body_string(response: Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn content_type(response: &Response) -> &str {
+    response.headers()["content-type"].to_str().unwrap()
+}
+
+#[derive(Serialize)]
+struct Order<'a> {
+    id: u64,
+    customer: &'a str,
+}
+
+// ════════════════════════════════════════════════════════════
+// CSV
+// ════════════════════════════════════════════════════════════
+
+#[cfg(feature = "csv")]
+#[tokio::test]
+async fn csv_writes_a_header_and_quotes_fields_that_need_it() {
+    let response = runtime::csv([
+        Order {
+            id: 1,
+            customer: "Acme, Inc.",
+        },
+        Order {
+            id: 2,
+            customer: "say \"hi\"",
+        },
+    ])
+    .with_toast("Exported", ToastLevel::Success)
+    .into_response();
+    assert!(content_type(&response).starts_with("text/csv"));
+    assert!(response.headers().contains_key("set-cookie"));
+    assert_eq!(
+        body_string(response).await,
+        "id,customer\r\n1,\"Acme, Inc.\"\r\n2,\"say \"\"hi\"\"\"\r\n"
+    );
+}
+
+#[cfg(feature = "csv")]
+#[tokio::test]
+async fn csv_takes_plain_records() {
+    let response = runtime::csv([vec!["a", "b"], vec!["1", "2"]]).into_response();
+    assert_eq!(body_string(response).await, "a,b\r\n1,2\r\n");
+}
+
+#[cfg(feature = "csv")]
+#[tokio::test]
+async fn csv_rows_of_uneven_length_are_a_500() {
+    let response = runtime::csv([vec!["a", "b"], vec!["1"]]).into_response();
+    assert_eq!(
+        response.status(),
+        axum::http::StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// XML
+// ════════════════════════════════════════════════════════════
+
+#[cfg(feature = "xml")]
+#[tokio::test]
+async fn xml_serializes_the_value() {
+    let response = runtime::xml(Order {
+        id: 7,
+        customer: "Ada & Co",
+    })
+    .no_cache()
+    .into_response();
+    assert!(content_type(&response).starts_with("application/xml"));
+    assert_eq!(response.headers()["silcrow-cache"], "no-cache");
+    assert_eq!(
+        body_string(response).await,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <Order><id>7</id><customer>Ada &amp; Co</customer></Order>"
+    );
+}
@@ -0,0 +1,170 @@
+// tests/flash.rs
+//
+// Flash<T>: typed data carried across a redirect in a short-lived cookie.
+
+use axum::extract::FromRequestParts;
+use axum::http::{Request, header};
+use axum::response::{IntoResponse, Response};
+use runtime::{Flash, navigate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Submitted {
+    email: String,
+    errors: Vec<String>,
+}
+
+// ── Helpers ─────────────────────────────────────────────────
+
+fn submitted() -> Submitted {
+    Submitted {
+        email: "a@b.c".into(),
+        errors: vec!["taken".into()],
+    }
+}
+
+fn set_cookies(response: &Response) -> Vec<String> {
+    response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect()
+}
+
+/// The `name=value` part of the flash Set-Cookie, as a browser would send it back.
+fn flash_pair(response: &Response) -> String {
+    set_cookies(response)
+        .into_iter()
+        .find(|c| c.starts_with("silcrow_flash="))
+        .expect("flash cookie")
+        .split(';')
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+async fn extract<T: serde::de::DeserializeOwned>(cookie: Option<&str>) -> Flash<T> {
+    let mut request = Request::builder().uri("/next");
+    if let Some(cookie) = cookie {
+        request = request.header(header::COOKIE, cookie);
+    }
+    let (mut parts, _) = request.body(()).unwrap().into_parts();
+    Flash::<T>::from_request_parts(&mut parts, &())
+        .await
+        .unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Setting
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn with_flash_sets_short_lived_cookie() {
+    let response = navigate("/users/new")
+        .with_flash(&submitted())
+        .into_response();
+    assert_eq!(response.status(), 303);
+    let cookie = set_cookies(&response)
+        .into_iter()
+        .find(|c| c.starts_with("silcrow_flash="))
+        .unwrap();
+    assert!(cookie.contains("HttpOnly"), "got: {cookie}");
+    assert!(cookie.contains("Path=/"), "got: {cookie}");
+    assert!(cookie.contains("Max-Age=5"), "got: {cookie}");
+}
+
+// ════════════════════════════════════════════════════════════
+// Reading
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn flash_round_trips_through_the_cookie() {
+    let response = navigate("/users/new")
+        .with_flash(&submitted())
+        .into_response();
+    let cookie = format!("theme=dark; {}", flash_pair(&response));
+    let Flash(data) = extract::<Submitted>(Some(&cookie)).await;
+    assert_eq!(data, Some(submitted()));
+}
+
+#[tokio::test]
+async fn missing_or_mismatched_flash_is_none() {
+    assert_eq!(extract::<Submitted>(None).await, Flash(None));
+
+    let response = navigate("/").with_flash(&42).into_response();
+    let cookie = flash_pair(&response);
+    assert_eq!(extract::<Submitted>(Some(&cookie)).await, Flash(None));
+    assert_eq!(extract::<u32>(Some(&cookie)).await.into_inner(), Some(42));
+}
+
+#[tokio::test]
+async fn returning_flash_clears_the_cookie() {
+    let response = (Flash::<Submitted>(None), "page").into_response();
+    let cookies = set_cookies(&response);
+    assert_eq!(cookies.len(), 1);
+    assert!(cookies[0].starts_with("silcrow_flash=;"));
+    assert!(cookies[0].contains("Max-Age=0"));
+}
+
+// ════════════════════════════════════════════════════════════
+// FlashLayer
+// ════════════════════════════════════════════════════════════
+
+async fn layered(uri: &str, cookie: Option<&str>) -> Response {
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    let app = axum::Router::new()
+        .route(
+            "/form",
+            get(|Flash(previous): Flash<Submitted>| async move {
+                format!("{:?}", previous.map(|data| data.email))
+            }),
+        )
+        .route(
+            "/again",
+            get(|_: Flash<Submitted>| async { navigate("/form").with_flash(&submitted()) }),
+        )
+        .route("/plain", get(|| async { "plain" }))
+        .layer(runtime::FlashLayer::new());
+    let mut request = Request::builder().uri(uri);
+    if let Some(cookie) = cookie {
+        request = request.header(header::COOKIE, cookie);
+    }
+    app.oneshot(request.body(axum::body::Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+fn flash_cookie_pair() -> String {
+    flash_pair(&navigate("/form").with_flash(&submitted()).into_response())
+}
+
+#[tokio::test]
+async fn layer_clears_the_cookie_once_read() {
+    let response = layered("/form", Some(&flash_cookie_pair())).await;
+    let cookies = set_cookies(&response);
+    assert_eq!(cookies.len(), 1);
+    assert!(
+        cookies[0].starts_with("silcrow_flash=;"),
+        "got: {cookies:?}"
+    );
+    assert!(cookies[0].contains("Max-Age=0"));
+}
+
+#[tokio::test]
+async fn layer_leaves_unread_and_absent_flashes_alone() {
+    let response = layered("/plain", Some(&flash_cookie_pair())).await;
+    assert!(set_cookies(&response).is_empty());
+    let response = layered("/form", None).await;
+    assert!(set_cookies(&response).is_empty());
+}
+
+#[tokio::test]
+async fn layer_keeps_a_flash_the_handler_sets_again() {
+    let response = layered("/again", Some(&flash_cookie_pair())).await;
+    let cookies = set_cookies(&response);
+    assert_eq!(cookies.len(), 1);
+    assert!(cookies[0].contains("Max-Age=5"), "got: {cookies:?}");
+}
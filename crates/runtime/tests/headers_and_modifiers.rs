@@ -3,7 +3,10 @@
 // Verify every ResponseExt modifier sets the correct header.
 
 use axum::response::{IntoResponse, Response};
-use runtime::{SseRoute, ToastLevel, WsRoute, html, json, response::ResponseExt};
+use runtime::{
+    JsonPatchOp, SseRoute, SwapMode, ToastLevel, WsRoute, fragment, html, json, no_content,
+    response::ResponseExt,
+};
 
 // ── Helpers ─────────────────────────────────────────────────
 
@@ -43,6 +46,27 @@ async fn with_header_on_json() {
     assert_eq!(get_header(&response, "x-api-version").unwrap(), "v2");
 }
 
+#[tokio::test]
+async fn with_link_adds_each_link() {
+    let response = html("<p>test</p>")
+        .with_link("preload", "/events")
+        .with_link("prefetch", "/orders?page=2")
+        .into_response();
+    let links: Vec<_> = response
+        .headers()
+        .get_all("link")
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            "</events>; rel=\"preload\"".to_string(),
+            "</orders?page=2>; rel=\"prefetch\"".to_string(),
+        ]
+    );
+}
+
 // ════════════════════════════════════════════════════════════
 // No Cache
 // ════════════════════════════════════════════════════════════
@@ -72,6 +96,71 @@ async fn retarget_sets_header() {
     );
 }
 
+#[tokio::test]
+async fn non_ascii_retarget_is_percent_encoded() {
+    let response = html("<p>test</p>").retarget("#café").into_response();
+    assert_eq!(
+        get_header(&response, "silcrow-retarget").unwrap(),
+        "utf-8''%23caf%C3%A9"
+    );
+}
+
+#[tokio::test]
+async fn ascii_values_are_sent_verbatim() {
+    let response = html("<p>test</p>")
+        .push_history("/orders?page=2")
+        .into_response();
+    assert_eq!(
+        get_header(&response, "silcrow-push").unwrap(),
+        "/orders?page=2"
+    );
+}
+
+#[tokio::test]
+async fn retarget_many_sets_json_array() {
+    let response = html("<span>4</span>")
+        .retarget_many(&["#cart-count", "#sidebar .count"])
+        .into_response();
+    assert_eq!(
+        get_header(&response, "silcrow-retarget").unwrap(),
+        r##"["#cart-count","#sidebar .count"]"##
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Fragment & Swap
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn fragment_sets_retarget_and_swap() {
+    let response = fragment("<nav>3 new</nav>", "#sidebar").into_response();
+    assert_eq!(
+        get_header(&response, "silcrow-retarget").unwrap(),
+        "#sidebar"
+    );
+    assert_eq!(get_header(&response, "silcrow-swap").unwrap(), "innerHTML");
+}
+
+#[tokio::test]
+async fn fragment_swap_can_be_overridden() {
+    let response = fragment("<li>new</li>", "#list")
+        .swap(SwapMode::BeforeEnd)
+        .into_response();
+    assert_eq!(get_header(&response, "silcrow-swap").unwrap(), "beforeend");
+}
+
+#[tokio::test]
+async fn preserve_joins_selectors() {
+    let response = html("<main>…</main>")
+        .preserve("#player")
+        .preserve("details.open")
+        .into_response();
+    assert_eq!(
+        get_header(&response, "silcrow-preserve").unwrap(),
+        "#player, details.open"
+    );
+}
+
 // ════════════════════════════════════════════════════════════
 // Push History
 // ════════════════════════════════════════════════════════════
@@ -99,6 +188,62 @@ async fn trigger_event_sets_header() {
     assert!(parsed.get("refresh").is_some());
 }
 
+#[tokio::test]
+async fn trigger_events_merge_with_payloads() {
+    let response = html("<p>test</p>")
+        .trigger_event("refresh")
+        .trigger_event_with("cart:updated", &serde_json::json!({"count": 3}))
+        .into_response();
+    let header = get_header(&response, "silcrow-trigger").unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&header).unwrap();
+    assert_eq!(
+        parsed,
+        serde_json::json!({"refresh": {}, "cart:updated": {"count": 3}})
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Run Hook
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn run_hook_queues_calls_in_order() {
+    let response = html("<p>test</p>")
+        .run_hook("openModal", &"#confirm")
+        .run_hook("scrollChart", &serde_json::json!({"at": 1700000000}))
+        .into_response();
+    let header = get_header(&response, "silcrow-hook").unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&header).unwrap();
+    assert_eq!(
+        parsed,
+        serde_json::json!([
+            {"name": "openModal", "args": "#confirm"},
+            {"name": "scrollChart", "args": {"at": 1700000000}},
+        ])
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Confirm
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn confirm_names_the_follow_up_request() {
+    let response = html("<p>test</p>")
+        .confirm("Delete 3 orders?", "/orders/delete?ids=1,2,3")
+        .into_response();
+    let header = get_header(&response, "silcrow-confirm").unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&header).unwrap();
+    assert_eq!(
+        parsed,
+        serde_json::json!({
+            "message": "Delete 3 orders?",
+            "path": "/orders/delete?ids=1,2,3",
+            "method": "POST",
+        })
+    );
+}
+
 // ════════════════════════════════════════════════════════════
 // Patch Target
 // ════════════════════════════════════════════════════════════
@@ -111,8 +256,54 @@ async fn patch_target_sets_header() {
         .into_response();
     let header = get_header(&response, "silcrow-patch").unwrap();
     let parsed: serde_json::Value = serde_json::from_str(&header).unwrap();
-    assert_eq!(parsed["target"], "#counter");
-    assert_eq!(parsed["data"]["count"], 42);
+    assert_eq!(parsed[0]["target"], "#counter");
+    assert_eq!(parsed[0]["data"]["count"], 42);
+}
+
+#[tokio::test]
+async fn unicode_patch_data_survives() {
+    let response = html("<p>test</p>")
+        .patch_target("#greeting", &serde_json::json!({"text": "héllo\nwörld"}))
+        .into_response();
+    let header = get_header(&response, "silcrow-patch").unwrap();
+    let encoded = header.strip_prefix("utf-8''").expect("encoded marker");
+    let decoded = urlencoding::decode(encoded).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&decoded).unwrap();
+    assert_eq!(parsed[0]["data"]["text"], "héllo\nwörld");
+}
+
+#[tokio::test]
+async fn patch_target_accumulates_in_order() {
+    let response = html("<p>test</p>")
+        .patch_target("#counter", &serde_json::json!({"count": 1}))
+        .patch_target("#badge", &serde_json::json!({"unread": 5}))
+        .into_response();
+    let header = get_header(&response, "silcrow-patch").unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&header).unwrap();
+    assert_eq!(
+        parsed,
+        serde_json::json!([
+            {"target": "#counter", "data": {"count": 1}},
+            {"target": "#badge", "data": {"unread": 5}},
+        ])
+    );
+}
+
+#[tokio::test]
+async fn json_patch_target_sends_ops() {
+    let response = html("<p>test</p>")
+        .patch_target("#counter", &serde_json::json!({"count": 1}))
+        .json_patch_target("#rows", &[JsonPatchOp::replace("/0/qty", 3)])
+        .into_response();
+    let header = get_header(&response, "silcrow-patch").unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&header).unwrap();
+    assert_eq!(
+        parsed[1],
+        serde_json::json!({
+            "target": "#rows",
+            "ops": [{"op": "replace", "path": "/0/qty", "value": 3}],
+        })
+    );
 }
 
 // ════════════════════════════════════════════════════════════
@@ -130,6 +321,74 @@ async fn invalidate_target_sets_header() {
     );
 }
 
+// ════════════════════════════════════════════════════════════
+// Title & Meta
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn with_title_encodes_non_ascii() {
+    let response = html("<p>test</p>")
+        .with_title("Café orders")
+        .into_response();
+    assert_eq!(
+        get_header(&response, "silcrow-title").unwrap(),
+        "utf-8''Caf%C3%A9%20orders"
+    );
+}
+
+#[tokio::test]
+async fn with_meta_merges_tags() {
+    let response = html("<p>test</p>")
+        .with_meta("description", "Old")
+        .with_meta("robots", "noindex")
+        .with_meta("description", "Open orders")
+        .into_response();
+    let header = get_header(&response, "silcrow-meta").unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&header).unwrap();
+    assert_eq!(
+        parsed,
+        serde_json::json!({"description": "Open orders", "robots": "noindex"})
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Polling
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn poll_every_sends_milliseconds() {
+    let response = html("<p>queued</p>")
+        .poll_every(std::time::Duration::from_secs(2))
+        .into_response();
+    assert_eq!(get_header(&response, "silcrow-poll").unwrap(), "2000");
+}
+
+#[tokio::test]
+async fn stop_polling_replaces_the_interval() {
+    let response = html("<p>done</p>")
+        .poll_every(std::time::Duration::from_secs(2))
+        .stop_polling()
+        .into_response();
+    assert_eq!(get_header(&response, "silcrow-poll").unwrap(), "stop");
+}
+
+// ════════════════════════════════════════════════════════════
+// Remove Target
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn remove_target_rides_on_no_content() {
+    let response = no_content()
+        .remove_target("#order-7")
+        .remove_target("#order-7-detail")
+        .into_response();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+    assert_eq!(
+        get_header(&response, "silcrow-remove").unwrap(),
+        "#order-7, #order-7-detail"
+    );
+}
+
 // ════════════════════════════════════════════════════════════
 // Client Navigate
 // ════════════════════════════════════════════════════════════
@@ -0,0 +1,89 @@
+// tests/instruction_recorder.rs
+//
+// Dev-only recorder of silcrow instructions carried by responses.
+#![cfg(feature = "recorder")]
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use runtime::debug::{INSTRUCTIONS_PATH, InstructionRecorder};
+use runtime::{ToastLevel, html, response::ResponseExt};
+use tower::ServiceExt;
+
+fn app(recorder: InstructionRecorder) -> Router {
+    let router = Router::new()
+        .route(
+            "/save",
+            get(|| async {
+                html("<p>ok</p>")
+                    .retarget("#main")
+                    .trigger_event("saved")
+                    .with_toast("Saved", ToastLevel::Success)
+            }),
+        )
+        .route("/plain", get(|| async { html("<p>plain</p>") }));
+    recorder.attach(router)
+}
+
+async fn get_path(app: Router, path: &str) -> axum::response::Response {
+    app.oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn records_instructions_and_tags_response() {
+    let recorder = InstructionRecorder::new(10);
+    let response = get_path(app(recorder.clone()), "/save").await;
+    assert_eq!(response.headers().get("silcrow-debug-record").unwrap(), "1");
+
+    let records = recorder.snapshot();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].path, "/save");
+    let kinds: Vec<_> = records[0]
+        .instructions
+        .iter()
+        .map(|i| i.kind.as_str())
+        .collect();
+    assert!(kinds.contains(&"retarget"));
+    assert!(kinds.contains(&"trigger"));
+    let toast = records[0]
+        .instructions
+        .iter()
+        .find(|i| i.kind == "toast")
+        .unwrap();
+    assert!(toast.value.contains("Saved"));
+}
+
+#[tokio::test]
+async fn skips_responses_without_instructions() {
+    let recorder = InstructionRecorder::new(10);
+    let response = get_path(app(recorder.clone()), "/plain").await;
+    assert!(response.headers().get("silcrow-debug-record").is_none());
+    assert!(recorder.snapshot().is_empty());
+}
+
+#[tokio::test]
+async fn ring_buffer_keeps_most_recent() {
+    let recorder = InstructionRecorder::new(2);
+    for _ in 0..3 {
+        get_path(app(recorder.clone()), "/save").await;
+    }
+    let ids: Vec<_> = recorder.snapshot().iter().map(|r| r.id).collect();
+    assert_eq!(ids, [2, 3]);
+}
+
+#[tokio::test]
+async fn serves_records_at_debug_endpoint() {
+    let recorder = InstructionRecorder::new(10);
+    get_path(app(recorder.clone()), "/save").await;
+
+    let response = get_path(app(recorder), INSTRUCTIONS_PATH).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(parsed[0]["path"], "/save");
+}
@@ -0,0 +1,184 @@
+// tests/live_event.rs
+//
+// LiveEvent and the WsEvent ↔ SilcrowEvent conversions: one event, either transport.
+
+use axum::response::sse::Event;
+use runtime::live::LiveEvent;
+use runtime::test::{CollectedEvent, SseCollector};
+use runtime::{JsonPatchOp, SilcrowEvent, SseBroadcaster, ToastLevel, WsEvent};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+fn wire(event: impl Into<SilcrowEvent>) -> String {
+    format!("{:?}", Event::from(event.into()))
+}
+
+fn ws_json(event: impl Into<WsEvent>) -> serde_json::Value {
+    serde_json::to_value(event.into()).unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// WsEvent → SilcrowEvent
+// ════════════════════════════════════════════════════════════
+
+#[test]
+fn ws_events_render_like_their_sse_counterparts() {
+    let pairs = [
+        (
+            WsEvent::patch(json!({"n": 1}), "#count"),
+            SilcrowEvent::patch(json!({"n": 1}), "#count"),
+        ),
+        (
+            WsEvent::html("<b>hi</b>", "#greeting"),
+            SilcrowEvent::html("<b>hi</b>", "#greeting"),
+        ),
+        (
+            WsEvent::invalidate("#list"),
+            SilcrowEvent::invalidate("#list"),
+        ),
+        (WsEvent::navigate("/home"), SilcrowEvent::navigate("/home")),
+        (
+            WsEvent::custom("cart:update", json!([1, 2])),
+            SilcrowEvent::custom("cart:update", json!([1, 2])),
+        ),
+        (
+            WsEvent::toast("Saved", ToastLevel::Success),
+            SilcrowEvent::toast("Saved", ToastLevel::Success),
+        ),
+        (
+            WsEvent::trigger("refresh", json!(null)),
+            SilcrowEvent::trigger("refresh", json!(null)),
+        ),
+        (
+            WsEvent::push_history("/orders/7"),
+            SilcrowEvent::push_history("/orders/7"),
+        ),
+    ];
+    for (ws, sse) in pairs {
+        assert_eq!(SilcrowEvent::from(ws), sse);
+    }
+}
+
+#[tokio::test]
+async fn push_history_is_sent_over_sse() {
+    let broadcaster = SseBroadcaster::default();
+    let mut events = SseCollector::new(axum::response::IntoResponse::into_response(
+        broadcaster.handler(),
+    ));
+    broadcaster.publish(WsEvent::push_history("/orders/7"));
+    assert_eq!(
+        events.next_event().await.unwrap(),
+        CollectedEvent::PushHistory {
+            url: "/orders/7".into()
+        }
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// SilcrowEvent → WsEvent
+// ════════════════════════════════════════════════════════════
+
+#[test]
+fn shared_events_convert_back_to_ws() {
+    let event = WsEvent::try_from(SilcrowEvent::patch(json!({"n": 2}), "#count")).unwrap();
+    assert_eq!(
+        ws_json(event),
+        json!({"type": "patch", "target": "#count", "data": {"n": 2}})
+    );
+}
+
+#[test]
+fn json_patch_events_carry_ops() {
+    let ops = [JsonPatchOp::replace("/count", 4)];
+    assert_eq!(
+        ws_json(WsEvent::json_patch(ops.clone(), "#count")),
+        json!({
+            "type": "json_patch",
+            "target": "#count",
+            "ops": [{"op": "replace", "path": "/count", "value": 4}],
+        })
+    );
+    assert_eq!(
+        SilcrowEvent::from(LiveEvent::json_patch(ops.clone(), "#count")),
+        SilcrowEvent::json_patch(ops, "#count")
+    );
+}
+
+#[tokio::test]
+async fn json_patch_is_sent_over_sse() {
+    let broadcaster = SseBroadcaster::default();
+    let mut events = SseCollector::new(axum::response::IntoResponse::into_response(
+        broadcaster.handler(),
+    ));
+    broadcaster.publish(SilcrowEvent::json_patch(
+        [JsonPatchOp::remove("/rows/2")],
+        "#rows",
+    ));
+    assert_eq!(
+        events.next_event().await.unwrap(),
+        CollectedEvent::JsonPatch {
+            target: "#rows".into(),
+            ops: vec![JsonPatchOp::remove("/rows/2")],
+        }
+    );
+}
+
+#[test]
+fn sse_only_events_are_handed_back() {
+    for event in [
+        SilcrowEvent::patch_many([("#a", 1)]),
+        SilcrowEvent::heartbeat("ok"),
+        SilcrowEvent::close(),
+    ] {
+        assert_eq!(WsEvent::try_from(event.clone()).unwrap_err(), event);
+    }
+}
+
+#[test]
+fn failed_payloads_do_not_convert() {
+    struct Unserializable;
+    impl serde::Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("nope"))
+        }
+    }
+    assert!(WsEvent::try_from(SilcrowEvent::patch(Unserializable, "#x")).is_err());
+}
+
+// ════════════════════════════════════════════════════════════
+// LiveEvent
+// ════════════════════════════════════════════════════════════
+
+#[test]
+fn live_events_convert_to_both_transports() {
+    let event = LiveEvent::patch(json!({"n": 3}), "#count");
+    assert_eq!(
+        ws_json(event.clone()),
+        ws_json(WsEvent::patch(json!({"n": 3}), "#count"))
+    );
+    assert_eq!(
+        wire(event.clone()),
+        wire(SilcrowEvent::patch(json!({"n": 3}), "#count"))
+    );
+    assert_eq!(
+        LiveEvent::try_from(SilcrowEvent::from(event.clone())).unwrap(),
+        event
+    );
+}
+
+#[tokio::test]
+async fn one_live_event_feeds_a_hub_and_a_broadcaster() {
+    let broadcaster = SseBroadcaster::default();
+    let mut events = Box::pin(broadcaster.subscribe());
+    let hub = runtime::WsHub::new();
+
+    let event = LiveEvent::invalidate("#orders");
+    assert_eq!(hub.publish(event.clone()), 0);
+    assert_eq!(broadcaster.publish(event), 1);
+    assert_eq!(
+        events.next().await.unwrap(),
+        SilcrowEvent::invalidate("#orders").with_id("1")
+    );
+}
@@ -0,0 +1,158 @@
+// tests/live_priority.rs
+//
+// Priority-aware outbound queue used by live transports.
+
+use runtime::live::{EventPriority, Prioritized, PushError, PushOutcome, priority_channel};
+use runtime::{SilcrowEvent, WsEvent};
+
+// ════════════════════════════════════════════════════════════
+// Classification
+// ════════════════════════════════════════════════════════════
+
+#[test]
+fn silcrow_event_priorities() {
+    assert_eq!(SilcrowEvent::navigate("/x").priority(), EventPriority::High);
+    assert_eq!(
+        SilcrowEvent::patch(serde_json::json!({}), "#a").priority(),
+        EventPriority::Normal
+    );
+    assert_eq!(
+        SilcrowEvent::patch_many([("#a", 1), ("#b", 2)]).priority(),
+        EventPriority::Normal
+    );
+    assert_eq!(
+        SilcrowEvent::html("<p></p>", "#a").priority(),
+        EventPriority::Low
+    );
+    assert_eq!(SilcrowEvent::close().priority(), EventPriority::Low);
+    assert!(SilcrowEvent::close().is_final());
+    assert_eq!(SilcrowEvent::heartbeat("ok").priority(), EventPriority::Low);
+    assert_eq!(
+        SilcrowEvent::push_history("/a").priority(),
+        EventPriority::Normal
+    );
+    assert_eq!(
+        SilcrowEvent::toast("Saved", runtime::ToastLevel::Success).priority(),
+        EventPriority::High
+    );
+    assert_eq!(
+        SilcrowEvent::trigger("saved", ()).priority(),
+        EventPriority::Normal
+    );
+}
+
+#[test]
+fn ws_event_priorities() {
+    assert_eq!(WsEvent::navigate("/x").priority(), EventPriority::High);
+    assert_eq!(WsEvent::invalidate("#a").priority(), EventPriority::Normal);
+    assert_eq!(
+        WsEvent::toast("Saved", runtime::ToastLevel::Info).priority(),
+        EventPriority::High
+    );
+    assert_eq!(
+        WsEvent::html("<p></p>", "#a").priority(),
+        EventPriority::Low
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Queue behaviour
+// ════════════════════════════════════════════════════════════
+
+fn label(event: &WsEvent) -> String {
+    match event {
+        WsEvent::Html { markup, .. } => markup.clone(),
+        WsEvent::Patch { target, .. }
+        | WsEvent::JsonPatch { target, .. }
+        | WsEvent::Invalidate { target } => target.clone(),
+        WsEvent::Navigate { path } | WsEvent::PushHistory { url: path } => path.clone(),
+        WsEvent::Custom { event, .. } | WsEvent::Trigger { event, .. } => event.clone(),
+        WsEvent::Toast { message, .. } => message.clone(),
+    }
+}
+
+#[tokio::test]
+async fn drains_highest_priority_first() {
+    let (tx, mut rx) = priority_channel::<WsEvent>(8);
+    tx.push(WsEvent::html("h1", "#a")).unwrap();
+    tx.push(WsEvent::invalidate("#p1")).unwrap();
+    tx.push(WsEvent::navigate("/n1")).unwrap();
+    tx.push(WsEvent::html("h2", "#a")).unwrap();
+    drop(tx);
+
+    let mut order = Vec::new();
+    while let Some(event) = rx.recv().await {
+        order.push(label(&event));
+    }
+    assert_eq!(order, ["/n1", "#p1", "h1", "h2"]);
+}
+
+#[tokio::test]
+async fn full_queue_displaces_oldest_lower_priority() {
+    let (tx, rx) = priority_channel::<WsEvent>(2);
+    tx.push(WsEvent::html("h1", "#a")).unwrap();
+    tx.push(WsEvent::html("h2", "#a")).unwrap();
+
+    match tx.push(WsEvent::navigate("/n")).unwrap() {
+        PushOutcome::Displaced(dropped) => assert_eq!(label(&dropped), "h1"),
+        PushOutcome::Queued => panic!("expected displacement"),
+    }
+    assert_eq!(rx.len(), 2);
+}
+
+#[tokio::test]
+async fn full_queue_rejects_equal_priority() {
+    let (tx, _rx) = priority_channel::<WsEvent>(1);
+    tx.push(WsEvent::html("h1", "#a")).unwrap();
+    assert!(matches!(
+        tx.push(WsEvent::html("h2", "#a")),
+        Err(PushError::Full(_))
+    ));
+}
+
+#[tokio::test]
+async fn push_after_receiver_dropped_is_closed() {
+    let (tx, rx) = priority_channel::<WsEvent>(1);
+    drop(rx);
+    assert!(tx.is_closed());
+    assert!(matches!(
+        tx.push(WsEvent::navigate("/n")),
+        Err(PushError::Closed(_))
+    ));
+}
+
+#[tokio::test]
+async fn send_waits_for_space() {
+    let (tx, mut rx) = priority_channel::<WsEvent>(1);
+    tx.push(WsEvent::navigate("/first")).unwrap();
+
+    let sender = tx.clone();
+    let pending = tokio::spawn(async move { sender.send(WsEvent::navigate("/second")).await });
+
+    assert_eq!(label(&rx.recv().await.unwrap()), "/first");
+    assert!(pending.await.unwrap().is_ok());
+    assert_eq!(label(&rx.recv().await.unwrap()), "/second");
+}
+
+#[tokio::test]
+async fn close_drains_last_and_ends_the_queue() {
+    let (tx, mut rx) = priority_channel::<SilcrowEvent>(2);
+    let patch = SilcrowEvent::patch(serde_json::json!({"n": 1}), "#a");
+    tx.push(patch.clone()).unwrap();
+    tx.push(SilcrowEvent::navigate("/n")).unwrap();
+    assert!(matches!(
+        tx.push(SilcrowEvent::close()).unwrap(),
+        PushOutcome::Queued
+    ));
+    assert!(matches!(
+        tx.push(SilcrowEvent::navigate("/late")),
+        Err(PushError::Closed(_))
+    ));
+
+    assert_eq!(rx.recv().await, Some(SilcrowEvent::navigate("/n")));
+    assert_eq!(rx.recv().await, Some(patch));
+    assert_eq!(rx.recv().await, Some(SilcrowEvent::close()));
+    // The sender is still alive, but nothing can follow a close.
+    assert_eq!(rx.recv().await, None);
+    drop(tx);
+}
@@ -0,0 +1,116 @@
+// tests/live_registry.rs
+//
+// PilcrowLive: per-route broadcasters and hubs, mounted and extractable.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::post;
+use runtime::live::PilcrowLive;
+use runtime::{SilcrowEvent, SseBroadcaster, SseRoute, WsRoute};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use tower::ServiceExt;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+const FEED: SseRoute = SseRoute::new("/events/feed");
+const ALERTS: SseRoute = SseRoute::new("/events/alerts");
+const CHAT: WsRoute = WsRoute::new("/ws/chat");
+
+async fn next_frame(body: &mut axum::body::BodyDataStream) -> String {
+    let chunk = tokio::time::timeout(Duration::from_secs(2), body.next())
+        .await
+        .expect("timed out waiting for SSE frame")
+        .expect("SSE stream ended early")
+        .unwrap();
+    String::from_utf8(chunk.to_vec()).unwrap()
+}
+
+async fn notify(live: PilcrowLive) -> StatusCode {
+    match live.broadcaster(FEED) {
+        Some(feed) => {
+            feed.publish(SilcrowEvent::invalidate("#orders"));
+            StatusCode::NO_CONTENT
+        }
+        None => StatusCode::NOT_FOUND,
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// Registration
+// ════════════════════════════════════════════════════════════
+
+#[test]
+fn lookups_return_registered_publishers_only() {
+    let live = PilcrowLive::new().sse(FEED).ws(CHAT);
+    assert!(live.broadcaster(FEED).is_some());
+    assert!(live.broadcaster(ALERTS).is_none());
+    assert!(live.hub(CHAT).is_some());
+}
+
+#[test]
+fn clones_share_publishers() {
+    let feed = SseBroadcaster::new(8);
+    let _subscriber = feed.subscribe();
+    let live = PilcrowLive::new().with_sse(FEED, feed);
+    let copy = live.clone();
+    assert_eq!(
+        copy.broadcaster(FEED)
+            .unwrap()
+            .publish(SilcrowEvent::invalidate("#x")),
+        1
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Mounted endpoints
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn handlers_publish_to_mounted_sse_route() {
+    let live = PilcrowLive::new().with_sse(FEED, SseBroadcaster::new(16));
+    let app = live.attach(Router::new().route("/notify", post(notify)));
+
+    let response = app
+        .clone()
+        .oneshot(Request::get(FEED.path()).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let mut body = response.into_body().into_data_stream();
+
+    let published = app
+        .oneshot(Request::post("/notify").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(published.status(), StatusCode::NO_CONTENT);
+
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("event: invalidate"), "got: {frame}");
+    assert!(frame.contains("#orders"), "got: {frame}");
+}
+
+#[tokio::test]
+async fn ws_route_is_mounted() {
+    let app = PilcrowLive::new().ws(CHAT).attach(Router::new());
+    let response = app
+        .oneshot(Request::get(CHAT.path()).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_ne!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "hub endpoint should exist and reject the non-upgrade request"
+    );
+}
+
+#[tokio::test]
+async fn extractor_fails_without_attach() {
+    let app = Router::new().route("/notify", post(notify));
+    let response = app
+        .oneshot(Request::post("/notify").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
@@ -0,0 +1,81 @@
+// tests/locale.rs
+//
+// Locale: Accept-Language ranking, the silcrow_locale override cookie and
+// the modifiers that set and clear it.
+
+use axum::extract::FromRequestParts;
+use axum::http::{Request, header};
+use axum::response::IntoResponse;
+use runtime::{Locale, html, response::ResponseExt};
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn extract(accept_language: Option<&str>, cookie: Option<&str>) -> Locale {
+    let mut request = Request::get("/");
+    if let Some(value) = accept_language {
+        request = request.header(header::ACCEPT_LANGUAGE, value);
+    }
+    if let Some(value) = cookie {
+        request = request.header(header::COOKIE, value);
+    }
+    let (mut parts, _) = request.body(()).unwrap().into_parts();
+    Locale::from_request_parts(&mut parts, &()).await.unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Accept-Language
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn ranks_by_quality_and_drops_wildcards() {
+    let locale = extract(Some("fr;q=0.5, en-GB, de;q=0.8, *;q=0.1, es;q=0"), None).await;
+    assert_eq!(locale.languages, ["en-GB", "de", "fr"]);
+    assert!(!locale.from_cookie);
+    assert_eq!(locale.primary(), Some("en-GB"));
+    assert_eq!(locale.language(), Some("en"));
+    assert_eq!(locale.region(), Some("GB"));
+}
+
+#[tokio::test]
+async fn no_preference_is_empty() {
+    let locale = extract(None, None).await;
+    assert!(locale.languages.is_empty());
+    assert_eq!(locale.primary(), None);
+    assert_eq!(locale.negotiate(&["en"]), None);
+}
+
+#[tokio::test]
+async fn negotiate_prefers_exact_then_language_matches() {
+    let locale = extract(Some("de-AT, fr-CA;q=0.9"), None).await;
+    assert_eq!(locale.negotiate(&["en", "fr-CA", "de"]), Some("de"));
+    assert_eq!(locale.negotiate(&["en", "fr-ca"]), Some("fr-ca"));
+    assert_eq!(locale.negotiate(&["en"]), None);
+}
+
+// ════════════════════════════════════════════════════════════
+// Override cookie
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn cookie_comes_first_without_duplicates() {
+    let locale = extract(Some("en, pt-BR;q=0.5"), Some("silcrow_locale=pt_BR")).await;
+    assert_eq!(locale.languages, ["pt-BR", "en"]);
+    assert!(locale.from_cookie);
+    assert_eq!(locale.region(), Some("BR"));
+}
+
+#[tokio::test]
+async fn with_locale_sets_and_clear_locale_expires_the_cookie() {
+    let response = html("<p>ok</p>").with_locale("pt-BR").into_response();
+    let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+    assert!(cookie.starts_with("silcrow_locale=pt-BR"));
+    assert!(cookie.contains("Path=/"));
+
+    let response = html("<p>ok</p>").with_locale("<script>").into_response();
+    assert!(!response.headers().contains_key(header::SET_COOKIE));
+
+    let response = html("<p>ok</p>").clear_locale().into_response();
+    let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+    assert!(cookie.starts_with("silcrow_locale="));
+    assert!(cookie.contains("Max-Age=0"));
+}
@@ -0,0 +1,171 @@
+// tests/payload.rs
+//
+// Payload<T>: one extractor for JSON, URL-encoded and multipart bodies, and
+// LimitedPayload's mode-aware size limit.
+
+use axum::body::Body;
+use axum::extract::FromRequest;
+use axum::http::{Request, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use runtime::{LimitedPayload, Payload};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Order {
+    item: String,
+    qty: u32,
+}
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn post(
+    content_type: &str,
+    body: impl Into<Body>,
+) -> Result<Payload<Order>, (StatusCode, &'static str)> {
+    let request = Request::post("/orders")
+        .header(header::CONTENT_TYPE, content_type)
+        .body(body.into())
+        .unwrap();
+    Payload::from_request(request, &()).await
+}
+
+async fn body_text(response: Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+async fn post_limited(
+    accept: &str,
+    silcrow: bool,
+    body: &'static str,
+) -> Result<LimitedPayload<Order, 32>, Response> {
+    let mut request = Request::post("/orders")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header(header::ACCEPT, accept);
+    if silcrow {
+        request = request.header("silcrow-target", "#order-form");
+    }
+    LimitedPayload::from_request(request.body(Body::from(body)).unwrap(), &())
+        .await
+        .map_err(IntoResponse::into_response)
+}
+
+fn widget() -> Order {
+    Order {
+        item: "widget".into(),
+        qty: 3,
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// Content types
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn json_body() {
+    let payload = post("application/json", r#"{"item":"widget","qty":3}"#)
+        .await
+        .unwrap();
+    assert_eq!(payload.into_inner(), widget());
+}
+
+#[tokio::test]
+async fn form_body() {
+    let payload = post("application/x-www-form-urlencoded", "item=widget&qty=3")
+        .await
+        .unwrap();
+    assert_eq!(payload.0, widget());
+}
+
+#[tokio::test]
+async fn multipart_body_skips_files() {
+    let body = "--XYZ\r\n\
+        Content-Disposition: form-data; name=\"item\"\r\n\r\n\
+        widget\r\n\
+        --XYZ\r\n\
+        Content-Disposition: form-data; name=\"photo\"; filename=\"w.png\"\r\n\
+        Content-Type: image/png\r\n\r\n\
+        PNG\r\n\
+        --XYZ\r\n\
+        Content-Disposition: form-data; name=\"qty\"\r\n\r\n\
+        3\r\n\
+        --XYZ--\r\n";
+    let payload = post("multipart/form-data; boundary=XYZ", body)
+        .await
+        .unwrap();
+    assert_eq!(payload.0, widget());
+}
+
+#[tokio::test]
+async fn get_reads_the_query_string() {
+    let request = Request::get("/orders?item=widget&qty=3")
+        .body(Body::empty())
+        .unwrap();
+    let Payload(order) = Payload::<Order>::from_request(request, &()).await.unwrap();
+    assert_eq!(order, widget());
+}
+
+// ════════════════════════════════════════════════════════════
+// Rejections
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn mismatched_body_is_422() {
+    let (status, _) = post("application/json", r#"{"item":"widget"}"#)
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, _) = post("application/x-www-form-urlencoded", "item=widget&qty=many")
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn unknown_content_type_is_415() {
+    let (status, _) = post("text/plain", "widget x3").await.unwrap_err();
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+// ════════════════════════════════════════════════════════════
+// Size limits
+// ════════════════════════════════════════════════════════════
+
+const OVERSIZED: &str = "item=an-item-name-well-over-the-limit&qty=3";
+
+#[tokio::test]
+async fn within_limit_deserializes() {
+    let payload = post_limited("text/html", true, "item=widget&qty=3")
+        .await
+        .unwrap();
+    assert_eq!(payload.into_inner(), widget());
+}
+
+#[tokio::test]
+async fn oversized_silcrow_request_gets_fragment_and_toast() {
+    let response = post_limited("text/html", true, OVERSIZED)
+        .await
+        .unwrap_err();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let toast_cookie = response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .any(|cookie| cookie.to_str().unwrap().starts_with("silcrow_toasts="));
+    assert!(toast_cookie);
+    let body = body_text(response).await;
+    assert!(body.starts_with("<div class=\"silcrow-error\""));
+    assert!(body.contains("data-status=\"413\""));
+}
+
+#[tokio::test]
+async fn oversized_api_request_gets_json() {
+    let response = post_limited("application/json", false, OVERSIZED)
+        .await
+        .unwrap_err();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert_eq!(body["error"]["status"], 413);
+}
@@ -0,0 +1,96 @@
+// tests/pending_toasts.rs
+//
+// PendingToasts: the toast cookie read and cleared on the server, for pages
+// that render their toasts themselves.
+
+use axum::extract::FromRequestParts;
+use axum::http::{Request, header};
+use axum::response::{IntoResponse, Response};
+use runtime::{PendingToasts, ToastLevel, html, navigate, response::ResponseExt};
+
+// ── Helpers ─────────────────────────────────────────────────
+
+fn set_cookies(response: &Response) -> Vec<String> {
+    response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect()
+}
+
+/// The `name=value` part of the toast Set-Cookie, as a browser would send it back.
+fn toast_pair(response: &Response) -> String {
+    set_cookies(response)
+        .into_iter()
+        .find(|c| c.starts_with("silcrow_toasts="))
+        .expect("toast cookie")
+        .split(';')
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+async fn extract(cookie: Option<&str>) -> PendingToasts {
+    let mut request = Request::get("/");
+    if let Some(cookie) = cookie {
+        request = request.header(header::COOKIE, cookie);
+    }
+    let (mut parts, _) = request.body(()).unwrap().into_parts();
+    PendingToasts::from_request_parts(&mut parts, &())
+        .await
+        .unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Reading
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn reads_toasts_set_before_a_redirect() {
+    let redirect = navigate("/orders")
+        .with_toast("Order <placed>", ToastLevel::Success)
+        .with_rich_toast("See <b>details</b>", ToastLevel::Info)
+        .into_response();
+    let pending = extract(Some(&toast_pair(&redirect))).await;
+
+    assert_eq!(pending.0.len(), 2);
+    assert_eq!(pending.0[0].message, "Order <placed>");
+    assert_eq!(
+        pending.to_html(),
+        "<div class=\"silcrow-toasts\" role=\"status\">\
+         <p class=\"silcrow-toast\" data-level=\"success\">Order &lt;placed&gt;</p>\
+         <p class=\"silcrow-toast\" data-level=\"info\">See <b>details</b></p>\
+         </div>"
+    );
+}
+
+#[tokio::test]
+async fn missing_or_garbled_cookie_is_empty() {
+    assert!(extract(None).await.is_empty());
+    let pending = extract(Some("silcrow_toasts=not-json")).await;
+    assert!(pending.is_empty());
+    assert_eq!(pending.to_html(), "");
+}
+
+#[tokio::test]
+async fn forged_markup_in_the_cookie_is_sanitized() {
+    let json = r#"[{"message":"x","level":"info","html":"<script>alert(1)</script><b>ok</b> &amp; <a href=\"javascript:alert(1)\">x</a>"}]"#;
+    let cookie = format!("silcrow_toasts={}", urlencoding::encode(json));
+    let html = extract(Some(&cookie)).await.to_html();
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;<b>ok</b> &amp; <a>x</a>"));
+}
+
+// ════════════════════════════════════════════════════════════
+// Clearing
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn returning_it_clears_the_cookie() {
+    let response = (PendingToasts::default(), html("<p>page</p>")).into_response();
+    let cookies = set_cookies(&response);
+    assert_eq!(cookies.len(), 1);
+    assert!(cookies[0].starts_with("silcrow_toasts="));
+    assert!(cookies[0].contains("Max-Age=0"));
+}
@@ -0,0 +1,274 @@
+// tests/pilcrow_error.rs
+//
+// PilcrowError: one error, rendered as HTML or JSON to match the request.
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::FromRequestParts;
+use axum::http::{Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use runtime::{
+    PilcrowError, RequestMode, ResponseConfig, SilcrowRequest, ToastLevel, response::ResponseExt,
+};
+use tower::ServiceExt;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn body_string(response: Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn content_type(response: &Response) -> String {
+    response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+async fn request(accept: &str) -> SilcrowRequest {
+    let (mut parts, _) = Request::get("/")
+        .header("silcrow-target", "true")
+        .header("accept", accept)
+        .body(())
+        .unwrap()
+        .into_parts();
+    SilcrowRequest::from_request_parts(&mut parts, &())
+        .await
+        .unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// HTML mode
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn html_mode_renders_escaped_fragment() {
+    let response = PilcrowError::not_found("No <order> here")
+        .with_detail("id=7")
+        .for_request(&request("text/html").await)
+        .into_response();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(content_type(&response).contains("text/html"));
+    assert_eq!(
+        body_string(response).await,
+        "<div class=\"silcrow-error\" role=\"alert\" data-status=\"404\">\
+         <p>No &lt;order&gt; here</p><pre>id=7</pre></div>"
+    );
+}
+
+#[tokio::test]
+async fn renders_html_without_a_mode() {
+    let response = PilcrowError::forbidden("Nope").into_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(content_type(&response).contains("text/html"));
+}
+
+#[tokio::test]
+async fn renders_in_the_layer_recorded_mode_without_for_request() {
+    async fn missing() -> Result<&'static str, PilcrowError> {
+        Err(PilcrowError::not_found("No such order"))
+    }
+    let app = Router::new()
+        .route("/", get(missing))
+        .layer(ResponseConfig::new());
+    let response = app
+        .oneshot(
+            Request::get("/")
+                .header("accept", "application/json")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(content_type(&response).contains("application/json"));
+    let body: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+    assert_eq!(body["error"]["message"], "No such order");
+}
+
+#[tokio::test]
+async fn html_mode_toast_uses_cookie() {
+    let response = PilcrowError::bad_request("Invalid")
+        .with_toast("Check the form", ToastLevel::Error)
+        .into_response();
+    assert!(
+        response
+            .headers()
+            .get_all("set-cookie")
+            .iter()
+            .any(|c| c.to_str().unwrap().starts_with("silcrow_toasts="))
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// JSON mode
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn json_mode_renders_error_object() {
+    let response = PilcrowError::unauthorized("Sign in first")
+        .for_request(&request("application/json").await)
+        .into_response();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(content_type(&response).contains("application/json"));
+    let body: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({"error": {"status": 401, "message": "Sign in first"}})
+    );
+}
+
+#[tokio::test]
+async fn json_mode_carries_toasts_in_body() {
+    let response = PilcrowError::internal("Boom")
+        .with_mode(RequestMode::Json)
+        .with_toast("Something went wrong", ToastLevel::Error)
+        .into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+    assert_eq!(body["error"]["message"], "Boom");
+    assert_eq!(body["_toasts"][0]["message"], "Something went wrong");
+}
+
+// ════════════════════════════════════════════════════════════
+// Error conversion
+// ════════════════════════════════════════════════════════════
+
+#[derive(Debug)]
+struct DbError(std::io::Error);
+
+impl std::fmt::Display for DbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "query failed")
+    }
+}
+
+impl std::error::Error for DbError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn load() -> Result<(), PilcrowError> {
+    Err(DbError(std::io::Error::other("connection reset")))?;
+    Ok(())
+}
+
+async fn failing() -> Result<&'static str, PilcrowError> {
+    load()?;
+    Ok("unreachable")
+}
+
+async fn call(app: Router) -> Response {
+    app.oneshot(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[test]
+fn question_mark_converts_with_the_chain_outside_a_router() {
+    let error = load().unwrap_err();
+    assert_eq!(error.status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(error.message, "Internal Server Error");
+    assert!(
+        error
+            .detail
+            .as_deref()
+            .unwrap()
+            .starts_with("query failed: connection reset"),
+        "debug builds expose the chain"
+    );
+}
+
+#[tokio::test]
+async fn response_config_redacts_per_router() {
+    let redacted = Router::new()
+        .route("/", get(failing))
+        .layer(ResponseConfig::new().expose_error_details(false));
+    let response = call(redacted).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = body_string(response).await;
+    assert!(body.contains("Internal Server Error"));
+    assert!(!body.contains("connection reset"), "got: {body}");
+
+    let exposed = Router::new()
+        .route("/", get(failing))
+        .layer(ResponseConfig::new().expose_error_details(true));
+    let body = body_string(call(exposed).await).await;
+    assert!(
+        body.contains("query failed: connection reset"),
+        "got: {body}"
+    );
+}
+
+#[tokio::test]
+async fn question_mark_renders_in_the_request_mode() {
+    let app = Router::new()
+        .route("/", get(failing))
+        .layer(ResponseConfig::new());
+    let response = app
+        .oneshot(
+            Request::get("/")
+                .header("accept", "application/json")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(content_type(&response).contains("application/json"));
+    let body: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+    assert_eq!(body["error"]["message"], "Internal Server Error");
+
+    let app = Router::new()
+        .route("/", get(failing))
+        .layer(ResponseConfig::new());
+    let response = app
+        .oneshot(
+            Request::get("/")
+                .header("accept", "text/html")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert!(content_type(&response).contains("text/html"));
+}
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn anyhow_errors_convert_with_their_context() {
+    use anyhow::Context;
+
+    fn load_with_context() -> Result<(), PilcrowError> {
+        Err(std::io::Error::other("connection reset")).context("loading order 7")?;
+        Ok(())
+    }
+    fn bail() -> Result<(), PilcrowError> {
+        Err(anyhow::anyhow!("plain anyhow"))?;
+        Ok(())
+    }
+
+    let error = load_with_context().unwrap_err();
+    assert_eq!(error.status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(
+        error
+            .detail
+            .as_deref()
+            .unwrap()
+            .starts_with("loading order 7: connection reset")
+    );
+    assert!(
+        bail()
+            .unwrap_err()
+            .detail
+            .unwrap()
+            .starts_with("plain anyhow")
+    );
+    // Plain std errors still convert.
+    assert!(load().is_err());
+}
@@ -0,0 +1,84 @@
+// tests/redis_hub.rs
+//
+// RedisHub connection handling and the envelopes it relays. Relaying itself
+// needs a live Redis server.
+
+#![cfg(feature = "redis")]
+
+use runtime::live::{LiveEvent, RedisEnvelope, RedisHub};
+use runtime::{SilcrowEvent, ToastLevel, WsEvent, WsHub};
+use serde_json::json;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+fn round_trip(envelope: &RedisEnvelope) -> RedisEnvelope {
+    let payload = serde_json::to_string(envelope).unwrap();
+    serde_json::from_str(&payload).unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Connecting
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn connect_reports_unreachable_server() {
+    let result = RedisHub::connect("redis://127.0.0.1:1/", "silcrow", WsHub::new()).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn connect_rejects_invalid_url() {
+    let result = RedisHub::connect("not a url", "silcrow", WsHub::new()).await;
+    assert!(result.is_err());
+}
+
+// ════════════════════════════════════════════════════════════
+// Envelopes
+// ════════════════════════════════════════════════════════════
+
+#[test]
+fn hub_envelopes_round_trip() {
+    let envelopes = [
+        RedisEnvelope::Hub {
+            room: None,
+            event: WsEvent::patch(json!({"n": 1}), "#count"),
+        },
+        RedisEnvelope::Hub {
+            room: Some("orders:42".to_owned()),
+            event: WsEvent::toast("Shipped", ToastLevel::Success),
+        },
+    ];
+    for envelope in &envelopes {
+        assert_eq!(&round_trip(envelope), envelope);
+    }
+}
+
+#[test]
+fn broadcaster_envelopes_round_trip_to_the_same_sse_event() {
+    let event = LiveEvent::custom("order:shipped", json!({"id": 42}));
+    let envelope = RedisEnvelope::Broadcaster {
+        topic: Some("orders:42".to_owned()),
+        event: event.clone(),
+    };
+    let RedisEnvelope::Broadcaster {
+        topic,
+        event: relayed,
+    } = round_trip(&envelope)
+    else {
+        panic!("expected a broadcaster envelope");
+    };
+    assert_eq!(topic.as_deref(), Some("orders:42"));
+    assert_eq!(SilcrowEvent::from(relayed), SilcrowEvent::from(event));
+}
+
+#[test]
+fn envelopes_name_their_destination() {
+    let envelope = RedisEnvelope::Broadcaster {
+        topic: None,
+        event: LiveEvent::invalidate("#orders"),
+    };
+    assert_eq!(
+        serde_json::to_value(&envelope).unwrap(),
+        json!({"to": "broadcaster", "event": {"type": "invalidate", "target": "#orders"}})
+    );
+}
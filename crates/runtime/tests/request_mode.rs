@@ -0,0 +1,439 @@
+// tests/request_mode.rs
+//
+// SilcrowRequest extraction, content negotiation and conditional GETs.
+
+use axum::Extension;
+use axum::extract::FromRequestParts;
+use axum::http::Request;
+use axum_extra::TypedHeader;
+use runtime::response::headers::{SilcrowCurrentUrl, SilcrowTarget};
+use runtime::{
+    Feature, FormatParam, IfNoneMatch, PROTOCOL_VERSION, RenderMode, RequestMode, SilcrowRequest,
+};
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn extract(request: Request<()>) -> SilcrowRequest {
+    let (mut parts, _) = request.into_parts();
+    SilcrowRequest::from_request_parts(&mut parts, &())
+        .await
+        .unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Header negotiation
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn browser_request_prefers_html() {
+    let req = Request::get("/")
+        .header("accept", "text/html,application/xhtml+xml,*/*;q=0.8")
+        .body(())
+        .unwrap();
+    assert_eq!(extract(req).await.preferred_mode(), RequestMode::Html);
+}
+
+#[tokio::test]
+async fn silcrow_json_request_prefers_json() {
+    let req = Request::get("/")
+        .header("silcrow-target", "true")
+        .header("accept", "application/json")
+        .body(())
+        .unwrap();
+    assert_eq!(extract(req).await.preferred_mode(), RequestMode::Json);
+}
+
+#[tokio::test]
+async fn target_selector_is_exposed() {
+    let req = Request::get("/")
+        .header("silcrow-target", "#cart")
+        .body(())
+        .unwrap();
+    let silcrow = extract(req).await;
+    assert!(silcrow.is_silcrow);
+    assert_eq!(silcrow.target.as_deref(), Some("#cart"));
+}
+
+#[tokio::test]
+async fn bare_target_header_names_no_element() {
+    let req = Request::get("/")
+        .header("silcrow-target", "true")
+        .body(())
+        .unwrap();
+    let silcrow = extract(req).await;
+    assert!(silcrow.is_silcrow);
+    assert_eq!(silcrow.target, None);
+}
+
+#[tokio::test]
+async fn current_url_is_exposed() {
+    let req = Request::get("/cart/items")
+        .header("silcrow-target", "true")
+        .header("silcrow-current-url", "https://shop.test/orders?page=2")
+        .body(())
+        .unwrap();
+    assert_eq!(
+        extract(req).await.current_url.as_deref(),
+        Some("https://shop.test/orders?page=2")
+    );
+}
+
+#[tokio::test]
+async fn navigation_flags_follow_headers() {
+    let req = Request::get("/")
+        .header("silcrow-target", "true")
+        .header("silcrow-history-restore", "true")
+        .body(())
+        .unwrap();
+    let silcrow = extract(req).await;
+    assert!(silcrow.is_history_restore);
+    assert!(!silcrow.is_boosted);
+
+    let req = Request::get("/")
+        .header("silcrow-target", "true")
+        .header("silcrow-boosted", "true")
+        .body(())
+        .unwrap();
+    let silcrow = extract(req).await;
+    assert!(silcrow.is_boosted);
+    assert!(!silcrow.is_history_restore);
+}
+
+#[tokio::test]
+async fn render_mode_splits_fragments_from_pages() {
+    let page = Request::get("/")
+        .header("accept", "text/html")
+        .body(())
+        .unwrap();
+    assert_eq!(extract(page).await.render_mode(), RenderMode::Document);
+
+    let swap = Request::get("/")
+        .header("silcrow-target", "#list")
+        .header("accept", "text/html")
+        .body(())
+        .unwrap();
+    assert_eq!(extract(swap).await.render_mode(), RenderMode::Fragment);
+
+    let data = Request::get("/")
+        .header("silcrow-target", "true")
+        .header("accept", "application/json")
+        .body(())
+        .unwrap();
+    let mode = extract(data).await.render_mode();
+    assert_eq!(mode, RenderMode::Json);
+    assert!(!mode.is_html());
+}
+
+#[tokio::test]
+async fn fragment_and_document_predicates() {
+    let page = extract(
+        Request::get("/")
+            .header("accept", "text/html")
+            .body(())
+            .unwrap(),
+    )
+    .await;
+    assert!(page.wants_document() && !page.wants_fragment() && !page.is_partial());
+
+    let body_swap = extract(
+        Request::get("/")
+            .header("silcrow-target", "true")
+            .header("accept", "text/html")
+            .body(())
+            .unwrap(),
+    )
+    .await;
+    assert!(body_swap.wants_fragment() && !body_swap.is_partial());
+
+    let element_swap = extract(
+        Request::get("/")
+            .header("silcrow-target", "#cart")
+            .header("accept", "text/html")
+            .body(())
+            .unwrap(),
+    )
+    .await;
+    assert!(element_swap.wants_fragment() && element_swap.is_partial());
+
+    let api = extract(
+        Request::get("/")
+            .header("accept", "application/json")
+            .body(())
+            .unwrap(),
+    )
+    .await;
+    assert!(!api.wants_fragment() && !api.wants_document());
+}
+
+#[tokio::test]
+async fn silcrow_headers_extract_as_typed_headers() {
+    let (mut parts, _) = Request::get("/")
+        .header("silcrow-target", "utf-8''%23caf%C3%A9")
+        .body(())
+        .unwrap()
+        .into_parts();
+    let TypedHeader(target) = TypedHeader::<SilcrowTarget>::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert_eq!(target.as_str(), "#café");
+    assert_eq!(String::from(target), "#café");
+
+    let missing = Option::<TypedHeader<SilcrowCurrentUrl>>::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+}
+
+// ════════════════════════════════════════════════════════════
+// Per-request caching
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn later_extractions_reuse_the_first() {
+    let (mut parts, _) = Request::get("/")
+        .header("accept", "application/json")
+        .body(())
+        .unwrap()
+        .into_parts();
+    let first = SilcrowRequest::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert!(parts.extensions.get::<SilcrowRequest>().is_some());
+
+    // Headers changed after the first parse (e.g. by middleware) are not
+    // re-read within the same request.
+    parts.headers.insert("accept", "text/html".parse().unwrap());
+    let second = SilcrowRequest::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert_eq!(first.preferred_mode(), RequestMode::Json);
+    assert_eq!(second.preferred_mode(), RequestMode::Json);
+}
+
+// ════════════════════════════════════════════════════════════
+// ?format= override
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn format_query_overrides_accept() {
+    let req = Request::get("/items?page=2&format=json")
+        .header("accept", "text/html")
+        .body(())
+        .unwrap();
+    let silcrow = extract(req).await;
+    assert_eq!(silcrow.format_override, Some(RequestMode::Json));
+    assert_eq!(silcrow.preferred_mode(), RequestMode::Json);
+}
+
+#[tokio::test]
+async fn format_query_can_force_html() {
+    let req = Request::get("/items?format=HTML")
+        .header("accept", "application/json")
+        .body(())
+        .unwrap();
+    assert_eq!(extract(req).await.preferred_mode(), RequestMode::Html);
+}
+
+#[tokio::test]
+async fn unknown_format_value_is_ignored() {
+    let req = Request::get("/items?format=xml")
+        .header("accept", "text/html")
+        .body(())
+        .unwrap();
+    let silcrow = extract(req).await;
+    assert_eq!(silcrow.format_override, None);
+    assert_eq!(silcrow.preferred_mode(), RequestMode::Html);
+}
+
+#[tokio::test]
+async fn format_param_name_is_configurable() {
+    let mut req = Request::get("/items?format=html&_as=json")
+        .header("accept", "text/html")
+        .body(())
+        .unwrap();
+    req.extensions_mut().insert(FormatParam("_as"));
+    assert_eq!(extract(req).await.preferred_mode(), RequestMode::Json);
+}
+
+#[tokio::test]
+async fn format_param_via_extension_layer() {
+    use axum::{Router, body::Body, routing::get};
+    use tower::ServiceExt;
+
+    let app = Router::new()
+        .route(
+            "/",
+            get(|req: SilcrowRequest| async move { format!("{:?}", req.preferred_mode()) }),
+        )
+        .layer(Extension(FormatParam("view")));
+    let response = app
+        .oneshot(
+            Request::get("/?view=json")
+                .header("accept", "text/html")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"Json");
+}
+
+// ════════════════════════════════════════════════════════════
+// Conditional GETs
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn if_none_match_tags_are_parsed_weakly() {
+    let req = Request::get("/cart")
+        .header("if-none-match", r#"W/"v1", "v2""#)
+        .body(())
+        .unwrap();
+    let silcrow = extract(req).await;
+    assert_eq!(
+        silcrow.if_none_match,
+        IfNoneMatch(vec!["v1".into(), "v2".into()])
+    );
+    assert!(silcrow.fresh("v1"));
+    assert!(silcrow.fresh("\"v2\""));
+    assert!(!silcrow.fresh("v3"));
+}
+
+#[tokio::test]
+async fn wildcard_matches_and_absence_never_does() {
+    let req = Request::get("/cart")
+        .header("if-none-match", "*")
+        .body(())
+        .unwrap();
+    assert!(extract(req).await.fresh("anything"));
+
+    let (mut parts, _) = Request::get("/cart").body(()).unwrap().into_parts();
+    let inm = IfNoneMatch::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert!(inm.is_empty());
+    assert!(!inm.matches("v1"));
+}
+
+#[tokio::test]
+async fn not_modified_echoes_the_etag() {
+    use axum::response::IntoResponse;
+    use runtime::{html, not_modified, response::ResponseExt};
+
+    let response = not_modified("v1").into_response();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()["etag"], "\"v1\"");
+
+    let response = html("<p>cart</p>").with_etag("W/\"v2\"").into_response();
+    assert_eq!(response.headers()["etag"], "W/\"v2\"");
+}
+
+// ════════════════════════════════════════════════════════════
+// Protocol versions
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn current_client_supports_every_feature() {
+    let req = Request::get("/")
+        .header("silcrow-target", "true")
+        .header("silcrow-version", PROTOCOL_VERSION.to_string())
+        .body(())
+        .unwrap();
+    let silcrow = extract(req).await;
+    assert_eq!(silcrow.client_version, Some(PROTOCOL_VERSION));
+    assert!(silcrow.supports(Feature::Swap));
+    assert!(silcrow.supports(Feature::JsonPatch));
+}
+
+#[tokio::test]
+async fn unversioned_or_garbled_clients_support_nothing_new() {
+    let legacy = Request::get("/")
+        .header("silcrow-target", "true")
+        .body(())
+        .unwrap();
+    let silcrow = extract(legacy).await;
+    assert_eq!(silcrow.client_version, None);
+    assert!(!silcrow.supports(Feature::Swap));
+
+    let garbled = Request::get("/")
+        .header("silcrow-target", "true")
+        .header("silcrow-version", "next")
+        .body(())
+        .unwrap();
+    assert!(!extract(garbled).await.supports(Feature::Hooks));
+}
+
+#[tokio::test]
+async fn te_trailers_is_detected_among_codings() {
+    let req = Request::get("/")
+        .header("te", "gzip;q=0.5, Trailers")
+        .body(())
+        .unwrap();
+    assert!(extract(req).await.accepts_trailers);
+
+    let req = Request::get("/").header("te", "gzip").body(()).unwrap();
+    assert!(!extract(req).await.accepts_trailers);
+}
+
+// ════════════════════════════════════════════════════════════
+// Builders
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn builders_negotiate_like_extracted_requests() {
+    let browser = extract(
+        Request::get("/")
+            .header("accept", "text/html,application/xhtml+xml,*/*;q=0.8")
+            .body(())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(
+        SilcrowRequest::browser().render_mode(),
+        browser.render_mode()
+    );
+
+    let swap = extract(
+        Request::get("/")
+            .header("silcrow-target", "#cart")
+            .header("silcrow-version", PROTOCOL_VERSION.to_string())
+            .header("accept", "text/html")
+            .body(())
+            .unwrap(),
+    )
+    .await;
+    let built = SilcrowRequest::silcrow_html("#cart");
+    assert_eq!(built.render_mode(), swap.render_mode());
+    assert_eq!(built.target, swap.target);
+    assert_eq!(built.client_version, swap.client_version);
+    assert!(built.is_partial());
+
+    assert_eq!(SilcrowRequest::api_json().render_mode(), RenderMode::Json);
+    assert_eq!(
+        SilcrowRequest::silcrow_html("true").target,
+        None,
+        "a bare silcrow-target names no element"
+    );
+}
+
+#[test]
+fn setters_fill_the_remaining_fields() {
+    let req = SilcrowRequest::default()
+        .silcrow("#list")
+        .accept(RequestMode::Json)
+        .format(RequestMode::Html)
+        .current_url("/orders?page=2")
+        .boosted()
+        .history_restore()
+        .if_none_match("W/\"v7\"")
+        .client_version(None)
+        .trailers();
+    assert_eq!(req.preferred_mode(), RequestMode::Html);
+    assert_eq!(req.current_url.as_deref(), Some("/orders?page=2"));
+    assert!(req.is_boosted && req.is_history_restore);
+    assert!(req.accepts_trailers);
+    assert!(req.fresh("v7"));
+    assert!(!req.supports(Feature::Swap));
+}
@@ -2,9 +2,19 @@
 //
 // Deep response verification — body content, content types, cookies, and toast transport.
 
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
-use runtime::{ToastLevel, html, json, navigate, response::ResponseExt};
+use http_body_util::BodyExt;
+use runtime::response::HtmlResponse;
+use runtime::{
+    Cookie, IntoPilcrow, JsonPatchOp, SilcrowRequest, Toast, ToastLevel, accepted, download,
+    download_stream, html, json, json_patch, navigate, no_content, response::ResponseExt,
+};
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio_stream::StreamExt;
 
 // ── Helpers ─────────────────────────────────────────────────
 
@@ -64,6 +74,146 @@ async fn html_response_with_dynamic_content() {
     assert_eq!(body, markup);
 }
 
+#[tokio::test]
+async fn html_oob_fragments_follow_main_content() {
+    let response = html("<ul id=\"items\"></ul>")
+        .with_oob_fragment("#cart-count", "3")
+        .with_oob_fragment("#sidebar", "<p>Updated</p>")
+        .into_response();
+    let body = body_string(response).await;
+    assert_eq!(
+        body,
+        "<ul id=\"items\"></ul>\
+         <template s-oob=\"#cart-count\">3</template>\
+         <template s-oob=\"#sidebar\"><p>Updated</p></template>"
+    );
+}
+
+#[tokio::test]
+async fn html_oob_selector_is_attribute_escaped() {
+    let response = html("")
+        .with_oob_fragment("[data-x=\"a&b\"]", "ok")
+        .into_response();
+    let body = body_string(response).await;
+    assert_eq!(
+        body,
+        "<template s-oob=\"[data-x=&quot;a&amp;b&quot;]\">ok</template>"
+    );
+}
+
+#[test]
+fn static_markup_is_not_copied() {
+    assert!(matches!(html("<p>static</p>").data, Cow::Borrowed(_)));
+    assert!(matches!(
+        html(String::from("<p>owned</p>")).data,
+        Cow::Owned(_)
+    ));
+}
+
+fn layout(body: String) -> String {
+    format!("<!DOCTYPE html><html><body><main>{body}</main></body></html>")
+}
+
+#[tokio::test]
+async fn in_layout_wraps_full_page_loads() {
+    let response = html("<ul></ul>")
+        .in_layout(&SilcrowRequest::browser(), layout)
+        .into_response();
+    assert_eq!(
+        body_string(response).await,
+        "<!DOCTYPE html><html><body><main><ul></ul></main></body></html>"
+    );
+}
+
+#[tokio::test]
+async fn in_layout_sends_fragment_to_silcrow() {
+    let response = html("<ul></ul>")
+        .in_layout(&SilcrowRequest::silcrow_html("true"), layout)
+        .into_response();
+    assert_eq!(body_string(response).await, "<ul></ul>");
+}
+
+#[tokio::test]
+async fn html_render_uses_display() {
+    struct Greeting<'a>(&'a str);
+    impl std::fmt::Display for Greeting<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "<h1>Hello, {}</h1>", self.0)
+        }
+    }
+    let response = HtmlResponse::render(&Greeting("Ada")).unwrap();
+    assert_eq!(
+        body_string(response.into_response()).await,
+        "<h1>Hello, Ada</h1>"
+    );
+}
+
+#[tokio::test]
+async fn html_render_failure_is_a_500() {
+    struct Broken;
+    impl std::fmt::Display for Broken {
+        fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            Err(std::fmt::Error)
+        }
+    }
+    let error = HtmlResponse::render(&Broken).err().unwrap();
+    assert_eq!(error.status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+// ════════════════════════════════════════════════════════════
+// Patch Body Sidecar
+// ════════════════════════════════════════════════════════════
+
+fn large_rows() -> serde_json::Value {
+    serde_json::json!(
+        (0..500)
+            .map(|i| format!("row <{i}> & more"))
+            .collect::<Vec<_>>()
+    )
+}
+
+#[tokio::test]
+async fn small_patches_stay_in_header() {
+    let response = html("<p>ok</p>")
+        .patch_target("#count", &serde_json::json!({"n": 1}))
+        .into_response();
+    assert!(get_header(&response, "silcrow-patch").is_some());
+    assert!(get_header(&response, "silcrow-patch-body").is_none());
+    assert_eq!(body_string(response).await, "<p>ok</p>");
+}
+
+#[tokio::test]
+async fn large_html_patches_move_into_body() {
+    let response = html("<p>ok</p>")
+        .patch_target("#rows", &large_rows())
+        .into_response();
+    assert!(get_header(&response, "silcrow-patch").is_none());
+    assert_eq!(get_header(&response, "silcrow-patch-body").unwrap(), "1");
+
+    let body = body_string(response).await;
+    let json = body
+        .strip_prefix("<p>ok</p><template s-patch>")
+        .and_then(|rest| rest.strip_suffix("</template>"))
+        .unwrap();
+    assert!(!json.contains('<') && !json.contains('&'));
+    let patches: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(patches[0]["target"], "#rows");
+    assert_eq!(patches[0]["data"], large_rows());
+}
+
+#[tokio::test]
+async fn large_json_patches_move_under_reserved_key() {
+    let response = json(serde_json::json!([1, 2]))
+        .patch_target("#rows", &large_rows())
+        .into_response();
+    assert!(get_header(&response, "silcrow-patch").is_none());
+    assert_eq!(get_header(&response, "silcrow-patch-body").unwrap(), "1");
+
+    let parsed: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+    assert_eq!(parsed["data"], serde_json::json!([1, 2]));
+    assert_eq!(parsed["_patches"][0]["data"], large_rows());
+}
+
 // ════════════════════════════════════════════════════════════
 // JSON Response Body
 // ════════════════════════════════════════════════════════════
@@ -116,6 +266,31 @@ async fn json_complex_nested_struct() {
     assert_eq!(parsed["tags"][1], "b");
 }
 
+#[tokio::test]
+async fn json_serialization_failure_returns_problem_details() {
+    struct Broken;
+    impl serde::Serialize for Broken {
+        fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("broken payload"))
+        }
+    }
+
+    let response = json(Broken).into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let ct = get_header(&response, "content-type").unwrap();
+    assert_eq!(ct, "application/problem+json");
+    let body = body_string(response).await;
+    let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(parsed["status"], 500);
+    assert!(
+        parsed["detail"]
+            .as_str()
+            .unwrap()
+            .contains("broken payload")
+    );
+    assert!(parsed["payload_type"].as_str().unwrap().contains("Broken"));
+}
+
 // ════════════════════════════════════════════════════════════
 // JSON Toast Injection
 // ════════════════════════════════════════════════════════════
@@ -161,6 +336,102 @@ async fn json_multiple_toasts() {
     assert_eq!(toasts[2]["message"], "Third");
 }
 
+#[tokio::test]
+async fn json_structured_toast_carries_options() {
+    let toast = Toast::new("Deleted", ToastLevel::Warning)
+        .with_duration(Duration::from_secs(8))
+        .with_id("delete-42")
+        .with_action("Undo", "/items/42/restore");
+    let response = json(serde_json::json!({})).toast(toast).into_response();
+    let body = body_string(response).await;
+    let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        parsed["_toasts"][0],
+        serde_json::json!({
+            "message": "Deleted",
+            "level": "warning",
+            "duration_ms": 8000,
+            "id": "delete-42",
+            "action": {"label": "Undo", "href": "/items/42/restore"},
+        })
+    );
+}
+
+#[tokio::test]
+async fn keyed_toast_replaces_earlier_one() {
+    let response = json(serde_json::json!({}))
+        .with_toast_keyed("save", "Saving…", ToastLevel::Info)
+        .with_toast("Unrelated", ToastLevel::Info)
+        .with_toast_keyed("save", "Saved", ToastLevel::Success)
+        .into_response();
+    let body = body_string(response).await;
+    let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        parsed["_toasts"],
+        serde_json::json!([
+            {"message": "Unrelated", "level": "info"},
+            {"message": "Saved", "level": "success", "key": "save"},
+        ])
+    );
+}
+
+#[tokio::test]
+async fn rich_toast_keeps_only_inline_markup() {
+    let response = json(serde_json::json!({}))
+        .with_rich_toast(
+            "<b>Bob</b> said <script>alert(1)</script> \
+             <a href=\"/posts/7\" onclick=\"x()\">see</a> \
+             <a href=\"javascript:alert(1)\">bad</a> <i>open",
+            ToastLevel::Info,
+        )
+        .into_response();
+    let body = body_string(response).await;
+    let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let toast = &parsed["_toasts"][0];
+    assert_eq!(
+        toast["html"],
+        "<b>Bob</b> said &lt;script&gt;alert(1)&lt;/script&gt; \
+         <a href=\"/posts/7\" rel=\"noopener noreferrer\">see</a> \
+         <a>bad</a> <i>open</i>"
+    );
+    assert_eq!(toast["message"], "Bob said alert(1) see bad open");
+}
+
+#[test]
+fn deserialized_toast_markup_is_sanitized_again() {
+    let toast: Toast = serde_json::from_str(
+        r#"{"message":"hi","level":"info","html":"<img src=x onerror=alert(1)><em>hi</em>"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        toast.html(),
+        Some("&lt;img src=x onerror=alert(1)&gt;<em>hi</em>")
+    );
+
+    let rich = Toast::rich("<b>Tom &amp; Jerry</b>", ToastLevel::Info);
+    let round_trip: Toast = serde_json::from_str(&serde_json::to_string(&rich).unwrap()).unwrap();
+    assert_eq!(round_trip.html(), rich.html());
+}
+
+#[tokio::test]
+async fn plain_toast_omits_optional_fields() {
+    let response = json(serde_json::json!({}))
+        .with_toast("Saved", ToastLevel::Success)
+        .into_response();
+    let body = body_string(response).await;
+    let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        parsed["_toasts"][0],
+        serde_json::json!({"message": "Saved", "level": "success"})
+    );
+}
+
+#[test]
+fn toast_deserializes_without_optional_fields() {
+    let toast: Toast = serde_json::from_str(r#"{"message":"Hi","level":"info"}"#).unwrap();
+    assert_eq!(toast, Toast::new("Hi", ToastLevel::Info));
+}
+
 // ════════════════════════════════════════════════════════════
 // HTML Toast Cookie
 // ════════════════════════════════════════════════════════════
@@ -204,6 +475,55 @@ async fn html_toast_cookie_decodes_to_valid_json() {
     assert_eq!(parsed[0]["message"], "Hello");
 }
 
+// ════════════════════════════════════════════════════════════
+// Cookies
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn with_cookie_sets_cookie() {
+    let response = html("<p>Hi</p>")
+        .with_cookie(Cookie::new("session", "abc"))
+        .into_response();
+    assert_eq!(get_cookies(&response), vec!["session=abc".to_string()]);
+}
+
+#[tokio::test]
+async fn cookie_builder_sets_attributes() {
+    let response = json(serde_json::json!({}))
+        .cookie("theme", "dark", |c| {
+            c.path("/").http_only(true).secure(true)
+        })
+        .into_response();
+    assert_eq!(
+        get_cookies(&response),
+        vec!["theme=dark; HttpOnly; Secure; Path=/".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn remove_cookie_sends_expired_cookie() {
+    let response = html("<p>Bye</p>").remove_cookie("session").into_response();
+    let cookies = get_cookies(&response);
+    assert_eq!(cookies.len(), 1);
+    let cookie = &cookies[0];
+    assert!(cookie.starts_with("session=;"), "got: {cookie}");
+    assert!(cookie.contains("Path=/"), "got: {cookie}");
+    assert!(cookie.contains("Max-Age=0"), "got: {cookie}");
+    assert!(cookie.contains("Expires="), "got: {cookie}");
+}
+
+#[tokio::test]
+async fn cookies_and_toasts_are_both_sent() {
+    let response = html("<p>Hi</p>")
+        .cookie("theme", "dark", |c| c)
+        .with_toast("Saved", ToastLevel::Success)
+        .into_response();
+    let cookies = get_cookies(&response);
+    assert_eq!(cookies.len(), 2);
+    assert!(cookies.iter().any(|c| c == "theme=dark"));
+    assert!(cookies.iter().any(|c| c.starts_with("silcrow_toasts=")));
+}
+
 // ════════════════════════════════════════════════════════════
 // Navigate Response
 // ════════════════════════════════════════════════════════════
@@ -230,3 +550,190 @@ async fn navigate_toast_via_cookie() {
     let cookies = get_cookies(&response);
     assert!(cookies.iter().any(|c| c.starts_with("silcrow_toasts=")));
 }
+
+// ════════════════════════════════════════════════════════════
+// Empty Responses
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn no_content_carries_side_effects() {
+    let response = no_content()
+        .invalidate_target("#orders")
+        .with_toast("Deleted", ToastLevel::Success)
+        .into_response();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        get_header(&response, "silcrow-invalidate").unwrap(),
+        "#orders"
+    );
+    assert!(
+        get_cookies(&response)
+            .iter()
+            .any(|c| c.starts_with("silcrow_toasts="))
+    );
+    assert!(body_bytes(response).await.is_empty());
+}
+
+#[tokio::test]
+async fn accepted_is_202_with_empty_body() {
+    let response = accepted().into_response();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert!(body_bytes(response).await.is_empty());
+}
+
+// ════════════════════════════════════════════════════════════
+// JSON Patch
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn json_patch_serializes_rfc_6902_ops() {
+    let response = json_patch([
+        JsonPatchOp::test("/version", 3),
+        JsonPatchOp::add("/rows/-", serde_json::json!({"id": 9})),
+        JsonPatchOp::remove("/rows/0"),
+        JsonPatchOp::move_from("/draft", "/title"),
+    ])
+    .into_response();
+    assert_eq!(
+        get_header(&response, "content-type").unwrap(),
+        "application/json-patch+json"
+    );
+    let body: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!([
+            {"op": "test", "path": "/version", "value": 3},
+            {"op": "add", "path": "/rows/-", "value": {"id": 9}},
+            {"op": "remove", "path": "/rows/0"},
+            {"op": "move", "from": "/draft", "path": "/title"},
+        ])
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Adapted Responses
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn foreign_responses_take_modifiers() {
+    let response = axum::response::Redirect::to("/login")
+        .pilcrow()
+        .with_toast("Please sign in", ToastLevel::Info)
+        .retarget("#main")
+        .into_response();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(get_header(&response, "location").unwrap(), "/login");
+    assert_eq!(get_header(&response, "silcrow-retarget").unwrap(), "#main");
+    assert!(
+        get_cookies(&response)
+            .iter()
+            .any(|cookie| cookie.starts_with("silcrow_toasts="))
+    );
+}
+
+#[tokio::test]
+async fn adapted_body_is_left_alone() {
+    let response = (StatusCode::CREATED, "made")
+        .pilcrow()
+        .with_status(StatusCode::ACCEPTED)
+        .into_response();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert_eq!(body_string(response).await, "made");
+}
+
+// ════════════════════════════════════════════════════════════
+// Downloads
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn download_sets_disposition_and_guessed_type() {
+    let response = download(b"%PDF-1.7".to_vec(), "report.pdf")
+        .no_cache()
+        .into_response();
+    assert_eq!(
+        get_header(&response, "content-type").unwrap(),
+        "application/pdf"
+    );
+    assert_eq!(
+        get_header(&response, "content-disposition").unwrap(),
+        "attachment; filename=\"report.pdf\"; filename*=UTF-8''report.pdf"
+    );
+    assert_eq!(body_bytes(response).await, b"%PDF-1.7");
+}
+
+#[tokio::test]
+async fn download_encodes_non_ascii_filenames() {
+    let response = download("a,b\r\n", "résumé \"final\".csv")
+        .with_content_type("text/csv")
+        .into_response();
+    assert_eq!(get_header(&response, "content-type").unwrap(), "text/csv");
+    assert_eq!(
+        get_header(&response, "content-disposition").unwrap(),
+        "attachment; filename=\"r_sum_ _final_.csv\"; \
+         filename*=UTF-8''r%C3%A9sum%C3%A9%20%22final%22.csv"
+    );
+}
+
+#[tokio::test]
+async fn download_stream_sends_chunks() {
+    let chunks = tokio_stream::iter(vec![Ok::<_, std::io::Error>("id\r\n"), Ok("1\r\n")]);
+    let response = download_stream(chunks, "ids.csv").into_response();
+    assert!(
+        get_header(&response, "content-type")
+            .unwrap()
+            .starts_with("text/csv")
+    );
+    assert_eq!(body_string(response).await, "id\r\n1\r\n");
+}
+
+fn counted_rows() -> (
+    impl futures_core::Stream<Item = Result<String, std::io::Error>>,
+    Arc<AtomicUsize>,
+) {
+    let rows = Arc::new(AtomicUsize::new(0));
+    let counted = rows.clone();
+    let stream = tokio_stream::iter(["1\r\n", "2\r\n", "3\r\n"]).map(move |row| {
+        counted.fetch_add(1, Ordering::Relaxed);
+        Ok(row.to_owned())
+    });
+    (stream, rows)
+}
+
+fn row_count(rows: Arc<AtomicUsize>) -> impl FnOnce() -> HeaderMap + Send + 'static {
+    move || {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-row-count", rows.load(Ordering::Relaxed).into());
+        trailers
+    }
+}
+
+#[tokio::test]
+async fn download_stream_ends_with_trailers_computed_after_the_body() {
+    let (stream, rows) = counted_rows();
+    let req = SilcrowRequest::default().trailers();
+    let response = download_stream(stream, "rows.csv")
+        .with_trailers(&req, &["x-row-count"], row_count(rows))
+        .into_response();
+    assert_eq!(get_header(&response, "trailer").unwrap(), "x-row-count");
+
+    let collected = response.into_body().collect().await.unwrap();
+    assert_eq!(collected.trailers().unwrap()["x-row-count"], "3");
+    assert_eq!(collected.to_bytes(), "1\r\n2\r\n3\r\n");
+}
+
+#[tokio::test]
+async fn download_stream_trailers_need_te_trailers() {
+    let (stream, rows) = counted_rows();
+    let response = download_stream(stream, "rows.csv")
+        .with_trailers(
+            &SilcrowRequest::default(),
+            &["x-row-count"],
+            row_count(rows),
+        )
+        .into_response();
+    assert_eq!(get_header(&response, "trailer"), None);
+
+    let collected = response.into_body().collect().await.unwrap();
+    assert!(collected.trailers().is_none());
+    assert_eq!(collected.to_bytes(), "1\r\n2\r\n3\r\n");
+}
@@ -0,0 +1,95 @@
+// tests/silcrow_form.rs
+//
+// SilcrowForm<T>: form bodies that fall back to raw values and field errors
+// instead of rejecting.
+
+use axum::extract::FromRequest;
+use axum::http::{Request, StatusCode, header};
+use runtime::{FieldError, SilcrowForm};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Signup {
+    email: String,
+    age: u8,
+}
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn post(body: &'static str) -> Result<SilcrowForm<Signup>, (StatusCode, &'static str)> {
+    let request = Request::post("/signup")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(axum::body::Body::from(body))
+        .unwrap();
+    SilcrowForm::from_request(request, &()).await
+}
+
+// ════════════════════════════════════════════════════════════
+// Parsing
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn valid_form_deserializes() {
+    let form = post("email=a%40b.c&age=30").await.unwrap();
+    assert_eq!(
+        form.into_result().unwrap(),
+        Signup {
+            email: "a@b.c".into(),
+            age: 30
+        }
+    );
+}
+
+#[tokio::test]
+async fn bad_value_keeps_input_and_names_the_field() {
+    let SilcrowForm::Invalid(invalid) = post("email=a%40b.c&age=old").await.unwrap() else {
+        panic!("expected an invalid form");
+    };
+    assert_eq!(invalid.value("email"), Some("a@b.c"));
+    assert_eq!(invalid.value("age"), Some("old"));
+    assert!(invalid.error("age").is_some());
+    assert_eq!(invalid.error("email"), None);
+}
+
+#[tokio::test]
+async fn missing_field_is_reported_against_it() {
+    let invalid = post("email=a%40b.c")
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(
+        invalid.errors,
+        vec![FieldError {
+            field: "age".into(),
+            message: "is required".into(),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn get_reads_the_query_string() {
+    let request = Request::get("/search?email=x%40y.z&age=5")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let form = SilcrowForm::<Signup>::from_request(request, &())
+        .await
+        .unwrap();
+    assert!(matches!(form, SilcrowForm::Valid(Signup { age: 5, .. })));
+}
+
+// ════════════════════════════════════════════════════════════
+// Rejections
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn non_form_body_is_rejected() {
+    let request = Request::post("/signup")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(axum::body::Body::from("{}"))
+        .unwrap();
+    let rejection = SilcrowForm::<Signup>::from_request(request, &())
+        .await
+        .unwrap_err();
+    assert_eq!(rejection.0, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
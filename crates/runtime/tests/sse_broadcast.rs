@@ -0,0 +1,730 @@
+// tests/sse_broadcast.rs
+//
+// SseBroadcaster: publish once, every subscriber stream receives it.
+
+use axum::response::IntoResponse;
+use runtime::{Lagged, LastEventId, SilcrowEvent, SseBroadcaster, SseLagPolicy, SseReplay};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn next_frame(body: &mut axum::body::BodyDataStream) -> String {
+    let chunk = tokio::time::timeout(Duration::from_secs(2), body.next())
+        .await
+        .expect("timed out waiting for SSE frame")
+        .expect("SSE stream ended early")
+        .unwrap();
+    String::from_utf8(chunk.to_vec()).unwrap()
+}
+
+fn subscribe(broadcaster: &SseBroadcaster) -> axum::body::BodyDataStream {
+    broadcaster
+        .handler()
+        .into_response()
+        .into_body()
+        .into_data_stream()
+}
+
+struct Unserializable;
+
+impl serde::Serialize for Unserializable {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("nope"))
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// Publish / subscribe
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn publish_reaches_every_subscriber() {
+    let broadcaster = SseBroadcaster::default();
+    let mut a = subscribe(&broadcaster);
+    let mut b = subscribe(&broadcaster);
+
+    let reached = broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": 1}), "#count"));
+    assert_eq!(reached, 2);
+
+    for body in [&mut a, &mut b] {
+        let frame = next_frame(body).await;
+        assert!(frame.contains("event: patch"), "got: {frame}");
+        assert!(frame.contains(r#""n":1"#), "got: {frame}");
+    }
+}
+
+#[tokio::test]
+async fn every_subscriber_gets_the_same_rendered_frame() {
+    let broadcaster = SseBroadcaster::default();
+    let mut bodies: Vec<_> = (0..3).map(|_| subscribe(&broadcaster)).collect();
+    let mut events = Box::pin(broadcaster.subscribe());
+
+    broadcaster.publish(SilcrowEvent::patch(
+        serde_json::json!({"rows": [1, 2, 3]}),
+        "#table",
+    ));
+
+    let first = next_frame(&mut bodies[0]).await;
+    for body in &mut bodies[1..] {
+        assert_eq!(next_frame(body).await, first);
+    }
+    let event = events.next().await.unwrap();
+    assert_eq!(event.id(), Some("1"));
+}
+
+#[tokio::test]
+async fn subscriber_responses_are_event_streams() {
+    let response = SseBroadcaster::default().handler();
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    assert_eq!(response.headers()["cache-control"], "no-cache");
+}
+
+#[tokio::test]
+async fn publish_without_subscribers_reaches_nobody() {
+    let broadcaster = SseBroadcaster::new(4);
+    assert_eq!(broadcaster.publish(SilcrowEvent::navigate("/home")), 0);
+}
+
+#[tokio::test]
+async fn dropped_subscribers_stop_counting() {
+    let broadcaster = SseBroadcaster::default();
+    let kept = subscribe(&broadcaster);
+    let dropped = subscribe(&broadcaster);
+    drop(dropped);
+
+    assert_eq!(broadcaster.publish(SilcrowEvent::invalidate("#list")), 1);
+    drop(kept);
+}
+
+#[tokio::test]
+async fn events_that_fail_to_serialize_are_not_published() {
+    let broadcaster = SseBroadcaster::default();
+    let mut body = subscribe(&broadcaster);
+
+    assert_eq!(
+        broadcaster.publish(SilcrowEvent::patch(Unserializable, "#x")),
+        0
+    );
+    broadcaster.publish(SilcrowEvent::navigate("/next"));
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("event: navigate"), "got: {frame}");
+}
+
+#[tokio::test]
+async fn lagging_subscriber_skips_to_recent_events() {
+    let broadcaster = SseBroadcaster::new(2);
+    let mut body = subscribe(&broadcaster);
+
+    for n in 0..5 {
+        broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": n}), "#count"));
+    }
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains(r#""n":3"#), "got: {frame}");
+}
+
+// ════════════════════════════════════════════════════════════
+// Event ids and Last-Event-ID resume
+// ════════════════════════════════════════════════════════════
+
+fn resume(broadcaster: &SseBroadcaster, last_event_id: &str) -> axum::body::BodyDataStream {
+    broadcaster
+        .handler_from(LastEventId(Some(last_event_id.to_owned())))
+        .into_response()
+        .into_body()
+        .into_data_stream()
+}
+
+#[tokio::test]
+async fn published_events_get_increasing_ids() {
+    let broadcaster = SseBroadcaster::default();
+    let mut body = subscribe(&broadcaster);
+
+    broadcaster.publish(SilcrowEvent::navigate("/a").with_id("custom"));
+    broadcaster.publish(SilcrowEvent::navigate("/b"));
+
+    assert!(next_frame(&mut body).await.contains("id: 1\n"));
+    assert!(next_frame(&mut body).await.contains("id: 2\n"));
+}
+
+#[tokio::test]
+async fn resume_replays_events_after_last_event_id() {
+    let broadcaster = SseBroadcaster::default();
+    for n in 1..=4 {
+        broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": n}), "#count"));
+    }
+
+    let mut body = resume(&broadcaster, "2");
+    broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": 5}), "#count"));
+
+    for n in 3..=5 {
+        let frame = next_frame(&mut body).await;
+        assert!(frame.contains(&format!("id: {n}\n")), "got: {frame}");
+        assert!(frame.contains(&format!(r#""n":{n}"#)), "got: {frame}");
+    }
+}
+
+#[tokio::test]
+async fn resume_from_the_latest_id_replays_nothing() {
+    let broadcaster = SseBroadcaster::default();
+    broadcaster.publish(SilcrowEvent::navigate("/seen"));
+
+    let mut body = resume(&broadcaster, "1");
+    broadcaster.publish(SilcrowEvent::navigate("/new"));
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("/new"), "got: {frame}");
+}
+
+#[tokio::test]
+async fn unknown_last_event_id_replays_the_whole_buffer() {
+    let broadcaster = SseBroadcaster::new(2);
+    for path in ["/a", "/b", "/c"] {
+        broadcaster.publish(SilcrowEvent::navigate(path));
+    }
+
+    // An id from a previous server run, then one that is not ours at all.
+    for last_event_id in ["99", "not-a-number"] {
+        let mut body = resume(&broadcaster, last_event_id);
+        assert!(next_frame(&mut body).await.contains("/b"));
+        assert!(next_frame(&mut body).await.contains("/c"));
+    }
+}
+
+#[tokio::test]
+async fn max_last_event_id_replays_the_whole_buffer() {
+    let broadcaster = SseBroadcaster::new(2);
+    for path in ["/a", "/b"] {
+        broadcaster.publish(SilcrowEvent::navigate(path));
+    }
+
+    let mut body = resume(&broadcaster, &u64::MAX.to_string());
+    assert!(next_frame(&mut body).await.contains("/a"));
+    assert!(next_frame(&mut body).await.contains("/b"));
+}
+
+#[tokio::test]
+async fn last_event_id_is_extracted_from_the_header() {
+    use axum::extract::FromRequestParts;
+
+    let request = axum::http::Request::builder()
+        .header("Last-Event-ID", " 42 ")
+        .body(())
+        .unwrap();
+    let (mut parts, ()) = request.into_parts();
+    let LastEventId(id) = LastEventId::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert_eq!(id.as_deref(), Some("42"));
+
+    let (mut parts, ()) = axum::http::Request::new(()).into_parts();
+    let missing = LastEventId::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert_eq!(missing, LastEventId(None));
+}
+
+#[tokio::test]
+async fn broadcaster_retry_is_sent_when_the_stream_opens() {
+    let broadcaster = SseBroadcaster::default().with_retry(Duration::from_secs(5));
+    let mut body = subscribe(&broadcaster);
+
+    let frame = next_frame(&mut body).await;
+    assert!(frame.starts_with("retry:5000\n"), "got: {frame}");
+}
+
+// ════════════════════════════════════════════════════════════
+// Topic-filtered subscriptions
+// ════════════════════════════════════════════════════════════
+
+fn subscribe_to(broadcaster: &SseBroadcaster, topics: &[&str]) -> axum::body::BodyDataStream {
+    broadcaster
+        .handler_filtered(topics, LastEventId::default())
+        .into_response()
+        .into_body()
+        .into_data_stream()
+}
+
+#[tokio::test]
+async fn topic_events_reach_only_matching_and_unfiltered_subscribers() {
+    let broadcaster = SseBroadcaster::default();
+    let mut orders = subscribe_to(&broadcaster, &["orders", "alerts"]);
+    let mut chat = subscribe_to(&broadcaster, &["chat"]);
+    let mut everything = subscribe(&broadcaster);
+
+    assert_eq!(
+        broadcaster.publish_to("orders", SilcrowEvent::navigate("/orders/1")),
+        2
+    );
+    assert_eq!(
+        broadcaster.publish_to("chat", SilcrowEvent::navigate("/chat")),
+        2
+    );
+
+    assert!(next_frame(&mut orders).await.contains("/orders/1"));
+    assert!(next_frame(&mut chat).await.contains("/chat"));
+    assert!(next_frame(&mut everything).await.contains("/orders/1"));
+    assert!(next_frame(&mut everything).await.contains("/chat"));
+}
+
+#[tokio::test]
+async fn untagged_events_reach_filtered_subscribers() {
+    let broadcaster = SseBroadcaster::default();
+    let mut alerts = subscribe_to(&broadcaster, &["alerts"]);
+
+    broadcaster.publish_to("orders", SilcrowEvent::navigate("/orders"));
+    assert_eq!(broadcaster.publish(SilcrowEvent::navigate("/everyone")), 1);
+    assert!(next_frame(&mut alerts).await.contains("/everyone"));
+}
+
+#[tokio::test]
+async fn dropped_filtered_subscribers_stop_counting() {
+    let broadcaster = SseBroadcaster::default();
+    let kept = subscribe_to(&broadcaster, &["orders"]);
+    drop(subscribe_to(&broadcaster, &["orders"]));
+
+    assert_eq!(
+        broadcaster.publish_to("orders", SilcrowEvent::invalidate("#orders")),
+        1
+    );
+    drop(kept);
+    assert_eq!(
+        broadcaster.publish_to("orders", SilcrowEvent::invalidate("#orders")),
+        0
+    );
+}
+
+#[tokio::test]
+async fn filtered_resume_replays_only_matching_topics() {
+    let broadcaster = SseBroadcaster::default();
+    broadcaster.publish_to("chat", SilcrowEvent::navigate("/chat"));
+    broadcaster.publish_to("orders", SilcrowEvent::navigate("/orders"));
+
+    let mut body = broadcaster
+        .handler_filtered(&["orders"], LastEventId(Some("0".to_owned())))
+        .into_response()
+        .into_body()
+        .into_data_stream();
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("/orders"), "got: {frame}");
+    assert!(frame.contains("id: 2\n"), "got: {frame}");
+}
+
+#[tokio::test]
+async fn subscribe_filtered_skips_other_topics() {
+    let broadcaster = SseBroadcaster::default();
+    let mut events = Box::pin(broadcaster.subscribe_filtered(&["alerts"]));
+
+    broadcaster.publish_to("orders", SilcrowEvent::navigate("/orders"));
+    broadcaster.publish_to("alerts", SilcrowEvent::navigate("/alerts"));
+
+    let event = tokio::time::timeout(Duration::from_secs(2), events.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.id(), Some("2"));
+}
+
+// ════════════════════════════════════════════════════════════
+// Connection lifecycle hooks
+// ════════════════════════════════════════════════════════════
+
+fn recording_broadcaster() -> (
+    SseBroadcaster,
+    std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+) {
+    let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let (connects, disconnects) = (log.clone(), log.clone());
+    let broadcaster = SseBroadcaster::default()
+        .on_connect(move |c| {
+            connects.lock().unwrap().push(format!(
+                "connect {} {:?} ({})",
+                c.id, c.topics, c.subscribers
+            ))
+        })
+        .on_disconnect(move |c| {
+            disconnects.lock().unwrap().push(format!(
+                "disconnect {} {:?} ({})",
+                c.id, c.topics, c.subscribers
+            ))
+        });
+    (broadcaster, log)
+}
+
+#[tokio::test]
+async fn hooks_track_subscribers_connecting_and_leaving() {
+    let (broadcaster, log) = recording_broadcaster();
+
+    let everything = subscribe(&broadcaster);
+    let orders = subscribe_to(&broadcaster, &["orders", "alerts"]);
+    drop(everything);
+    drop(orders);
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            r#"connect 1 [] (1)"#,
+            r#"connect 2 ["alerts", "orders"] (2)"#,
+            r#"disconnect 1 [] (1)"#,
+            r#"disconnect 2 ["alerts", "orders"] (0)"#,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn hooks_may_publish_without_deadlocking() {
+    let broadcaster = SseBroadcaster::default();
+    let publisher = broadcaster.clone();
+    let broadcaster = broadcaster.on_connect(move |c| {
+        publisher.publish(SilcrowEvent::patch(c.subscribers, "#online"));
+    });
+
+    let mut body = subscribe(&broadcaster);
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains(r#""data":1"#), "got: {frame}");
+}
+
+// ════════════════════════════════════════════════════════════
+// Heartbeat
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn heartbeats_carry_the_current_status() {
+    let ticks = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let counter = ticks.clone();
+    let broadcaster = SseBroadcaster::default()
+        .with_heartbeat(Duration::from_millis(20), move || {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+        });
+    let mut body = subscribe(&broadcaster);
+
+    let first = next_frame(&mut body).await;
+    assert!(first.contains("event: heartbeat\n"), "got: {first}");
+    assert!(first.contains(r#""status":0"#), "got: {first}");
+    assert!(
+        !first.contains("id:"),
+        "heartbeats are not numbered: {first}"
+    );
+    assert!(next_frame(&mut body).await.contains(r#""status":1"#));
+}
+
+#[tokio::test]
+async fn heartbeats_are_interleaved_with_published_events() {
+    let broadcaster = SseBroadcaster::default().with_heartbeat(Duration::from_secs(60), || "ok");
+    let mut body = subscribe(&broadcaster);
+
+    broadcaster.publish(SilcrowEvent::navigate("/now"));
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("event: navigate"), "got: {frame}");
+}
+
+#[tokio::test]
+async fn zero_heartbeat_period_sends_no_heartbeats() {
+    let broadcaster = SseBroadcaster::default().with_heartbeat(Duration::ZERO, || "ok");
+    let mut body = subscribe(&broadcaster);
+
+    broadcaster.publish(SilcrowEvent::navigate("/now"));
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("event: navigate"), "got: {frame}");
+}
+
+// ════════════════════════════════════════════════════════════
+// Replay for new subscribers
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn new_subscribers_see_the_last_n_events() {
+    let broadcaster = SseBroadcaster::default().with_replay(SseReplay::Last(2));
+    for n in 1..=4 {
+        broadcaster.publish(SilcrowEvent::patch(n, "#count"));
+    }
+
+    let mut body = subscribe(&broadcaster);
+    assert!(next_frame(&mut body).await.contains("id: 3\n"));
+    assert!(next_frame(&mut body).await.contains("id: 4\n"));
+    broadcaster.publish(SilcrowEvent::patch(5, "#count"));
+    assert!(next_frame(&mut body).await.contains("id: 5\n"));
+}
+
+#[tokio::test]
+async fn latest_per_target_replays_current_values_only() {
+    let broadcaster = SseBroadcaster::default().with_replay(SseReplay::LatestPerTarget);
+    broadcaster.publish(SilcrowEvent::patch(1, "#cpu"));
+    broadcaster.publish(SilcrowEvent::patch(10, "#mem"));
+    broadcaster.publish(SilcrowEvent::navigate("/elsewhere"));
+    broadcaster.publish(SilcrowEvent::patch(2, "#cpu"));
+
+    let mut events = Box::pin(broadcaster.subscribe());
+    let replayed: Vec<_> = [events.next().await.unwrap(), events.next().await.unwrap()]
+        .iter()
+        .map(|event| event.id().unwrap().to_owned())
+        .collect();
+    assert_eq!(replayed, ["2", "4"]);
+
+    broadcaster.publish(SilcrowEvent::patch(3, "#cpu"));
+    assert_eq!(events.next().await.unwrap().id(), Some("5"));
+}
+
+#[tokio::test]
+async fn latest_per_target_forgets_the_stalest_targets() {
+    let broadcaster = SseBroadcaster::default().with_replay(SseReplay::LatestPerTarget);
+    for n in 0..1025 {
+        broadcaster.publish(SilcrowEvent::patch(n, &format!("#row-{n}")));
+    }
+    // #row-0 (id 1) was forgotten; updating #row-1 moves it to the back.
+    broadcaster.publish(SilcrowEvent::patch(-1, "#row-1"));
+    broadcaster.publish(SilcrowEvent::patch(1025, "#row-1025"));
+
+    let events: Vec<_> = broadcaster.subscribe().take(1024).collect().await;
+    let ids: Vec<_> = events.iter().map(|e| e.id().unwrap().to_owned()).collect();
+    // Adding #row-1025 then forgot #row-2 (id 3), not the updated #row-1.
+    assert_eq!(ids[0], "4");
+    assert_eq!(ids[ids.len() - 2..], ["1026", "1027"]);
+}
+
+#[tokio::test]
+async fn replay_respects_topic_filters() {
+    let broadcaster = SseBroadcaster::default().with_replay(SseReplay::LatestPerTarget);
+    broadcaster.publish_to("orders:1", SilcrowEvent::patch("shipped", "#status"));
+    broadcaster.publish_to("orders:2", SilcrowEvent::patch("pending", "#status"));
+
+    let mut body = subscribe_to(&broadcaster, &["orders:2"]);
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("pending"), "got: {frame}");
+    assert!(frame.contains("id: 2\n"), "got: {frame}");
+}
+
+#[tokio::test]
+async fn resuming_clients_get_missed_events_instead_of_replay() {
+    let broadcaster = SseBroadcaster::default().with_replay(SseReplay::Last(1));
+    for n in 1..=3 {
+        broadcaster.publish(SilcrowEvent::patch(n, "#count"));
+    }
+
+    let mut body = resume(&broadcaster, "1");
+    assert!(next_frame(&mut body).await.contains("id: 2\n"));
+    assert!(next_frame(&mut body).await.contains("id: 3\n"));
+}
+
+// ════════════════════════════════════════════════════════════
+// close_all
+// ════════════════════════════════════════════════════════════
+
+async fn ended(body: &mut axum::body::BodyDataStream) -> bool {
+    tokio::time::timeout(Duration::from_secs(2), body.next())
+        .await
+        .expect("timed out waiting for the stream to end")
+        .is_none()
+}
+
+#[tokio::test]
+async fn close_all_sends_close_then_ends_every_stream() {
+    let broadcaster = SseBroadcaster::default().with_heartbeat(Duration::from_secs(60), || ());
+    let mut a = subscribe(&broadcaster);
+    let mut b = subscribe_to(&broadcaster, &["orders"]);
+
+    assert_eq!(broadcaster.close_all(), 2);
+    for body in [&mut a, &mut b] {
+        let frame = next_frame(body).await;
+        assert!(frame.contains("event: close\n"), "got: {frame}");
+        assert!(ended(body).await);
+    }
+}
+
+#[tokio::test]
+async fn close_is_not_replayed_to_later_subscribers() {
+    let broadcaster = SseBroadcaster::default().with_replay(SseReplay::Last(5));
+    broadcaster.publish(SilcrowEvent::navigate("/before"));
+    broadcaster.close_all();
+
+    let mut body = subscribe(&broadcaster);
+    assert!(next_frame(&mut body).await.contains("/before"));
+    broadcaster.publish(SilcrowEvent::navigate("/after"));
+    assert!(next_frame(&mut body).await.contains("/after"));
+}
+
+// ════════════════════════════════════════════════════════════
+// Lagging subscribers
+// ════════════════════════════════════════════════════════════
+
+fn lag_recording_broadcaster(
+    policy: SseLagPolicy,
+) -> (
+    SseBroadcaster,
+    std::sync::Arc<std::sync::Mutex<Vec<Lagged>>>,
+) {
+    let lags = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = lags.clone();
+    let broadcaster = SseBroadcaster::new(2)
+        .with_lag_policy(policy)
+        .on_lag(move |_, lagged| recorded.lock().unwrap().push(lagged));
+    (broadcaster, lags)
+}
+
+#[tokio::test]
+async fn drop_oldest_reports_the_lag_and_carries_on() {
+    let (broadcaster, lags) = lag_recording_broadcaster(SseLagPolicy::DropOldest);
+    let mut body = subscribe(&broadcaster);
+
+    for n in 0..5 {
+        broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": n}), "#count"));
+    }
+    assert!(next_frame(&mut body).await.contains(r#""n":3"#));
+    assert!(next_frame(&mut body).await.contains(r#""n":4"#));
+    assert_eq!(*lags.lock().unwrap(), [Lagged(3)]);
+}
+
+#[tokio::test]
+async fn disconnect_ends_a_lagging_stream_so_it_resumes() {
+    let (broadcaster, lags) = lag_recording_broadcaster(SseLagPolicy::Disconnect);
+    let mut body = subscribe(&broadcaster);
+
+    for n in 0..5 {
+        broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": n}), "#count"));
+    }
+    assert!(ended(&mut body).await);
+    assert_eq!(*lags.lock().unwrap(), [Lagged(3)]);
+
+    let mut body = resume(&broadcaster, "3");
+    assert!(next_frame(&mut body).await.contains("id: 4\n"));
+}
+
+#[tokio::test]
+async fn block_holds_publishers_until_subscribers_catch_up() {
+    let broadcaster = SseBroadcaster::new(2).with_lag_policy(SseLagPolicy::Block);
+    let mut events = Box::pin(broadcaster.subscribe());
+    broadcaster
+        .publish_wait(SilcrowEvent::invalidate("#a"))
+        .await;
+    broadcaster
+        .publish_wait(SilcrowEvent::invalidate("#b"))
+        .await;
+
+    let publisher = broadcaster.clone();
+    let mut third =
+        tokio::spawn(async move { publisher.publish_wait(SilcrowEvent::invalidate("#c")).await });
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), &mut third)
+            .await
+            .is_err(),
+        "publisher should wait while the subscriber's buffer is full"
+    );
+
+    events.next().await.unwrap();
+    let reached = tokio::time::timeout(Duration::from_secs(2), third)
+        .await
+        .expect("publisher should resume once there is room")
+        .unwrap();
+    assert_eq!(reached, 1);
+    for id in ["2", "3"] {
+        assert_eq!(events.next().await.unwrap().id(), Some(id));
+    }
+}
+
+#[tokio::test]
+async fn block_releases_publishers_when_a_subscriber_leaves() {
+    let broadcaster = SseBroadcaster::new(1).with_lag_policy(SseLagPolicy::Block);
+    let events = Box::pin(broadcaster.subscribe());
+    broadcaster
+        .publish_wait(SilcrowEvent::invalidate("#a"))
+        .await;
+
+    let publisher = broadcaster.clone();
+    let second =
+        tokio::spawn(async move { publisher.publish_wait(SilcrowEvent::invalidate("#b")).await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    drop(events);
+
+    let reached = tokio::time::timeout(Duration::from_secs(2), second)
+        .await
+        .expect("publisher should resume once the subscriber is gone")
+        .unwrap();
+    assert_eq!(reached, 0);
+}
+
+// ════════════════════════════════════════════════════════════
+// Subscriber activity
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn subscriber_count_follows_open_streams() {
+    let broadcaster = SseBroadcaster::default();
+    assert_eq!(broadcaster.subscriber_count(), 0);
+    assert!(!broadcaster.is_active());
+
+    let a = subscribe(&broadcaster);
+    let b = subscribe_to(&broadcaster, &["orders"]);
+    assert_eq!(broadcaster.subscriber_count(), 2);
+    assert!(broadcaster.is_active());
+
+    drop(a);
+    drop(b);
+    assert_eq!(broadcaster.subscriber_count(), 0);
+    assert!(!broadcaster.is_active());
+}
+
+#[tokio::test]
+async fn watch_subscribers_wakes_producers_when_someone_listens() {
+    let broadcaster = SseBroadcaster::default();
+    let mut listeners = broadcaster.watch_subscribers();
+    assert_eq!(*listeners.borrow_and_update(), 0);
+
+    let producer = tokio::spawn(async move {
+        listeners.wait_for(|&n| n > 0).await.unwrap();
+        listeners.wait_for(|&n| n == 0).await.unwrap();
+    });
+    let body = subscribe(&broadcaster);
+    tokio::task::yield_now().await;
+    drop(body);
+
+    tokio::time::timeout(Duration::from_secs(2), producer)
+        .await
+        .expect("producer should see the subscriber come and go")
+        .unwrap();
+}
+
+// ════════════════════════════════════════════════════════════
+// Coalescing
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn coalescing_keeps_the_latest_patch_per_target() {
+    let broadcaster = SseBroadcaster::default().with_coalescing(Duration::from_millis(50));
+    let mut events = Box::pin(broadcaster.subscribe());
+
+    broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": 1}), "#a"));
+    broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": 1}), "#b"));
+    broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": 2}), "#a"));
+    broadcaster.publish(SilcrowEvent::invalidate("#list"));
+    broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": 3}), "#a"));
+
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        received.push(events.next().await.unwrap());
+    }
+    assert_eq!(
+        received,
+        [
+            SilcrowEvent::patch(serde_json::json!({"n": 1}), "#b").with_id("2"),
+            SilcrowEvent::invalidate("#list").with_id("4"),
+            SilcrowEvent::patch(serde_json::json!({"n": 3}), "#a").with_id("5"),
+        ]
+    );
+
+    broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": 4}), "#a"));
+    assert_eq!(
+        events.next().await.unwrap(),
+        SilcrowEvent::patch(serde_json::json!({"n": 4}), "#a").with_id("6")
+    );
+}
+
+#[tokio::test]
+async fn coalescing_does_not_delay_other_events() {
+    let broadcaster = SseBroadcaster::default().with_coalescing(Duration::from_secs(60));
+    let mut events = Box::pin(broadcaster.subscribe());
+
+    broadcaster.publish(SilcrowEvent::navigate("/next"));
+    let event = tokio::time::timeout(Duration::from_secs(2), events.next())
+        .await
+        .expect("events outside a patch window pass straight through");
+    assert_eq!(event.unwrap().id(), Some("1"));
+}
@@ -0,0 +1,101 @@
+// tests/sse_channel.rs
+//
+// SseChannel<T>: typed publishing on an SseRoute, served by its own handler.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::Request;
+use runtime::{SseChannel, SseRoute};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use tower::ServiceExt;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+const ORDERS: SseRoute = SseRoute::new("/events/orders");
+
+#[derive(serde::Serialize)]
+struct Order {
+    id: u32,
+    status: &'static str,
+}
+
+async fn next_frame(body: &mut axum::body::BodyDataStream) -> String {
+    let chunk = tokio::time::timeout(Duration::from_secs(2), body.next())
+        .await
+        .expect("timed out waiting for SSE frame")
+        .expect("SSE stream ended early")
+        .unwrap();
+    String::from_utf8(chunk.to_vec()).unwrap()
+}
+
+async fn open(app: Router, last_event_id: Option<&str>) -> axum::body::BodyDataStream {
+    let mut request = Request::get(ORDERS.path());
+    if let Some(id) = last_event_id {
+        request = request.header("Last-Event-ID", id);
+    }
+    let response = app
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/event-stream",
+        "route handler must serve SSE"
+    );
+    response.into_body().into_data_stream()
+}
+
+// ════════════════════════════════════════════════════════════
+// Typed channels
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn route_handler_streams_typed_patches() {
+    let orders: SseChannel<Order> = SseChannel::new(ORDERS);
+    let app = Router::new().route(orders.route().path(), orders.route_handler());
+    let mut body = open(app, None).await;
+
+    let order = Order {
+        id: 7,
+        status: "shipped",
+    };
+    assert_eq!(orders.patch(&order, "#order-7"), 1);
+
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("event: patch"), "got: {frame}");
+    assert!(frame.contains(r##""target":"#order-7""##), "got: {frame}");
+    assert!(frame.contains(r#""status":"shipped""#), "got: {frame}");
+}
+
+#[tokio::test]
+async fn route_handler_resumes_from_last_event_id() {
+    let orders: SseChannel<Order> = SseChannel::new(ORDERS);
+    for id in 1..=3 {
+        orders.patch(&Order { id, status: "new" }, "#orders");
+    }
+    let app = Router::new().route(ORDERS.path(), orders.route_handler());
+    let mut body = open(app, Some("2")).await;
+
+    let frame = next_frame(&mut body).await;
+    assert!(frame.contains("id: 3\n"), "got: {frame}");
+}
+
+#[test]
+fn clones_share_the_route_and_subscribers() {
+    let orders: SseChannel<Order> = SseChannel::new(ORDERS);
+    let copy = orders.clone();
+    let _subscriber = orders.broadcaster().subscribe();
+
+    assert_eq!(copy.route(), ORDERS);
+    assert_eq!(
+        copy.patch(
+            &Order {
+                id: 1,
+                status: "new"
+            },
+            "#o"
+        ),
+        1
+    );
+}
@@ -0,0 +1,132 @@
+// tests/sse_collector.rs
+//
+// test::SseCollector: SSE bodies parsed back into typed events.
+
+use axum::body::Body;
+use axum::response::IntoResponse;
+use runtime::test::{CollectedEvent, SseCollectError, SseCollector};
+use runtime::{SilcrowEvent, SseBroadcaster, SseOptions, ToastLevel, sse_with};
+use serde_json::json;
+use std::time::Duration;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+fn raw(wire: &'static str) -> SseCollector {
+    SseCollector::from_body(Body::from(wire))
+}
+
+// ════════════════════════════════════════════════════════════
+// Wire parsing
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn parses_fields_and_joins_data_lines() {
+    let mut events = raw("event: note\nid: 4\ndata: one\ndata: two\n\n");
+    let frame = events.next_frame().await.unwrap();
+    assert_eq!(frame.event, "note");
+    assert_eq!(frame.id.as_deref(), Some("4"));
+    assert_eq!(frame.data, "one\ntwo");
+}
+
+#[tokio::test]
+async fn skips_comments_and_remembers_retry() {
+    let mut events = raw(":\n\nretry:5000\n\n: ping\n\ndata: hi\n\n");
+    let frame = events.next_frame().await.unwrap();
+    assert_eq!(frame.event, "message", "unnamed frames default to message");
+    assert_eq!(frame.data, "hi");
+    assert_eq!(events.retry(), Some(Duration::from_millis(5000)));
+}
+
+#[tokio::test]
+async fn handles_crlf_line_endings() {
+    let mut events = raw("event: invalidate\r\ndata: #list\r\n\r\n");
+    assert_eq!(
+        events.next_event().await.unwrap(),
+        CollectedEvent::Invalidate {
+            target: "#list".into()
+        }
+    );
+}
+
+#[tokio::test]
+async fn reports_end_and_drops_unterminated_frame() {
+    let mut events = raw("data: done\n\ndata: partial");
+    assert_eq!(events.next_frame().await.unwrap().data, "done");
+    assert!(matches!(
+        events.next_frame().await,
+        Err(SseCollectError::Ended)
+    ));
+}
+
+#[tokio::test]
+async fn malformed_payload_is_an_error() {
+    let mut events = raw("event: patch\ndata: not json\n\n");
+    assert!(matches!(
+        events.next_event().await,
+        Err(SseCollectError::Malformed(_))
+    ));
+}
+
+// ════════════════════════════════════════════════════════════
+// Silcrow events
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn decodes_events_from_a_stream() {
+    let response = sse_with(
+        SseOptions::default().no_keep_alive(),
+        |emitter| async move {
+            emitter
+                .send(SilcrowEvent::patch(json!({ "count": 3 }), "#counter"))
+                .await?;
+            emitter
+                .send(SilcrowEvent::patch_many([("#a", 1), ("#b", 2)]))
+                .await?;
+            emitter.send(SilcrowEvent::invalidate("#list")).await?;
+            Ok(())
+        },
+    )
+    .into_response();
+
+    let events = SseCollector::new(response).collect_to_end().await.unwrap();
+    assert_eq!(
+        events,
+        vec![
+            CollectedEvent::Patch {
+                target: "#counter".into(),
+                data: json!({ "count": 3 }),
+            },
+            CollectedEvent::PatchMany(vec![("#a".into(), json!(1)), ("#b".into(), json!(2)),]),
+            CollectedEvent::Invalidate {
+                target: "#list".into()
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn times_out_when_nothing_is_sent() {
+    let broadcaster = SseBroadcaster::new(8);
+    let mut events = SseCollector::new(broadcaster.handler().into_response())
+        .with_timeout(Duration::from_millis(50));
+    assert!(matches!(
+        events.next_event().await,
+        Err(SseCollectError::Timeout)
+    ));
+
+    assert!(
+        events
+            .try_next_frame(Duration::from_millis(20))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    broadcaster.publish(SilcrowEvent::toast("Saved", ToastLevel::Success));
+    assert_eq!(
+        events.next_event().await.unwrap(),
+        CollectedEvent::Toast {
+            message: "Saved".into(),
+            level: "success".into(),
+        }
+    );
+}
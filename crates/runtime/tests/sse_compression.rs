@@ -0,0 +1,99 @@
+// tests/sse_compression.rs
+//
+// compress_sse: streaming gzip/deflate for event streams, flushed per event.
+#![cfg(feature = "compression")]
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::Request;
+use axum::middleware::from_fn;
+use axum::routing::get;
+use flate2::write::{GzDecoder, ZlibDecoder};
+use runtime::{SilcrowEvent, SseBroadcaster, compress_sse};
+use std::io::Write;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use tower::ServiceExt;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+fn app(broadcaster: &SseBroadcaster) -> Router {
+    let feed = broadcaster.clone();
+    Router::new()
+        .route("/events", get(move || async move { feed.handler() }))
+        .route("/page", get(|| async { "<p>plain</p>" }))
+        .layer(from_fn(compress_sse))
+}
+
+async fn get_with(app: Router, path: &str, accept: Option<&str>) -> axum::response::Response {
+    let mut request = Request::get(path);
+    if let Some(accept) = accept {
+        request = request.header("accept-encoding", accept);
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn next_chunk(body: &mut axum::body::BodyDataStream) -> Vec<u8> {
+    tokio::time::timeout(Duration::from_secs(2), body.next())
+        .await
+        .expect("timed out waiting for chunk")
+        .expect("stream ended early")
+        .unwrap()
+        .to_vec()
+}
+
+// ════════════════════════════════════════════════════════════
+// Negotiation
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn gzip_events_are_decodable_as_they_arrive() {
+    let broadcaster = SseBroadcaster::default();
+    let response = get_with(app(&broadcaster), "/events", Some("br, gzip")).await;
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+    assert_eq!(response.headers()["vary"], "accept-encoding");
+
+    let mut body = response.into_body().into_data_stream();
+    let mut decoder = GzDecoder::new(Vec::new());
+    for n in 1..=2 {
+        broadcaster.publish(SilcrowEvent::patch(serde_json::json!({"n": n}), "#count"));
+        decoder.write_all(&next_chunk(&mut body).await).unwrap();
+        decoder.flush().unwrap();
+        let text = String::from_utf8(std::mem::take(decoder.get_mut())).unwrap();
+        assert!(text.contains(&format!(r#""n":{n}"#)), "got: {text}");
+        assert!(text.ends_with("\n\n"), "event should be complete: {text}");
+    }
+}
+
+#[tokio::test]
+async fn deflate_is_used_when_gzip_is_not_accepted() {
+    let broadcaster = SseBroadcaster::default();
+    let response = get_with(app(&broadcaster), "/events", Some("gzip;q=0, deflate")).await;
+    assert_eq!(response.headers()["content-encoding"], "deflate");
+
+    let mut body = response.into_body().into_data_stream();
+    broadcaster.publish(SilcrowEvent::navigate("/next"));
+    let mut decoder = ZlibDecoder::new(Vec::new());
+    decoder.write_all(&next_chunk(&mut body).await).unwrap();
+    decoder.flush().unwrap();
+    let text = String::from_utf8(decoder.get_ref().clone()).unwrap();
+    assert!(text.contains("event: navigate"), "got: {text}");
+}
+
+#[tokio::test]
+async fn streams_are_left_alone_without_accept_encoding() {
+    let broadcaster = SseBroadcaster::default();
+    let response = get_with(app(&broadcaster), "/events", None).await;
+    assert!(response.headers().get("content-encoding").is_none());
+
+    let response = get_with(app(&broadcaster), "/events", Some("identity")).await;
+    assert!(response.headers().get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn non_sse_responses_pass_through() {
+    let response = get_with(app(&SseBroadcaster::default()), "/page", Some("gzip")).await;
+    assert!(response.headers().get("content-encoding").is_none());
+}
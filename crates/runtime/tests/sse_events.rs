@@ -114,3 +114,632 @@ fn html_event_with_dynamic_content() {
     let event = SilcrowEvent::html(format!("<p>Hello, {name}</p>"), "#greeting");
     let _sse_event: Event = event.into();
 }
+
+// ════════════════════════════════════════════════════════════
+// sse_interval
+// ════════════════════════════════════════════════════════════
+
+async fn read_sse_frames(response: axum::response::Response, count: usize) -> Vec<String> {
+    use tokio_stream::StreamExt;
+    let mut body = response.into_body().into_data_stream();
+    let mut frames = Vec::new();
+    while frames.len() < count {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(2), body.next())
+            .await
+            .expect("timed out waiting for SSE frame")
+            .expect("SSE stream ended early")
+            .unwrap();
+        frames.push(String::from_utf8(chunk.to_vec()).unwrap());
+    }
+    frames
+}
+
+#[tokio::test]
+async fn sse_interval_skips_none_and_unchanged_events() {
+    use axum::response::IntoResponse;
+    use runtime::sse_interval;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let ticks = Arc::new(AtomicU32::new(0));
+    let counter = ticks.clone();
+    let response = sse_interval(std::time::Duration::from_millis(5), move || {
+        let tick = counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            match tick % 3 {
+                0 => None,
+                _ => Some(SilcrowEvent::patch(
+                    serde_json::json!({ "n": tick / 3 }),
+                    "#stats",
+                )),
+            }
+        }
+    })
+    .into_response();
+
+    let frames = read_sse_frames(response, 2).await;
+    assert!(frames[0].contains(r#""n":0"#), "got: {}", frames[0]);
+    assert!(frames[1].contains(r#""n":1"#), "got: {}", frames[1]);
+    assert!(ticks.load(Ordering::SeqCst) >= 5);
+}
+
+#[tokio::test]
+async fn sse_interval_with_a_zero_period_never_polls() {
+    use axum::response::IntoResponse;
+    use runtime::sse_interval;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio_stream::StreamExt;
+
+    let polls = Arc::new(AtomicU32::new(0));
+    let counter = polls.clone();
+    let response = sse_interval(std::time::Duration::ZERO, move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        async { Some(SilcrowEvent::navigate("/home")) }
+    })
+    .into_response();
+
+    let mut body = response.into_body().into_data_stream();
+    let idle = tokio::time::timeout(std::time::Duration::from_millis(100), body.next()).await;
+    assert!(idle.is_err(), "expected an idle stream, got {idle:?}");
+    assert_eq!(polls.load(Ordering::SeqCst), 0);
+}
+
+// ════════════════════════════════════════════════════════════
+// Teardown guards
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn on_close_runs_when_raw_stream_dropped() {
+    use axum::response::IntoResponse;
+    use runtime::{PilcrowStreamExt, sse_raw};
+    use std::convert::Infallible;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let stream = tokio_stream::pending::<Result<Event, Infallible>>().on_close(async move {
+        let _ = tx.send("released");
+    });
+    let response = sse_raw(stream).into_response();
+    drop(response);
+
+    let released = tokio::time::timeout(std::time::Duration::from_secs(1), rx)
+        .await
+        .expect("cleanup should run")
+        .unwrap();
+    assert_eq!(released, "released");
+}
+
+#[tokio::test]
+async fn emitter_on_close_runs_on_disconnect() {
+    use axum::response::IntoResponse;
+    use runtime::sse_stream;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let response = sse_stream(|emit| async move {
+        emit.on_close(async move {
+            let _ = tx.send(());
+        });
+        emit.closed().await;
+        Ok(())
+    })
+    .into_response();
+
+    // Give the handler a chance to register its cleanup, then disconnect.
+    tokio::task::yield_now().await;
+    drop(response);
+
+    tokio::time::timeout(std::time::Duration::from_secs(1), rx)
+        .await
+        .expect("cleanup should run")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn emitter_on_close_runs_and_stream_ends_when_handler_returns() {
+    use axum::body::to_bytes;
+    use axum::response::IntoResponse;
+    use runtime::sse_stream;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let response = sse_stream(|emit| async move {
+        emit.on_close(async move {
+            let _ = tx.send(());
+        });
+        emit.send(SilcrowEvent::navigate("/done")).await
+    })
+    .into_response();
+
+    let body = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        to_bytes(response.into_body(), usize::MAX),
+    )
+    .await
+    .expect("stream should end once the handler returns")
+    .unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("/done"));
+
+    tokio::time::timeout(std::time::Duration::from_secs(1), rx)
+        .await
+        .expect("cleanup should run")
+        .unwrap();
+}
+
+// ════════════════════════════════════════════════════════════
+// Retry interval
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn with_retry_sets_the_retry_field() {
+    use axum::response::IntoResponse;
+    use runtime::sse_raw;
+    use std::convert::Infallible;
+
+    let event =
+        SilcrowEvent::navigate("/maintenance").with_retry(std::time::Duration::from_secs(3));
+    let stream = tokio_stream::iter([Ok::<Event, Infallible>(event.into())]);
+    let frames = read_sse_frames(sse_raw(stream).into_response(), 1).await;
+    assert!(frames[0].contains("retry:3000\n"), "got: {}", frames[0]);
+    assert!(frames[0].contains("event: navigate"), "got: {}", frames[0]);
+}
+
+#[test]
+fn retry_is_part_of_event_equality() {
+    let plain = SilcrowEvent::invalidate("#list");
+    let retrying = plain
+        .clone()
+        .with_retry(std::time::Duration::from_millis(500));
+    assert_ne!(plain, retrying);
+}
+
+// ════════════════════════════════════════════════════════════
+// Toast and trigger
+// ════════════════════════════════════════════════════════════
+
+async fn render(event: SilcrowEvent) -> String {
+    use axum::response::IntoResponse;
+    use runtime::sse_raw;
+    use std::convert::Infallible;
+
+    let stream = tokio_stream::iter([Ok::<Event, Infallible>(event.into())]);
+    read_sse_frames(sse_raw(stream).into_response(), 1)
+        .await
+        .remove(0)
+}
+
+fn data_json(frame: &str) -> serde_json::Value {
+    let data = frame
+        .lines()
+        .find_map(|line| line.strip_prefix("data:"))
+        .expect("frame has a data line");
+    serde_json::from_str(data).unwrap()
+}
+
+#[tokio::test]
+async fn toast_event_wire_format() {
+    let frame = render(SilcrowEvent::toast("Saved", runtime::ToastLevel::Success)).await;
+    assert!(frame.contains("event: toast\n"), "got: {frame}");
+    assert_eq!(
+        data_json(&frame),
+        serde_json::json!({"message": "Saved", "level": "success"})
+    );
+}
+
+#[tokio::test]
+async fn trigger_event_wire_format() {
+    let frame = render(SilcrowEvent::trigger(
+        "cart:updated",
+        serde_json::json!({"items": 3}),
+    ))
+    .await;
+    assert!(frame.contains("event: trigger\n"), "got: {frame}");
+    assert_eq!(
+        data_json(&frame),
+        serde_json::json!({"event": "cart:updated", "data": {"items": 3}})
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Multi-target patch
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn patch_many_sends_every_target_in_one_event() {
+    let frame = render(SilcrowEvent::patch_many([
+        ("#count", serde_json::json!(3)),
+        ("#total", serde_json::json!({"sum": 42})),
+    ]))
+    .await;
+    assert!(frame.contains("event: patches\n"), "got: {frame}");
+    assert_eq!(
+        data_json(&frame),
+        serde_json::json!([
+            {"target": "#count", "data": 3},
+            {"target": "#total", "data": {"sum": 42}},
+        ])
+    );
+}
+
+#[tokio::test]
+async fn patch_many_with_unserializable_data_is_dropped() {
+    struct Unserializable;
+
+    impl serde::Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("nope"))
+        }
+    }
+
+    let frame = render(SilcrowEvent::patch_many([("#a", Unserializable)])).await;
+    assert!(frame.contains("pilcrow:serialize_error"), "got: {frame}");
+}
+
+// ════════════════════════════════════════════════════════════
+// sse_channel
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn sse_channel_sender_can_be_cloned_into_tasks() {
+    use axum::response::IntoResponse;
+    use runtime::sse_channel;
+
+    let (tx, response) = sse_channel(8);
+    let background = tx.clone();
+    tokio::spawn(async move {
+        background
+            .send(SilcrowEvent::navigate("/from-task"))
+            .await
+            .unwrap();
+    })
+    .await
+    .unwrap();
+    tx.send(SilcrowEvent::invalidate("#list")).await.unwrap();
+    drop(tx);
+
+    let frames = read_sse_frames(response.into_response(), 2).await;
+    assert!(frames[0].contains("/from-task"), "got: {}", frames[0]);
+    assert!(
+        frames[1].contains("event: invalidate"),
+        "got: {}",
+        frames[1]
+    );
+}
+
+#[tokio::test]
+async fn sse_channel_send_fails_once_the_client_is_gone() {
+    use runtime::{EmitError, sse_channel};
+
+    let (tx, response) = sse_channel(8);
+    drop(response);
+
+    assert!(tx.is_closed());
+    let sent = tx.send(SilcrowEvent::navigate("/gone")).await;
+    assert!(
+        matches!(sent, Err(EmitError::Disconnected)),
+        "got: {sent:?}"
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Heartbeat
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn heartbeat_carries_server_time_and_status() {
+    let before = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let frame = render(SilcrowEvent::heartbeat(serde_json::json!({"queue": 3}))).await;
+    assert!(frame.contains("event: heartbeat\n"), "got: {frame}");
+
+    let data = data_json(&frame);
+    assert_eq!(data["status"], serde_json::json!({"queue": 3}));
+    let time = data["time"].as_u64().expect("time is a number");
+    assert!(time >= before && time < before + 60_000, "got: {time}");
+}
+
+// ════════════════════════════════════════════════════════════
+// Close
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn close_event_wire_format() {
+    let frame = render(SilcrowEvent::close()).await;
+    assert!(frame.contains("event: close\n"), "got: {frame}");
+    assert_eq!(data_json(&frame), serde_json::json!({}));
+}
+
+// ════════════════════════════════════════════════════════════
+// sse_with options
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn sse_with_sends_retry_then_numbers_events() {
+    use axum::response::IntoResponse;
+    use runtime::{SseEventIds, SseOptions, sse_with};
+
+    let options = SseOptions::default()
+        .retry(std::time::Duration::from_secs(2))
+        .event_ids(SseEventIds::Sequential)
+        .no_keep_alive();
+    let response = sse_with(options, |emit| async move {
+        emit.send(SilcrowEvent::navigate("/a")).await?;
+        emit.send(SilcrowEvent::navigate("/b").with_id("mine"))
+            .await?;
+        emit.send(SilcrowEvent::navigate("/c")).await
+    })
+    .into_response();
+
+    let frames = read_sse_frames(response, 4).await;
+    assert_eq!(frames[0], "retry:2000\n\n");
+    assert!(frames[1].contains("id: 1\n"), "got: {}", frames[1]);
+    assert!(frames[2].contains("id: mine\n"), "got: {}", frames[2]);
+    assert!(frames[3].contains("id: 3\n"), "got: {}", frames[3]);
+}
+
+#[tokio::test]
+async fn sse_with_keep_alive_interval_is_configurable() {
+    use axum::response::IntoResponse;
+    use runtime::{SseOptions, sse_with};
+
+    let options = SseOptions::default().keep_alive(std::time::Duration::from_millis(20));
+    let response = sse_with(options, |emit| async move {
+        emit.closed().await;
+        Ok(())
+    })
+    .into_response();
+
+    let frames = read_sse_frames(response, 1).await;
+    assert!(frames[0].starts_with(':'), "got: {}", frames[0]);
+}
+
+#[test]
+fn sse_options_defaults_match_sse_stream() {
+    use runtime::{SseEventIds, SseOptions};
+
+    let options = SseOptions::default();
+    assert_eq!(options.keep_alive, Some(std::time::Duration::from_secs(15)));
+    assert_eq!(options.buffer, 32);
+    assert_eq!(options.retry, None);
+    assert_eq!(options.event_ids, SseEventIds::Manual);
+    assert!(!options.prioritized);
+}
+
+/// Sends two HTML frames then a navigation into a two-event buffer before
+/// the client reads anything, and returns the body.
+async fn lagging_body(options: runtime::SseOptions) -> String {
+    use axum::body::to_bytes;
+    use axum::response::IntoResponse;
+    use runtime::sse_with;
+
+    let response = sse_with(options.buffer(2).no_keep_alive(), |emit| async move {
+        emit.send(SilcrowEvent::html("<p>1</p>", "#a")).await?;
+        emit.send(SilcrowEvent::html("<p>2</p>", "#a")).await?;
+        emit.send(SilcrowEvent::navigate("/next")).await
+    })
+    .into_response();
+    let body = tokio::time::timeout(
+        std::time::Duration::from_secs(2),
+        to_bytes(response.into_body(), usize::MAX),
+    )
+    .await
+    .expect("stream should end")
+    .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn sse_with_keeps_send_order_by_default() {
+    let body = lagging_body(runtime::SseOptions::default()).await;
+    let (first, second, nav) = (
+        body.find("<p>1</p>").expect("first frame kept"),
+        body.find("<p>2</p>").expect("second frame kept"),
+        body.find("/next").unwrap(),
+    );
+    assert!(first < second && second < nav, "got: {body}");
+}
+
+#[tokio::test]
+async fn prioritized_sse_sheds_html_for_navigation() {
+    let body = lagging_body(runtime::SseOptions::default().prioritized()).await;
+    assert!(!body.contains("<p>1</p>"), "got: {body}");
+    assert!(
+        body.find("/next").unwrap() < body.find("<p>2</p>").unwrap(),
+        "got: {body}"
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// sse_patch_stream
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn sse_patch_stream_patches_each_item_then_ends() {
+    use axum::response::IntoResponse;
+    use runtime::sse_patch_stream;
+    use runtime::test::{CollectedEvent, SseCollector};
+
+    #[derive(serde::Serialize)]
+    struct Price {
+        symbol: &'static str,
+        cents: u32,
+    }
+
+    let prices = tokio_stream::iter([
+        Price {
+            symbol: "ACME",
+            cents: 1250,
+        },
+        Price {
+            symbol: "ACME",
+            cents: 1275,
+        },
+    ]);
+    let response = sse_patch_stream(prices, "#price").into_response();
+
+    let events = SseCollector::new(response).collect_to_end().await.unwrap();
+    assert_eq!(
+        events,
+        [1250, 1275].map(|cents| CollectedEvent::Patch {
+            target: "#price".into(),
+            data: serde_json::json!({ "symbol": "ACME", "cents": cents }),
+        })
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// sse_guarded
+// ════════════════════════════════════════════════════════════
+
+async fn guarded_feed(request: axum::http::request::Parts) -> axum::response::Response {
+    use axum::http::StatusCode;
+    use runtime::{sse_guarded, sse_patch_stream};
+
+    sse_guarded(
+        request,
+        |parts| async move {
+            match parts.headers.get("authorization") {
+                Some(token) if token == "Bearer letmein" => Ok("alice"),
+                _ => Err((StatusCode::UNAUTHORIZED, "sign in first")),
+            }
+        },
+        |user| sse_patch_stream(tokio_stream::iter([user]), "#user"),
+    )
+    .await
+}
+
+async fn request_guarded_feed(token: Option<&str>) -> axum::response::Response {
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    let app = axum::Router::new().route("/events/me", axum::routing::get(guarded_feed));
+    let mut request = Request::get("/events/me");
+    if let Some(token) = token {
+        request = request.header("authorization", token);
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn sse_guarded_streams_for_authorized_requests() {
+    use runtime::test::{CollectedEvent, SseCollector};
+
+    let response = request_guarded_feed(Some("Bearer letmein")).await;
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let events = SseCollector::new(response).collect_to_end().await.unwrap();
+    assert_eq!(
+        events,
+        [CollectedEvent::Patch {
+            target: "#user".into(),
+            data: serde_json::json!("alice"),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn sse_guarded_refuses_before_the_stream_opens() {
+    let response = request_guarded_feed(Some("Bearer guess")).await;
+    assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+    assert_ne!(
+        response
+            .headers()
+            .get("content-type")
+            .map(|value| value.as_bytes()),
+        Some(&b"text/event-stream"[..])
+    );
+
+    let response = request_guarded_feed(None).await;
+    assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+}
+
+// ════════════════════════════════════════════════════════════
+// Coalescing
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn sse_with_coalesce_collapses_bursts_per_target() {
+    use axum::response::IntoResponse;
+    use runtime::test::{CollectedEvent, SseCollector};
+    use runtime::{SseOptions, sse_with};
+
+    let options = SseOptions::default()
+        .no_keep_alive()
+        .coalesce(std::time::Duration::from_millis(50));
+    let response = sse_with(options, |emit| async move {
+        for tick in 0..100 {
+            emit.send(SilcrowEvent::patch(tick, "#cpu")).await?;
+        }
+        Ok(())
+    })
+    .into_response();
+
+    let events = SseCollector::new(response).collect_to_end().await.unwrap();
+    assert!(events.len() < 100, "got {} events", events.len());
+    assert_eq!(
+        events.last(),
+        Some(&CollectedEvent::Patch {
+            target: "#cpu".into(),
+            data: serde_json::json!(99),
+        })
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Named custom events
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn custom_event_carries_its_name() {
+    let frame = render(SilcrowEvent::custom(
+        "chat:typing",
+        serde_json::json!({"user": "ana"}),
+    ))
+    .await;
+    assert!(frame.contains("event: custom\n"), "got: {frame}");
+    assert_eq!(
+        data_json(&frame),
+        serde_json::json!({"event": "chat:typing", "data": {"user": "ana"}})
+    );
+}
+
+#[tokio::test]
+async fn custom_at_adds_a_dispatch_target() {
+    let frame = render(SilcrowEvent::custom_at("chat:typing", 1, "#room-7")).await;
+    assert_eq!(
+        data_json(&frame),
+        serde_json::json!({"event": "chat:typing", "data": 1, "target": "#room-7"})
+    );
+}
+
+#[tokio::test]
+async fn collector_decodes_custom_targets() {
+    use runtime::test::{CollectedEvent, SseCollector};
+
+    let (emit, sse) = runtime::sse_channel(4);
+    emit.send(SilcrowEvent::custom_at("ping", (), "#status"))
+        .await
+        .unwrap();
+    drop(emit);
+
+    let events = SseCollector::new(axum::response::IntoResponse::into_response(sse))
+        .collect_to_end()
+        .await
+        .unwrap();
+    assert_eq!(
+        events,
+        [CollectedEvent::Custom {
+            event: "ping".into(),
+            data: serde_json::Value::Null,
+            target: Some("#status".into()),
+        }]
+    );
+}
+
+#[test]
+fn targeted_custom_events_have_no_ws_equivalent() {
+    assert!(runtime::WsEvent::try_from(SilcrowEvent::custom_at("ping", (), "#status")).is_err());
+    assert!(runtime::WsEvent::try_from(SilcrowEvent::custom("ping", ())).is_ok());
+}
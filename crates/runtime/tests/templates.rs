@@ -0,0 +1,89 @@
+// tests/templates.rs
+//
+// Template engine integrations: Maud markup, Askama and MiniJinja templates
+// rendered into HtmlResponse, with failed renders answered as a 500.
+#![cfg(any(feature = "maud", feature = "askama", feature = "minijinja"))]
+
+use axum::body::to_bytes;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use runtime::response::HtmlResponse;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn body_of(response: Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Maud
+// ════════════════════════════════════════════════════════════
+
+#[cfg(feature = "maud")]
+#[tokio::test]
+async fn maud_markup_converts_into_html() {
+    let name = "<Ada>";
+    let response: HtmlResponse = maud::html! { h1 { "Hi " (name) } }.into();
+    assert_eq!(
+        body_of(response.into_response()).await,
+        "<h1>Hi &lt;Ada&gt;</h1>"
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// Askama
+// ════════════════════════════════════════════════════════════
+
+#[cfg(feature = "askama")]
+#[derive(askama::Template)]
+#[template(source = "<p>{{ name }}</p>", ext = "html")]
+struct Greeting<'a> {
+    name: &'a str,
+}
+
+#[cfg(feature = "askama")]
+#[tokio::test]
+async fn askama_template_renders_escaped() {
+    let response = HtmlResponse::askama(&Greeting { name: "<Ada>" }).unwrap();
+    assert_eq!(
+        body_of(response.into_response()).await,
+        "<p>&#60;Ada&#62;</p>"
+    );
+}
+
+// ════════════════════════════════════════════════════════════
+// MiniJinja
+// ════════════════════════════════════════════════════════════
+
+#[cfg(feature = "minijinja")]
+#[tokio::test]
+async fn minijinja_template_renders_with_context() {
+    let mut env = minijinja::Environment::new();
+    env.add_template("greeting.html", "<p>{{ name }}</p>")
+        .unwrap();
+    let template = env.get_template("greeting.html").unwrap();
+
+    let response =
+        HtmlResponse::minijinja(&template, serde_json::json!({"name": "<Ada>"})).unwrap();
+    assert_eq!(
+        body_of(response.into_response()).await,
+        "<p>&lt;Ada&gt;</p>"
+    );
+}
+
+#[cfg(feature = "minijinja")]
+#[tokio::test]
+async fn failed_minijinja_render_is_a_500() {
+    let mut env = minijinja::Environment::new();
+    env.set_undefined_behavior(minijinja::UndefinedBehavior::Strict);
+    env.add_template("broken.html", "{{ missing.field }}")
+        .unwrap();
+    let template = env.get_template("broken.html").unwrap();
+
+    let error = HtmlResponse::minijinja(&template, ()).err().unwrap();
+    assert_eq!(
+        error.into_response().status(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
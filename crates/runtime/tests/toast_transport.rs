@@ -0,0 +1,138 @@
+// tests/toast_transport.rs
+//
+// ToastTransport: toasts by cookie, header or JSON body, chosen per router or
+// per route with a ResponseConfig layer.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::Request;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use runtime::{
+    PilcrowError, ResponseConfig, ToastLevel, ToastTransport, html, json, response::ResponseExt,
+};
+use tower::ServiceExt;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn body_json(response: Response) -> serde_json::Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn toast_cookie(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get_all(axum::http::header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find(|cookie| cookie.starts_with("silcrow_toasts="))
+        .map(str::to_owned)
+}
+
+async fn saved_json() -> Response {
+    json(serde_json::json!({"ok": true}))
+        .with_toast("Saved", ToastLevel::Success)
+        .into_response()
+}
+
+async fn saved_html() -> Response {
+    html("<p>hi</p>")
+        .with_toast("Saved", ToastLevel::Success)
+        .into_response()
+}
+
+async fn failing() -> Result<Response, PilcrowError> {
+    Err(std::io::Error::other("disk on fire"))?;
+    Ok(saved_html().await)
+}
+
+async fn get_path(app: Router, path: &str) -> Response {
+    app.oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+fn app(transport: ToastTransport) -> Router {
+    Router::new()
+        .route("/json", get(saved_json))
+        .route("/html", get(saved_html))
+        .layer(ResponseConfig::new().toast_transport(transport))
+}
+
+// ════════════════════════════════════════════════════════════
+// Transports
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn body_is_the_default_and_does_not_also_set_a_cookie() {
+    let response = saved_json().await;
+    assert!(toast_cookie(&response).is_none());
+    assert_eq!(body_json(response).await["_toasts"][0]["message"], "Saved");
+
+    let response = saved_html().await;
+    assert!(toast_cookie(&response).is_some());
+}
+
+#[tokio::test]
+async fn header_transport_skips_cookie_and_body() {
+    let response = get_path(app(ToastTransport::Header), "/json").await;
+    assert!(toast_cookie(&response).is_none());
+    let header = response.headers()["silcrow-toast"].to_str().unwrap();
+    let toasts: serde_json::Value = serde_json::from_str(header).unwrap();
+    assert_eq!(
+        toasts,
+        serde_json::json!([{"message": "Saved", "level": "success"}])
+    );
+    assert_eq!(body_json(response).await, serde_json::json!({"ok": true}));
+
+    let response = get_path(app(ToastTransport::Header), "/html").await;
+    assert!(response.headers().contains_key("silcrow-toast"));
+    assert!(toast_cookie(&response).is_none());
+}
+
+#[tokio::test]
+async fn cookie_transport_applies_to_json() {
+    let response = get_path(app(ToastTransport::Cookie), "/json").await;
+    assert!(toast_cookie(&response).is_some());
+    assert!(body_json(response).await.get("_toasts").is_none());
+}
+
+// ════════════════════════════════════════════════════════════
+// Nesting
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn route_layer_overrides_only_what_it_sets() {
+    let app = Router::new()
+        .route("/html", get(saved_html))
+        .route(
+            "/json",
+            get(saved_json).layer(ResponseConfig::new().toast_transport(ToastTransport::Body)),
+        )
+        .route(
+            "/fail",
+            get(failing).layer(ResponseConfig::new().toast_transport(ToastTransport::Body)),
+        )
+        .layer(
+            ResponseConfig::new()
+                .toast_transport(ToastTransport::Header)
+                .expose_error_details(false),
+        );
+
+    let response = get_path(app.clone(), "/html").await;
+    assert!(response.headers().contains_key("silcrow-toast"));
+
+    let response = get_path(app.clone(), "/json").await;
+    assert!(!response.headers().contains_key("silcrow-toast"));
+    assert_eq!(body_json(response).await["_toasts"][0]["message"], "Saved");
+
+    // The outer layer's redaction still applies under the route's layer.
+    let response = get_path(app, "/fail").await;
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(!String::from_utf8_lossy(&bytes).contains("disk on fire"));
+}
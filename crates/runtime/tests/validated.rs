@@ -0,0 +1,140 @@
+// tests/validated.rs
+//
+// Validated<T>: JSON and form bodies checked with `validator`, failures
+// answered with a 422 in the request's mode.
+#![cfg(feature = "validation")]
+
+use axum::extract::FromRequest;
+use axum::http::{Request, StatusCode, header};
+use axum::response::Response;
+use runtime::Validated;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, PartialEq, Deserialize, Validate)]
+struct Signup {
+    #[validate(email(message = "must be an email address"))]
+    email: String,
+    #[validate(range(min = 18))]
+    age: u8,
+}
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn extract(
+    content_type: &str,
+    accept: &str,
+    body: &'static str,
+) -> Result<Validated<Signup>, Response> {
+    let request = Request::post("/signup")
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT, accept)
+        .body(axum::body::Body::from(body))
+        .unwrap();
+    Validated::from_request(request, &()).await
+}
+
+async fn body_text(response: Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Valid bodies
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn valid_json_and_form_bodies_pass() {
+    let expected = Signup {
+        email: "a@b.co".into(),
+        age: 30,
+    };
+    let from_json = extract(
+        "application/json",
+        "application/json",
+        r#"{"email":"a@b.co","age":30}"#,
+    )
+    .await
+    .unwrap();
+    assert_eq!(from_json.into_inner(), expected);
+
+    let from_form = extract(
+        "application/x-www-form-urlencoded",
+        "text/html",
+        "email=a%40b.co&age=30",
+    )
+    .await
+    .unwrap();
+    assert_eq!(from_form.0, expected);
+}
+
+// ════════════════════════════════════════════════════════════
+// Rejections
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn json_requests_get_a_field_error_map() {
+    let response = extract(
+        "application/json",
+        "application/json",
+        r#"{"email":"nope","age":12}"#,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "errors": {
+                "age": ["range"],
+                "email": ["must be an email address"],
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn html_requests_get_an_error_fragment() {
+    let response = extract(
+        "application/x-www-form-urlencoded",
+        "text/html",
+        "email=nope&age=30",
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body_text(response).await,
+        "<ul class=\"silcrow-errors\" role=\"alert\">\
+         <li data-field=\"email\">must be an email address</li></ul>"
+    );
+}
+
+#[tokio::test]
+async fn undeserializable_bodies_are_422_too() {
+    let response = extract(
+        "application/json",
+        "application/json",
+        r#"{"email":"a@b.co"}"#,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = extract(
+        "application/x-www-form-urlencoded",
+        "text/html",
+        "email=a%40b.co",
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        body_text(response)
+            .await
+            .contains("data-field=\"age\">is required")
+    );
+}
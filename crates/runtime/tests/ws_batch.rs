@@ -0,0 +1,115 @@
+// tests/ws_batch.rs
+//
+// WsStream::send_all — several events in a single frame.
+
+use axum::Router;
+use axum::extract::WebSocketUpgrade;
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::StreamExt;
+use runtime::ws::ws;
+use runtime::{ToastLevel, WsEvent, WsInterceptor};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Swallows toasts on the way out.
+struct NoToasts;
+
+impl WsInterceptor for NoToasts {
+    fn outbound(&self, event: WsEvent) -> Option<WsEvent> {
+        match event {
+            WsEvent::Toast { .. } => None,
+            event => Some(event),
+        }
+    }
+}
+
+fn batch() -> Vec<WsEvent> {
+    vec![
+        WsEvent::patch(serde_json::json!({"n": 1}), "#count"),
+        WsEvent::toast("Saved", ToastLevel::Success),
+        WsEvent::trigger("saved", serde_json::json!({"id": 7})),
+    ]
+}
+
+async fn send_batch(upgrade: WebSocketUpgrade) -> Response {
+    ws(upgrade, |mut stream| async move {
+        let _ = stream.send_all(batch()).await;
+        stream.close().await;
+    })
+}
+
+async fn send_filtered(upgrade: WebSocketUpgrade) -> Response {
+    ws(upgrade, |stream| async move {
+        let mut stream = stream.with_interceptor(NoToasts);
+        let _ = stream.send_all(batch()).await;
+        let _ = stream
+            .send_all(vec![WsEvent::toast("Dropped", ToastLevel::Info)])
+            .await;
+        let _ = stream.send(WsEvent::navigate("/done")).await;
+        stream.close().await;
+    })
+}
+
+async fn serve() -> String {
+    let app = Router::new()
+        .route("/batch", get(send_batch))
+        .route("/filtered", get(send_filtered));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}")
+}
+
+/// Every text frame until the server closes the connection.
+async fn frames(url: &str) -> Vec<serde_json::Value> {
+    let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    let mut frames = Vec::new();
+    while let Ok(Some(Ok(message))) =
+        tokio::time::timeout(Duration::from_secs(2), client.next()).await
+    {
+        match message {
+            Message::Text(text) => frames.push(serde_json::from_str(&text).unwrap()),
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    frames
+}
+
+fn types(frame: &serde_json::Value) -> Vec<&str> {
+    frame
+        .as_array()
+        .expect("batch frame is an array")
+        .iter()
+        .map(|event| event["type"].as_str().unwrap())
+        .collect()
+}
+
+// ════════════════════════════════════════════════════════════
+// send_all
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn send_all_writes_one_array_frame() {
+    let url = serve().await;
+    let frames = frames(&format!("{url}/batch")).await;
+
+    assert_eq!(frames.len(), 1);
+    assert_eq!(types(&frames[0]), ["patch", "toast", "trigger"]);
+    assert_eq!(frames[0][0]["data"]["n"], 1);
+    assert_eq!(frames[0][2]["data"]["id"], 7);
+}
+
+#[tokio::test]
+async fn send_all_runs_interceptors_per_event() {
+    let url = serve().await;
+    let frames = frames(&format!("{url}/filtered")).await;
+
+    // The all-toast batch is dropped entirely rather than sent as `[]`.
+    assert_eq!(frames.len(), 2);
+    assert_eq!(types(&frames[0]), ["patch", "trigger"]);
+    assert_eq!(frames[1]["type"], "navigate");
+}
@@ -0,0 +1,80 @@
+// tests/ws_context.rs
+//
+// WsContext captured from the upgrade request.
+
+use axum::Router;
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::StreamExt;
+use runtime::{WsContext, WsEvent, WsUpgrade};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+fn describe(ctx: &WsContext) -> serde_json::Value {
+    serde_json::json!({
+        "id": ctx.id().to_string(),
+        "remote": ctx.remote_addr().map(|addr| addr.ip().to_string()),
+        "room": ctx.query("room"),
+        "protocol": ctx.protocol(),
+    })
+}
+
+async fn whoami(upgrade: WsUpgrade) -> Response {
+    upgrade.on_connect(|mut stream, ctx| async move {
+        assert_eq!(ctx.id(), stream.id());
+        let _ = stream.send(WsEvent::custom("whoami", describe(&ctx))).await;
+    })
+}
+
+async fn serve(connect_info: bool) -> String {
+    let app = Router::new().route("/ws", get(whoami));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        if connect_info {
+            let app = app.into_make_service_with_connect_info::<SocketAddr>();
+            axum::serve(listener, app).await.unwrap()
+        } else {
+            axum::serve(listener, app).await.unwrap()
+        }
+    });
+    format!("ws://{addr}/ws")
+}
+
+async fn whoami_reply(url: &str) -> serde_json::Value {
+    let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for frame")
+        .unwrap()
+        .unwrap();
+    let frame: serde_json::Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+    assert_eq!(frame["event"], "whoami");
+    frame["data"].clone()
+}
+
+// ════════════════════════════════════════════════════════════
+// Context fields
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn context_carries_query_and_remote_addr() {
+    let url = serve(true).await;
+    let ctx = whoami_reply(&format!("{url}?room=lobby")).await;
+
+    assert!(ctx["id"].as_str().unwrap().starts_with("ws-"));
+    assert_eq!(ctx["remote"], "127.0.0.1");
+    assert_eq!(ctx["room"], "lobby");
+    assert!(ctx["protocol"].is_null());
+}
+
+#[tokio::test]
+async fn remote_addr_is_absent_without_connect_info() {
+    let url = serve(false).await;
+    let ctx = whoami_reply(&url).await;
+
+    assert!(ctx["remote"].is_null());
+    assert!(ctx["room"].is_null());
+}
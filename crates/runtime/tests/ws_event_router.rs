@@ -0,0 +1,139 @@
+// tests/ws_event_router.rs
+//
+// WsEventRouter: name-based dispatch of Custom events with typed payloads.
+
+use axum::Router;
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::ws::ws;
+use runtime::{WsEvent, WsEventRouter, WsSender};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+#[derive(serde::Deserialize)]
+struct ChatMessage {
+    room: String,
+    text: String,
+}
+
+fn router() -> WsEventRouter {
+    WsEventRouter::new()
+        .on(
+            "chat.message",
+            |msg: ChatMessage, tx: WsSender| async move {
+                let reply = format!("{}: {}", msg.room, msg.text);
+                let _ = tx.send(&WsEvent::custom("chat.ack", reply)).await;
+            },
+        )
+        .on("ping", |n: u32, tx: WsSender| async move {
+            let _ = tx.send(&WsEvent::custom("pong", n + 1)).await;
+        })
+        .fallback(|event, tx| async move {
+            let name = match event {
+                WsEvent::Custom { event, .. } => event,
+                _ => "non-custom".to_owned(),
+            };
+            let _ = tx.send(&WsEvent::custom("unhandled", name)).await;
+        })
+}
+
+async fn handler(State(router): State<WsEventRouter>, upgrade: WebSocketUpgrade) -> Response {
+    ws(
+        upgrade,
+        move |stream| async move { router.run(stream).await },
+    )
+}
+
+async fn serve() -> String {
+    let app = Router::new()
+        .route("/ws", get(handler))
+        .with_state(router());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}/ws")
+}
+
+async fn next_json<S>(client: &mut S) -> serde_json::Value
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for frame")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+fn frame(value: serde_json::Value) -> Message {
+    Message::Text(value.to_string())
+}
+
+// ════════════════════════════════════════════════════════════
+// Dispatch
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn routes_by_name_with_typed_payloads() {
+    let url = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let chat = serde_json::json!({
+        "type": "custom",
+        "event": "chat.message",
+        "data": {"room": "lobby", "text": "hi"}
+    });
+    client.send(frame(chat)).await.unwrap();
+    let ack = next_json(&mut client).await;
+    assert_eq!(ack["event"], "chat.ack");
+    assert_eq!(ack["data"], "lobby: hi");
+
+    let ping = serde_json::json!({"type": "custom", "event": "ping", "data": 41});
+    client.send(frame(ping)).await.unwrap();
+    assert_eq!(next_json(&mut client).await["data"], 42);
+}
+
+#[tokio::test]
+async fn unclaimed_events_reach_the_fallback() {
+    let url = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let unknown = serde_json::json!({"type": "custom", "event": "wave", "data": null});
+    client.send(frame(unknown)).await.unwrap();
+    assert_eq!(next_json(&mut client).await["data"], "wave");
+
+    let navigate = serde_json::json!({"type": "navigate", "path": "/"});
+    client.send(frame(navigate)).await.unwrap();
+    assert_eq!(next_json(&mut client).await["data"], "non-custom");
+}
+
+#[tokio::test]
+async fn mismatched_payload_is_skipped() {
+    let url = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let bad = serde_json::json!({"type": "custom", "event": "ping", "data": "nope"});
+    client.send(frame(bad)).await.unwrap();
+    let good = serde_json::json!({"type": "custom", "event": "ping", "data": 1});
+    client.send(frame(good)).await.unwrap();
+
+    let reply = next_json(&mut client).await;
+    assert_eq!(reply["event"], "pong");
+    assert_eq!(reply["data"], 2);
+}
+
+#[test]
+fn debug_lists_registered_events() {
+    let router = WsEventRouter::new()
+        .on("b", |_: u32, _| async {})
+        .on("a", |_: u32, _| async {});
+    assert_eq!(
+        format!("{router:?}"),
+        r#"WsEventRouter { events: ["a", "b"], fallback: false }"#
+    );
+}
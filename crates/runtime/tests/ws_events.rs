@@ -216,3 +216,83 @@ fn ws_custom_roundtrip() {
         _ => panic!("Expected Custom variant"),
     }
 }
+
+// ════════════════════════════════════════════════════════════
+// Typed custom payloads
+// ════════════════════════════════════════════════════════════
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct ChatMessage {
+    room: String,
+    body: String,
+}
+
+#[test]
+fn ws_custom_typed_roundtrip() {
+    let message = ChatMessage {
+        room: "lobby".into(),
+        body: "hi".into(),
+    };
+    let event = WsEvent::custom_typed("chat.message", &message).unwrap();
+    let json = serde_json::to_string(&event).unwrap();
+    let restored: WsEvent = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.custom_name(), Some("chat.message"));
+    let decoded: ChatMessage = restored.decode_custom().unwrap().unwrap();
+    assert_eq!(decoded, message);
+}
+
+#[test]
+fn ws_custom_typed_surfaces_serialize_errors() {
+    let mut bad = std::collections::HashMap::new();
+    bad.insert(vec![1u8], "non-string key");
+    assert!(WsEvent::custom_typed("bad", &bad).is_err());
+}
+
+#[test]
+fn ws_decode_custom_reports_shape_mismatch() {
+    let event = WsEvent::custom("chat.message", serde_json::json!({"room": 1}));
+    assert!(event.decode_custom::<ChatMessage>().unwrap().is_err());
+}
+
+#[test]
+fn ws_decode_custom_ignores_other_variants() {
+    let event = WsEvent::navigate("/home");
+    assert!(event.custom_name().is_none());
+    assert!(event.decode_custom::<ChatMessage>().is_none());
+}
+
+// ════════════════════════════════════════════════════════════
+// Response-modifier mirrors (toast, trigger, push_history)
+// ════════════════════════════════════════════════════════════
+
+#[test]
+fn ws_toast_serialization() {
+    let event = WsEvent::toast("Saved", runtime::ToastLevel::Success);
+    let parsed: serde_json::Value = serde_json::to_value(&event).unwrap();
+
+    assert_eq!(parsed["type"], "toast");
+    assert_eq!(parsed["message"], "Saved");
+    assert_eq!(parsed["level"], "success");
+}
+
+#[test]
+fn ws_trigger_serialization() {
+    let event = WsEvent::trigger("cart:updated", serde_json::json!({"count": 3}));
+    let parsed: serde_json::Value = serde_json::to_value(&event).unwrap();
+
+    assert_eq!(parsed["type"], "trigger");
+    assert_eq!(parsed["event"], "cart:updated");
+    assert_eq!(parsed["data"]["count"], 3);
+}
+
+#[test]
+fn ws_push_history_roundtrip() {
+    let json = serde_json::to_string(&WsEvent::push_history("/items?page=2")).unwrap();
+    assert!(json.contains(r#""type":"push_history""#));
+
+    match serde_json::from_str(&json).unwrap() {
+        WsEvent::PushHistory { url } => assert_eq!(url, "/items?page=2"),
+        _ => panic!("Expected PushHistory variant"),
+    }
+}
@@ -0,0 +1,117 @@
+// tests/ws_heartbeat.rs
+//
+// Heartbeat pings and dead-connection detection for ws_with.
+
+use axum::Router;
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::StreamExt;
+use runtime::{WsOptions, ws_with};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Set when the handler future is dropped or returns.
+struct Finished(Arc<AtomicBool>);
+
+impl Drop for Finished {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+fn options() -> WsOptions {
+    WsOptions::default()
+        .heartbeat(Duration::from_millis(20))
+        .idle_timeout(Duration::from_millis(100))
+}
+
+async fn handler(State(finished): State<Arc<AtomicBool>>, upgrade: WebSocketUpgrade) -> Response {
+    serve_with(options(), finished, upgrade)
+}
+
+/// Heartbeat off: nothing should ever be sent or time out.
+async fn quiet_handler(
+    State(finished): State<Arc<AtomicBool>>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let options = options().heartbeat(Duration::ZERO);
+    serve_with(options, finished, upgrade)
+}
+
+fn serve_with(
+    options: WsOptions,
+    finished: Arc<AtomicBool>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    ws_with(upgrade, options, move |mut stream| async move {
+        let _guard = Finished(finished);
+        while let Some(Ok(_)) = stream.recv().await {}
+        std::future::pending::<()>().await;
+    })
+}
+
+async fn serve(finished: Arc<AtomicBool>) -> String {
+    let app = Router::new()
+        .route("/ws", get(handler))
+        .route("/quiet", get(quiet_handler))
+        .with_state(finished);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}/ws")
+}
+
+// ════════════════════════════════════════════════════════════
+// Dead-connection detection
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn silent_client_terminates_handler() {
+    let finished = Arc::new(AtomicBool::new(false));
+    let url = serve(finished.clone()).await;
+    // Never reading means pings are never answered.
+    let (_client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while !finished.load(Ordering::SeqCst) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("handler was not terminated");
+}
+
+#[tokio::test]
+async fn responsive_client_stays_connected() {
+    let finished = Arc::new(AtomicBool::new(false));
+    let url = serve(finished.clone()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    // Reading lets the client answer each ping with a pong.
+    let mut pings = 0;
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(300);
+    while let Ok(Some(Ok(message))) = tokio::time::timeout_at(deadline, client.next()).await {
+        if matches!(message, Message::Ping(_)) {
+            pings += 1;
+        }
+    }
+
+    assert!(pings >= 3, "expected repeated pings, saw {pings}");
+    assert!(!finished.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn zero_heartbeat_sends_no_pings() {
+    let finished = Arc::new(AtomicBool::new(false));
+    let url = serve(finished.clone()).await.replace("/ws", "/quiet");
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let idle = tokio::time::timeout(Duration::from_millis(200), client.next()).await;
+    assert!(idle.is_err(), "expected silence, got {idle:?}");
+    assert!(!finished.load(Ordering::SeqCst));
+}
@@ -0,0 +1,298 @@
+// tests/ws_hub.rs
+//
+// WsHub fan-out over real WebSocket connections.
+
+use axum::Router;
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::StreamExt;
+use runtime::{WsEvent, WsHub};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn hub_handler(State(hub): State<WsHub>, upgrade: WebSocketUpgrade) -> Response {
+    hub.handle(upgrade, |mut stream| async move {
+        while let Some(Ok(_)) = stream.recv().await {}
+    })
+}
+
+/// Joins the room named by each custom event the client sends.
+async fn room_handler(State(hub): State<WsHub>, upgrade: WebSocketUpgrade) -> Response {
+    let rooms = hub.clone();
+    hub.handle(upgrade, move |mut stream| async move {
+        while let Some(Ok(event)) = stream.recv().await {
+            if let WsEvent::Custom { event, .. } = event {
+                rooms.join(event, stream.id());
+            }
+        }
+    })
+}
+
+async fn serve(hub: WsHub) -> String {
+    let app = Router::new()
+        .route("/ws", get(hub_handler))
+        .route("/rooms", get(room_handler))
+        .with_state(hub);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}/ws")
+}
+
+async fn wait_for_connections(hub: &WsHub, count: usize) {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while hub.connection_count() != count {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("connections did not settle");
+}
+
+async fn next_json<S>(client: &mut S) -> serde_json::Value
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for frame")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Fan-out
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn publish_reaches_every_client() {
+    let hub = WsHub::new();
+    let url = serve(hub.clone()).await;
+    let (mut a, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut b, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    wait_for_connections(&hub, 2).await;
+
+    let delivered = hub.publish(WsEvent::patch(serde_json::json!({"n": 1}), "#count"));
+    assert_eq!(delivered, 2);
+
+    for client in [&mut a, &mut b] {
+        let frame = next_json(client).await;
+        assert_eq!(frame["type"], "patch");
+        assert_eq!(frame["data"]["n"], 1);
+    }
+}
+
+#[tokio::test]
+async fn disconnected_clients_are_removed() {
+    let hub = WsHub::new();
+    let url = serve(hub.clone()).await;
+    let (a, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (_b, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    wait_for_connections(&hub, 2).await;
+
+    drop(a);
+    wait_for_connections(&hub, 1).await;
+    assert_eq!(hub.publish(WsEvent::invalidate("#feed")), 1);
+}
+
+// ════════════════════════════════════════════════════════════
+// Rooms
+// ════════════════════════════════════════════════════════════
+
+async fn join_room<S>(client: &mut S, room: &str)
+where
+    S: futures_util::Sink<Message> + Unpin,
+    S::Error: std::fmt::Debug,
+{
+    use futures_util::SinkExt;
+    let join = serde_json::to_string(&WsEvent::custom(room, serde_json::json!(null))).unwrap();
+    client.send(Message::Text(join)).await.unwrap();
+}
+
+async fn wait_for_room(hub: &WsHub, room: &str, size: usize) {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while hub.room_size(room) != size {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("room membership did not settle");
+}
+
+#[tokio::test]
+async fn publish_to_only_reaches_room_members() {
+    let hub = WsHub::new();
+    let url = serve(hub.clone()).await.replace("/ws", "/rooms");
+    let (mut lobby, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut other, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    join_room(&mut lobby, "lobby").await;
+    join_room(&mut other, "other").await;
+    wait_for_room(&hub, "lobby", 1).await;
+    wait_for_room(&hub, "other", 1).await;
+
+    assert_eq!(hub.publish_to("lobby", WsEvent::navigate("/lobby")), 1);
+    assert_eq!(hub.publish_to("other", WsEvent::navigate("/other")), 1);
+    assert_eq!(hub.publish_to("empty", WsEvent::navigate("/none")), 0);
+
+    assert_eq!(next_json(&mut lobby).await["path"], "/lobby");
+    assert_eq!(next_json(&mut other).await["path"], "/other");
+}
+
+#[tokio::test]
+async fn disconnect_removes_room_membership() {
+    let hub = WsHub::new();
+    let url = serve(hub.clone()).await.replace("/ws", "/rooms");
+    let (mut a, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut b, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    join_room(&mut a, "chat").await;
+    join_room(&mut b, "chat").await;
+    wait_for_room(&hub, "chat", 2).await;
+
+    drop(a);
+    wait_for_room(&hub, "chat", 1).await;
+}
+
+// ════════════════════════════════════════════════════════════
+// Shutdown
+// ════════════════════════════════════════════════════════════
+
+async fn expect_close<S>(client: &mut S, code: u16, reason: &str)
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for close")
+        .unwrap()
+        .unwrap();
+    let Message::Close(Some(frame)) = message else {
+        panic!("expected a close frame, got {message:?}");
+    };
+    assert_eq!(u16::from(frame.code), code);
+    assert_eq!(frame.reason, reason);
+}
+
+#[tokio::test]
+async fn shutdown_closes_every_connection() {
+    let hub = WsHub::new();
+    let url = serve(hub.clone()).await;
+    let (mut a, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut b, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    wait_for_connections(&hub, 2).await;
+
+    assert_eq!(hub.shutdown(1001, "server restarting"), 2);
+    assert!(hub.is_shut_down());
+    assert_eq!(hub.connection_count(), 0);
+
+    expect_close(&mut a, 1001, "server restarting").await;
+    expect_close(&mut b, 1001, "server restarting").await;
+}
+
+#[tokio::test]
+async fn connections_after_shutdown_are_closed() {
+    let hub = WsHub::new();
+    let url = serve(hub.clone()).await;
+    hub.shutdown(1012, "restarting");
+
+    let (mut late, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    expect_close(&mut late, 1012, "restarting").await;
+    assert_eq!(hub.connection_count(), 0);
+}
+
+#[tokio::test]
+async fn shutdown_on_fires_cancellation() {
+    let hub = WsHub::new();
+    let (trigger, signal) = tokio::sync::oneshot::channel::<()>();
+    let shutdown = tokio::spawn(hub.shutdown_on(
+        async move {
+            let _ = signal.await;
+        },
+        1001,
+        "bye",
+    ));
+
+    let cancelled = tokio::spawn({
+        let hub = hub.clone();
+        async move { hub.cancelled().await }
+    });
+    assert!(!hub.is_shut_down());
+
+    trigger.send(()).unwrap();
+    shutdown.await.unwrap();
+    tokio::time::timeout(Duration::from_secs(2), cancelled)
+        .await
+        .expect("cancellation was not signalled")
+        .unwrap();
+}
+
+/// Reports how the handler that owns it ended.
+struct Outcome(
+    tokio::sync::mpsc::UnboundedSender<&'static str>,
+    &'static str,
+);
+
+impl Drop for Outcome {
+    fn drop(&mut self) {
+        let _ = self.0.send(self.1);
+    }
+}
+
+/// Serves a handler that, once the hub shuts down, takes `flush` to finish.
+/// It reports `finished`, or `dropped` if it was cut short.
+async fn serve_flushing(
+    hub: WsHub,
+    flush: Duration,
+    outcomes: tokio::sync::mpsc::UnboundedSender<&'static str>,
+) -> String {
+    let app = Router::new().route(
+        "/ws",
+        get(move |upgrade: WebSocketUpgrade| {
+            let hub = hub.clone();
+            let outcomes = outcomes.clone();
+            async move {
+                let cancelled = hub.clone();
+                hub.handle(upgrade, move |_stream| async move {
+                    let mut outcome = Outcome(outcomes, "dropped");
+                    cancelled.cancelled().await;
+                    tokio::time::sleep(flush).await;
+                    outcome.1 = "finished";
+                })
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}/ws")
+}
+
+async fn shut_down_with(grace: Duration, flush: Duration) -> &'static str {
+    let hub = WsHub::new().with_shutdown_grace(grace);
+    let (outcomes, mut outcome) = tokio::sync::mpsc::unbounded_channel();
+    let url = serve_flushing(hub.clone(), flush, outcomes).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    wait_for_connections(&hub, 1).await;
+
+    hub.shutdown(1001, "bye");
+    expect_close(&mut client, 1001, "bye").await;
+    tokio::time::timeout(Duration::from_secs(2), outcome.recv())
+        .await
+        .expect("handler neither finished nor was dropped")
+        .unwrap()
+}
+
+#[tokio::test]
+async fn shutdown_lets_handlers_finish_within_the_grace() {
+    let outcome = shut_down_with(Duration::from_secs(2), Duration::from_millis(50)).await;
+    assert_eq!(outcome, "finished");
+}
+
+#[tokio::test]
+async fn shutdown_drops_handlers_after_the_grace() {
+    let outcome = shut_down_with(Duration::from_millis(20), Duration::from_secs(60)).await;
+    assert_eq!(outcome, "dropped");
+}
@@ -0,0 +1,127 @@
+// tests/ws_intercept.rs
+//
+// Inbound/outbound interceptors on WsStream.
+
+use axum::Router;
+use axum::extract::WebSocketUpgrade;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::{WsDirection, WsEvent, WsInterceptor, WsRecvError, ws::ws};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Rejects custom events named "bad" and never lets raw markup out.
+struct Policy;
+
+impl WsInterceptor for Policy {
+    fn inbound(&self, event: WsEvent) -> Result<WsEvent, WsRecvError> {
+        match event.custom_name() {
+            Some("bad") => Err(WsRecvError::Rejected("bad event".into())),
+            _ => Ok(event),
+        }
+    }
+
+    fn outbound(&self, event: WsEvent) -> Option<WsEvent> {
+        match event {
+            WsEvent::Html { .. } => None,
+            other => Some(other),
+        }
+    }
+}
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+/// Echoes every event back, turning rejections into a "rejected" custom event.
+async fn serve(log: Log) -> String {
+    let handler = move |upgrade: WebSocketUpgrade| {
+        let log = log.clone();
+        async move {
+            ws(upgrade, move |stream| async move {
+                let mut stream = stream
+                    .with_interceptor(move |direction: WsDirection, event: &WsEvent| {
+                        let kind = serde_json::to_value(event).unwrap()["type"].clone();
+                        log.lock().unwrap().push(format!("{direction:?}:{kind}"));
+                    })
+                    .with_interceptor(Policy);
+                while let Some(received) = stream.recv().await {
+                    let reply = match received {
+                        Ok(event) => event,
+                        Err(WsRecvError::Rejected(reason)) => WsEvent::custom("rejected", reason),
+                        Err(_) => break,
+                    };
+                    let _ = stream.send(reply).await;
+                }
+            })
+        }
+    };
+    let app = Router::new().route("/ws", get(handler));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}/ws")
+}
+
+fn text(event: WsEvent) -> Message {
+    Message::Text(serde_json::to_string(&event).unwrap())
+}
+
+async fn next_json<S>(client: &mut S) -> serde_json::Value
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for frame")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Interceptor chain
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn inbound_rejection_reaches_handler() {
+    let url = serve(Log::default()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    client.send(text(WsEvent::custom("bad", ()))).await.unwrap();
+
+    let reply = next_json(&mut client).await;
+    assert_eq!(reply["event"], "rejected");
+    assert_eq!(reply["data"], "bad event");
+}
+
+#[tokio::test]
+async fn outbound_interceptor_can_drop_events() {
+    let url = serve(Log::default()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    client
+        .send(text(WsEvent::html("<b>x</b>", "#a")))
+        .await
+        .unwrap();
+    client.send(text(WsEvent::navigate("/ok"))).await.unwrap();
+
+    // The html echo was dropped, so the navigate echo arrives first.
+    assert_eq!(next_json(&mut client).await["path"], "/ok");
+}
+
+#[tokio::test]
+async fn closure_interceptor_observes_both_directions() {
+    let log = Log::default();
+    let url = serve(log.clone()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    client.send(text(WsEvent::invalidate("#a"))).await.unwrap();
+    next_json(&mut client).await;
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            r#"Inbound:"invalidate""#.to_owned(),
+            r#"Outbound:"invalidate""#.to_owned()
+        ]
+    );
+}
@@ -0,0 +1,163 @@
+// tests/ws_metrics.rs
+//
+// WsMetrics counters fed by streams and hubs.
+
+use axum::Router;
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::{OverflowPolicy, WsEvent, WsHub, WsMetrics, WsOptions, ws_with};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+#[derive(Clone)]
+struct AppState {
+    metrics: WsMetrics,
+    hub: WsHub,
+}
+
+async fn echo(State(app): State<AppState>, upgrade: WebSocketUpgrade) -> Response {
+    let options = WsOptions::default().metrics(app.metrics);
+    ws_with(upgrade, options, |mut stream| async move {
+        while let Some(Ok(event)) = stream.recv().await {
+            let _ = stream.send(event).await;
+        }
+    })
+}
+
+/// Bursts three events into a one-frame queue without yielding to the writer.
+async fn burst(State(app): State<AppState>, upgrade: WebSocketUpgrade) -> Response {
+    let options = WsOptions::default()
+        .queue(1, OverflowPolicy::DropNewest)
+        .metrics(app.metrics);
+    ws_with(upgrade, options, |stream| async move {
+        let sender = stream.sender();
+        for n in 0..3 {
+            let _ = sender.try_send(&WsEvent::custom("n", n));
+        }
+        stream.close().await;
+    })
+}
+
+async fn hub_handler(State(app): State<AppState>, upgrade: WebSocketUpgrade) -> Response {
+    app.hub.handle(upgrade, |mut stream| async move {
+        while let Some(Ok(_)) = stream.recv().await {}
+    })
+}
+
+async fn serve(app: AppState) -> String {
+    let router = Router::new()
+        .route("/echo", get(echo))
+        .route("/burst", get(burst))
+        .route("/hub", get(hub_handler))
+        .with_state(app);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("ws://{addr}")
+}
+
+async fn settle(what: &str, mut done: impl FnMut() -> bool) {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while !done() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("{what} did not settle"));
+}
+
+fn custom(event: &str) -> Message {
+    Message::Text(serde_json::json!({"type": "custom", "event": event, "data": null}).to_string())
+}
+
+// ════════════════════════════════════════════════════════════
+// Streams
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn stream_counts_connections_and_messages() {
+    let metrics = WsMetrics::new();
+    let app = AppState {
+        metrics: metrics.clone(),
+        hub: WsHub::new(),
+    };
+    let url = serve(app).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("{url}/echo"))
+        .await
+        .unwrap();
+    settle("active", || metrics.active_connections() == 1).await;
+
+    for event in ["a", "b"] {
+        client.send(custom(event)).await.unwrap();
+        client.next().await.unwrap().unwrap();
+    }
+    settle("sent", || metrics.messages_sent() == 2).await;
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.messages_received, 2);
+    assert_eq!(snapshot.messages_sent, 2);
+    assert_eq!(snapshot.send_errors, 0);
+    assert!(snapshot.average_queue_depth >= 1.0);
+
+    drop(client);
+    settle("disconnect", || metrics.active_connections() == 0).await;
+}
+
+#[tokio::test]
+async fn overflow_counts_as_send_error() {
+    let metrics = WsMetrics::new();
+    let app = AppState {
+        metrics: metrics.clone(),
+        hub: WsHub::new(),
+    };
+    let url = serve(app).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("{url}/burst"))
+        .await
+        .unwrap();
+    while let Some(Ok(_)) = client.next().await {}
+
+    settle("disconnect", || metrics.active_connections() == 0).await;
+    assert_eq!(metrics.send_errors(), 2);
+    assert_eq!(metrics.messages_sent(), 1);
+}
+
+// ════════════════════════════════════════════════════════════
+// Hubs
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn hub_reports_its_connections() {
+    let metrics = WsMetrics::new();
+    let hub = WsHub::new().with_metrics(metrics.clone());
+    let app = AppState {
+        metrics: WsMetrics::new(),
+        hub: hub.clone(),
+    };
+    let url = serve(app).await;
+    let (mut a, _) = tokio_tungstenite::connect_async(format!("{url}/hub"))
+        .await
+        .unwrap();
+    let (mut b, _) = tokio_tungstenite::connect_async(format!("{url}/hub"))
+        .await
+        .unwrap();
+    settle("active", || metrics.active_connections() == 2).await;
+
+    assert_eq!(hub.publish(WsEvent::navigate("/home")), 2);
+    a.next().await.unwrap().unwrap();
+    b.next().await.unwrap().unwrap();
+    settle("sent", || metrics.messages_sent() == 2).await;
+
+    drop(a);
+    settle("disconnect", || metrics.active_connections() == 1).await;
+}
+
+#[test]
+fn handles_compare_by_identity() {
+    let metrics = WsMetrics::new();
+    assert_eq!(metrics, metrics.clone());
+    assert_ne!(metrics, WsMetrics::new());
+    assert_eq!(metrics.average_queue_depth(), 0.0);
+}
@@ -0,0 +1,128 @@
+// tests/ws_msgpack.rs
+//
+// MessagePack wire format negotiated via Sec-WebSocket-Protocol.
+
+#![cfg(feature = "msgpack")]
+
+use axum::Router;
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::ws::{JSON_PROTOCOL, MSGPACK_PROTOCOL};
+use runtime::{WsEvent, WsHub, ws::ws};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+async fn echo(upgrade: WebSocketUpgrade) -> Response {
+    ws(upgrade, |mut stream| async move {
+        while let Some(Ok(event)) = stream.recv().await {
+            if stream.send(event).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+async fn hub_handler(State(hub): State<WsHub>, upgrade: WebSocketUpgrade) -> Response {
+    hub.handle(upgrade, |mut stream| async move {
+        while let Some(Ok(_)) = stream.recv().await {}
+    })
+}
+
+async fn serve(hub: WsHub) -> String {
+    let app = Router::new()
+        .route("/echo", get(echo))
+        .route("/hub", get(hub_handler))
+        .with_state(hub);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}")
+}
+
+async fn connect(
+    url: &str,
+    protocol: Option<&str>,
+) -> tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>> {
+    let mut request = url.into_client_request().unwrap();
+    if let Some(protocol) = protocol {
+        request
+            .headers_mut()
+            .insert("sec-websocket-protocol", protocol.parse().unwrap());
+    }
+    tokio_tungstenite::connect_async(request).await.unwrap().0
+}
+
+async fn next_frame<S>(client: &mut S) -> Message
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for frame")
+        .unwrap()
+        .unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Negotiation
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn msgpack_client_roundtrips_binary_frames() {
+    let url = serve(WsHub::new()).await;
+    let mut client = connect(&format!("{url}/echo"), Some(MSGPACK_PROTOCOL)).await;
+
+    let event = WsEvent::patch(serde_json::json!({"cpu": 0.75, "mem": 512}), "#stats");
+    let bytes = rmp_serde::to_vec_named(&event).unwrap();
+    client.send(Message::Binary(bytes)).await.unwrap();
+
+    let Message::Binary(reply) = next_frame(&mut client).await else {
+        panic!("expected a binary frame");
+    };
+    match rmp_serde::from_slice::<WsEvent>(&reply).unwrap() {
+        WsEvent::Patch { target, data } => {
+            assert_eq!(target, "#stats");
+            assert_eq!(data["mem"], 512);
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn clients_without_protocol_keep_json() {
+    let url = serve(WsHub::new()).await;
+    for protocol in [None, Some(JSON_PROTOCOL)] {
+        let mut client = connect(&format!("{url}/echo"), protocol).await;
+        let json = serde_json::to_string(&WsEvent::navigate("/home")).unwrap();
+        client.send(Message::Text(json)).await.unwrap();
+
+        let frame = next_frame(&mut client).await;
+        let parsed: serde_json::Value = serde_json::from_str(frame.to_text().unwrap()).unwrap();
+        assert_eq!(parsed["path"], "/home");
+    }
+}
+
+#[tokio::test]
+async fn hub_publishes_in_each_connections_format() {
+    let hub = WsHub::new();
+    let url = serve(hub.clone()).await;
+    let mut binary = connect(&format!("{url}/hub"), Some(MSGPACK_PROTOCOL)).await;
+    let mut text = connect(&format!("{url}/hub"), None).await;
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while hub.connection_count() != 2 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("connections did not settle");
+
+    assert_eq!(hub.publish(WsEvent::invalidate("#feed")), 2);
+
+    assert!(matches!(next_frame(&mut binary).await, Message::Binary(_)));
+    assert!(matches!(next_frame(&mut text).await, Message::Text(_)));
+}
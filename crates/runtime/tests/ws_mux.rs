@@ -0,0 +1,130 @@
+// tests/ws_mux.rs
+//
+// WsMux: several channels sharing one WebSocket.
+
+use axum::Router;
+use axum::extract::WebSocketUpgrade;
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::ws::ws;
+use runtime::{WsChannel, WsEvent, WsMux};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Answers every event on its channel with `<channel>:<event name>`.
+async fn echo(channel: WsChannel) {
+    let mut channel = channel;
+    while let Some(event) = channel.recv().await {
+        let WsEvent::Custom { event, .. } = event else {
+            continue;
+        };
+        let reply = format!("{}:{event}", channel.name());
+        let _ = channel.send(WsEvent::custom("echo", reply)).await;
+    }
+}
+
+async fn mux_handler(upgrade: WebSocketUpgrade) -> Response {
+    ws(upgrade, |stream| async move {
+        WsMux::new()
+            .channel("chat", echo)
+            .channel("presence", |presence| async move {
+                let _ = presence.send(WsEvent::custom("online", 3)).await;
+                echo(presence).await;
+            })
+            .run(stream)
+            .await
+    })
+}
+
+async fn serve() -> String {
+    let app = Router::new().route("/ws", get(mux_handler));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}/ws")
+}
+
+async fn next_json<S>(client: &mut S) -> serde_json::Value
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for frame")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+fn custom(channel: Option<&str>, event: &str) -> Message {
+    let mut frame = serde_json::json!({"type": "custom", "event": event, "data": null});
+    if let Some(channel) = channel {
+        frame["channel"] = channel.into();
+    }
+    Message::Text(frame.to_string())
+}
+
+// ════════════════════════════════════════════════════════════
+// Routing
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn events_reach_their_channel_and_replies_are_tagged() {
+    let url = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let online = next_json(&mut client).await;
+    assert_eq!(online["channel"], "presence");
+    assert_eq!(online["event"], "online");
+    assert_eq!(online["data"], 3);
+
+    client.send(custom(Some("chat"), "hello")).await.unwrap();
+    let reply = next_json(&mut client).await;
+    assert_eq!(reply["type"], "custom");
+    assert_eq!(reply["channel"], "chat");
+    assert_eq!(reply["data"], "chat:hello");
+
+    client.send(custom(Some("presence"), "ping")).await.unwrap();
+    let reply = next_json(&mut client).await;
+    assert_eq!(reply["channel"], "presence");
+    assert_eq!(reply["data"], "presence:ping");
+}
+
+#[tokio::test]
+async fn untagged_and_unknown_channels_are_dropped() {
+    let url = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    next_json(&mut client).await;
+
+    client.send(custom(None, "lost")).await.unwrap();
+    client.send(custom(Some("nobody"), "lost")).await.unwrap();
+    client.send(custom(Some("chat"), "kept")).await.unwrap();
+
+    assert_eq!(next_json(&mut client).await["data"], "chat:kept");
+}
+
+#[tokio::test]
+async fn plain_recv_ignores_the_channel_tag() {
+    async fn handler(upgrade: WebSocketUpgrade) -> Response {
+        ws(upgrade, |mut stream| async move {
+            while let Some(Ok(event)) = stream.recv().await {
+                let _ = stream.send(event).await;
+            }
+        })
+    }
+    let app = Router::new().route("/ws", get(handler));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws"))
+        .await
+        .unwrap();
+
+    client.send(custom(Some("chat"), "hi")).await.unwrap();
+    let echoed = next_json(&mut client).await;
+    assert_eq!(echoed["event"], "hi");
+    assert!(echoed.get("channel").is_none());
+}
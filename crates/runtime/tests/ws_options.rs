@@ -0,0 +1,97 @@
+// tests/ws_options.rs
+//
+// Frame limits and app-defined subprotocols forwarded through WsOptions.
+
+use axum::Router;
+use axum::extract::WebSocketUpgrade;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::{WsEvent, WsOptions, ws_with};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+fn options() -> WsOptions {
+    WsOptions::default()
+        .max_message_size(256)
+        .max_frame_size(256)
+        .protocols(["chat.v2"])
+}
+
+/// Reports every received event, then a final "ended" once `recv` yields `None`.
+async fn serve() -> (String, mpsc::UnboundedReceiver<String>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let handler = move |upgrade: WebSocketUpgrade| {
+        let tx = tx.clone();
+        async move {
+            ws_with(upgrade, options(), move |mut stream| async move {
+                while let Some(Ok(event)) = stream.recv().await {
+                    let _ = tx.send(event.custom_name().unwrap_or("other").to_owned());
+                }
+                let _ = tx.send("ended".to_owned());
+            })
+        }
+    };
+    let app = Router::new().route("/ws", get(handler));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("ws://{addr}/ws"), rx)
+}
+
+async fn next_report(rx: &mut mpsc::UnboundedReceiver<String>) -> String {
+    tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("timed out waiting for handler")
+        .unwrap()
+}
+
+fn custom(name: &str, body: &str) -> Message {
+    let event = WsEvent::custom(name, serde_json::json!(body));
+    Message::Text(serde_json::to_string(&event).unwrap())
+}
+
+// ════════════════════════════════════════════════════════════
+// Frame limits
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn small_messages_are_accepted() {
+    let (url, mut reports) = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    client.send(custom("hello", "hi")).await.unwrap();
+    assert_eq!(next_report(&mut reports).await, "hello");
+}
+
+#[tokio::test]
+async fn oversized_message_ends_the_connection() {
+    let (url, mut reports) = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    client
+        .send(custom("huge", &"x".repeat(4096)))
+        .await
+        .unwrap();
+    assert_eq!(next_report(&mut reports).await, "ended");
+    let _ = tokio::time::timeout(Duration::from_secs(2), client.next()).await;
+}
+
+// ════════════════════════════════════════════════════════════
+// Subprotocols
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn app_protocol_is_selected_when_offered() {
+    let (url, _reports) = serve().await;
+    let mut request = url.into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("sec-websocket-protocol", "chat.v2".parse().unwrap());
+
+    let (_client, response) = tokio_tungstenite::connect_async(request).await.unwrap();
+    assert_eq!(response.headers()["sec-websocket-protocol"], "chat.v2");
+}
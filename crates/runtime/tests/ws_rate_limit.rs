@@ -0,0 +1,119 @@
+// tests/ws_rate_limit.rs
+//
+// Inbound rate limiting on WsStream::recv.
+
+use axum::Router;
+use axum::extract::WebSocketUpgrade;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::{RateLimit, WsEvent, WsOptions, WsRecvError, ws_with};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Serves a handler that reports "ok", "limited" or "ended" for each `recv`.
+async fn serve(limit: RateLimit) -> (String, mpsc::UnboundedReceiver<&'static str>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let handler = move |upgrade: WebSocketUpgrade| {
+        let tx = tx.clone();
+        async move {
+            let options = WsOptions::default().rate_limit(limit);
+            ws_with(upgrade, options, move |mut stream| async move {
+                loop {
+                    let report = match stream.recv().await {
+                        Some(Ok(_)) => "ok",
+                        Some(Err(WsRecvError::RateLimited)) => "limited",
+                        Some(Err(_)) => "error",
+                        None => "ended",
+                    };
+                    let _ = tx.send(report);
+                    if report == "ended" {
+                        break;
+                    }
+                }
+            })
+        }
+    };
+    let app = Router::new().route("/ws", get(handler));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("ws://{addr}/ws"), rx)
+}
+
+async fn reports(
+    rx: &mut mpsc::UnboundedReceiver<&'static str>,
+    count: usize,
+) -> Vec<&'static str> {
+    let mut seen = Vec::new();
+    for _ in 0..count {
+        let report = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("timed out waiting for handler")
+            .unwrap();
+        seen.push(report);
+    }
+    seen
+}
+
+fn message(n: usize) -> Message {
+    let event = WsEvent::custom("n", serde_json::json!(n));
+    Message::Text(serde_json::to_string(&event).unwrap())
+}
+
+// ════════════════════════════════════════════════════════════
+// Token bucket
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn messages_over_burst_are_rejected() {
+    let (url, mut rx) = serve(RateLimit::new(0.01, 3)).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    for n in 0..5 {
+        client.send(message(n)).await.unwrap();
+    }
+
+    assert_eq!(
+        reports(&mut rx, 5).await,
+        vec!["ok", "ok", "ok", "limited", "limited"]
+    );
+}
+
+#[tokio::test]
+async fn tokens_refill_over_time() {
+    let (url, mut rx) = serve(RateLimit::new(50.0, 1)).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    client.send(message(0)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    client.send(message(1)).await.unwrap();
+
+    assert_eq!(reports(&mut rx, 2).await, vec!["ok", "ok"]);
+}
+
+#[tokio::test]
+async fn close_on_exceed_disconnects_client() {
+    let (url, mut rx) = serve(RateLimit::new(0.01, 2).close_on_exceed()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    for n in 0..4 {
+        client.send(message(n)).await.unwrap();
+    }
+
+    assert_eq!(
+        reports(&mut rx, 4).await,
+        vec!["ok", "ok", "limited", "ended"]
+    );
+    let close = tokio::time::timeout(Duration::from_secs(2), async {
+        loop {
+            match client.next().await {
+                Some(Ok(Message::Close(frame))) => return frame.map(|f| u16::from(f.code)),
+                Some(Ok(_)) => continue,
+                _ => return None,
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for close");
+    assert_eq!(close, Some(1008));
+}
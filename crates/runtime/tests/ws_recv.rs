@@ -0,0 +1,163 @@
+// tests/ws_recv.rs
+//
+// Receiving on WsStream: deadlines, timeouts and close frames.
+
+use axum::Router;
+use axum::extract::WebSocketUpgrade;
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::{WsEvent, WsRecvError, ws::ws};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Ticks while idle, echoes each event, and reports the outcome as a custom event.
+async fn ticking(upgrade: WebSocketUpgrade) -> Response {
+    ws(upgrade, |mut stream| async move {
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(40);
+        let first = match stream.recv_until(deadline).await {
+            Some(Err(WsRecvError::Timeout)) => "timeout",
+            _ => "other",
+        };
+        let _ = stream.send(WsEvent::custom("first", first)).await;
+
+        let mut ticks = 0;
+        loop {
+            match stream.recv_timeout(Duration::from_millis(20)).await {
+                Some(Err(WsRecvError::Timeout)) => ticks += 1,
+                Some(Ok(event)) => {
+                    let _ = stream.send(event).await;
+                    let _ = stream.send(WsEvent::custom("ticks", ticks)).await;
+                }
+                _ => break,
+            }
+        }
+    })
+}
+
+/// Reports the close code and reason the client sent.
+async fn closing(upgrade: WebSocketUpgrade, tx: mpsc::UnboundedSender<String>) -> Response {
+    ws(upgrade, move |mut stream| async move {
+        while let Some(result) = stream.recv().await {
+            match result {
+                Err(WsRecvError::Closed { code, reason }) => {
+                    let _ = tx.send(format!("{code:?} {reason}"));
+                    break;
+                }
+                Ok(WsEvent::Custom { event, .. }) if event == "logout" => {
+                    stream.close_with(4001, "logged out").await;
+                    break;
+                }
+                _ => {}
+            }
+        }
+    })
+}
+
+async fn serve() -> String {
+    serve_with_reports().await.0
+}
+
+async fn serve_with_reports() -> (String, mpsc::UnboundedReceiver<String>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let app = Router::new()
+        .route("/ws", get(ticking))
+        .route("/close", get(move |upgrade| closing(upgrade, tx.clone())));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("ws://{addr}/ws"), rx)
+}
+
+async fn next_json<S>(client: &mut S) -> serde_json::Value
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for frame")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+// ════════════════════════════════════════════════════════════
+// Deadlines
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn recv_until_times_out_when_idle() {
+    let url = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let first = next_json(&mut client).await;
+    assert_eq!(first["event"], "first");
+    assert_eq!(first["data"], "timeout");
+}
+
+#[tokio::test]
+async fn recv_timeout_keeps_messages_that_arrive_later() {
+    let url = serve().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    next_json(&mut client).await;
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let hello = serde_json::to_string(&WsEvent::navigate("/hello")).unwrap();
+    client.send(Message::Text(hello)).await.unwrap();
+
+    assert_eq!(next_json(&mut client).await["path"], "/hello");
+    let ticks = next_json(&mut client).await;
+    assert!(ticks["data"].as_u64().unwrap() >= 2);
+}
+
+// ════════════════════════════════════════════════════════════
+// Close frames
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn client_close_code_and_reason_reach_handler() {
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+
+    let (url, mut reports) = serve_with_reports().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(url.replace("/ws", "/close"))
+        .await
+        .unwrap();
+    client
+        .close(Some(CloseFrame {
+            code: CloseCode::Policy,
+            reason: "bad input".into(),
+        }))
+        .await
+        .unwrap();
+
+    let report = tokio::time::timeout(Duration::from_secs(2), reports.recv())
+        .await
+        .expect("timed out waiting for handler")
+        .unwrap();
+    assert_eq!(report, "Some(1008) bad input");
+}
+
+#[tokio::test]
+async fn close_with_sends_code_and_reason() {
+    let (url, _reports) = serve_with_reports().await;
+    let (mut client, _) = tokio_tungstenite::connect_async(url.replace("/ws", "/close"))
+        .await
+        .unwrap();
+    let logout = serde_json::to_string(&WsEvent::custom("logout", ())).unwrap();
+    client.send(Message::Text(logout)).await.unwrap();
+
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for close")
+        .unwrap()
+        .unwrap();
+    let Message::Close(Some(frame)) = message else {
+        panic!("expected a close frame, got {message:?}");
+    };
+    assert_eq!(u16::from(frame.code), 4001);
+    assert_eq!(frame.reason, "logged out");
+}
@@ -0,0 +1,228 @@
+// tests/ws_resume.rs
+//
+// Resumable WsHub sessions: reconnect tokens, room restore and missed-event replay.
+
+use axum::Router;
+use axum::extract::State;
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use runtime::{WsEvent, WsHub, WsUpgrade};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Joins the room named by each custom event the client sends, then echoes it.
+async fn room_handler(State(hub): State<WsHub>, upgrade: WsUpgrade) -> Response {
+    let rooms = hub.clone();
+    hub.handle_resumable(upgrade, move |mut stream, _ctx| async move {
+        while let Some(Ok(event)) = stream.recv().await {
+            if let WsEvent::Custom { event, .. } = event {
+                rooms.join(event.clone(), stream.id());
+                let _ = stream.send(WsEvent::custom("joined", event)).await;
+            }
+        }
+    })
+}
+
+/// Like `room_handler`, but first leaves every restored room except `lobby`,
+/// the way an app re-checks access on reconnect.
+async fn revoking_handler(State(hub): State<WsHub>, upgrade: WsUpgrade) -> Response {
+    let rooms = hub.clone();
+    hub.handle_resumable(upgrade, move |mut stream, _ctx| async move {
+        for room in rooms.rooms_of(stream.id()) {
+            if room != "lobby" {
+                rooms.leave(&room, stream.id());
+            }
+        }
+        while let Some(Ok(event)) = stream.recv().await {
+            if let WsEvent::Custom { event, .. } = event {
+                rooms.join(event.clone(), stream.id());
+                let _ = stream.send(WsEvent::custom("joined", event)).await;
+            }
+        }
+    })
+}
+
+async fn serve(hub: WsHub) -> String {
+    let app = Router::new()
+        .route("/ws", get(room_handler))
+        .route("/revoking", get(revoking_handler))
+        .with_state(hub);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}/ws")
+}
+
+async fn wait_for_connections(hub: &WsHub, count: usize) {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while hub.connection_count() != count {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("connections did not settle");
+}
+
+async fn next_json<S>(client: &mut S) -> serde_json::Value
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("timed out waiting for frame")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+async fn token_of<S>(client: &mut S) -> String
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let frame = next_json(client).await;
+    assert_eq!(frame["type"], "custom");
+    assert_eq!(frame["event"], runtime::ws::RESUME_EVENT);
+    frame["data"]["token"].as_str().unwrap().to_owned()
+}
+
+// ════════════════════════════════════════════════════════════
+// Tokens
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn token_is_sent_first_on_connect() {
+    let hub = WsHub::resumable(16, Duration::from_secs(30));
+    let url = serve(hub.clone()).await;
+    let (mut a, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut b, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let (first, second) = (token_of(&mut a).await, token_of(&mut b).await);
+    assert_eq!(first.len(), 32);
+    assert!(first.bytes().all(|b| b.is_ascii_hexdigit()));
+    assert_ne!(first, second);
+}
+
+#[tokio::test]
+async fn plain_hub_sends_no_token() {
+    let hub = WsHub::new();
+    let url = serve(hub.clone()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    wait_for_connections(&hub, 1).await;
+
+    hub.publish(WsEvent::navigate("/home"));
+    assert_eq!(next_json(&mut client).await["type"], "navigate");
+}
+
+#[tokio::test]
+async fn unknown_token_starts_a_fresh_session() {
+    let hub = WsHub::resumable(16, Duration::from_secs(30));
+    let url = serve(hub.clone()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("{url}?resume=bogus"))
+        .await
+        .unwrap();
+
+    assert_ne!(token_of(&mut client).await, "bogus");
+}
+
+// ════════════════════════════════════════════════════════════
+// Replay
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn reconnect_restores_rooms_and_replays_missed_events() {
+    let hub = WsHub::resumable(16, Duration::from_secs(30));
+    let url = serve(hub.clone()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let token = token_of(&mut client).await;
+
+    let join = serde_json::json!({"type": "custom", "event": "lobby", "data": null});
+    client.send(Message::Text(join.to_string())).await.unwrap();
+    assert_eq!(next_json(&mut client).await["event"], "joined");
+    drop(client);
+    wait_for_connections(&hub, 0).await;
+
+    assert_eq!(hub.room_size("lobby"), 0);
+    hub.publish_to("lobby", WsEvent::patch(serde_json::json!({"n": 1}), "#a"));
+    hub.publish_to(
+        "elsewhere",
+        WsEvent::patch(serde_json::json!({"n": 2}), "#b"),
+    );
+    hub.publish(WsEvent::navigate("/home"));
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("{url}?resume={token}"))
+        .await
+        .unwrap();
+    assert_eq!(token_of(&mut client).await, token);
+    assert_eq!(next_json(&mut client).await["data"]["n"], 1);
+    assert_eq!(next_json(&mut client).await["path"], "/home");
+    assert_eq!(hub.room_size("lobby"), 1);
+}
+
+#[tokio::test]
+async fn replay_keeps_only_the_newest_events() {
+    let hub = WsHub::resumable(2, Duration::from_secs(30));
+    let url = serve(hub.clone()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let token = token_of(&mut client).await;
+    drop(client);
+    wait_for_connections(&hub, 0).await;
+
+    for n in 0..5 {
+        hub.publish(WsEvent::patch(serde_json::json!({"n": n}), "#count"));
+    }
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("{url}?resume={token}"))
+        .await
+        .unwrap();
+    token_of(&mut client).await;
+    assert_eq!(next_json(&mut client).await["data"]["n"], 3);
+    assert_eq!(next_json(&mut client).await["data"]["n"], 4);
+}
+
+#[tokio::test]
+async fn expired_session_is_not_resumed() {
+    let hub = WsHub::resumable(16, Duration::from_millis(20));
+    let url = serve(hub.clone()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let token = token_of(&mut client).await;
+    drop(client);
+    wait_for_connections(&hub, 0).await;
+
+    hub.publish(WsEvent::navigate("/missed"));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("{url}?resume={token}"))
+        .await
+        .unwrap();
+    assert_ne!(token_of(&mut client).await, token);
+    hub.publish(WsEvent::navigate("/live"));
+    assert_eq!(next_json(&mut client).await["path"], "/live");
+}
+
+#[tokio::test]
+async fn handler_can_leave_restored_rooms() {
+    let hub = WsHub::resumable(16, Duration::from_secs(30));
+    let url = serve(hub.clone()).await.replace("/ws", "/revoking");
+    let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let token = token_of(&mut client).await;
+
+    for room in ["lobby", "admin"] {
+        let join = serde_json::json!({"type": "custom", "event": room, "data": null});
+        client.send(Message::Text(join.to_string())).await.unwrap();
+        assert_eq!(next_json(&mut client).await["event"], "joined");
+    }
+    drop(client);
+    wait_for_connections(&hub, 0).await;
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("{url}?resume={token}"))
+        .await
+        .unwrap();
+    assert_eq!(token_of(&mut client).await, token);
+    hub.publish_to("admin", WsEvent::navigate("/admin"));
+    hub.publish_to("lobby", WsEvent::navigate("/lobby"));
+    assert_eq!(next_json(&mut client).await["path"], "/lobby");
+    assert_eq!(hub.room_size("admin"), 0);
+}
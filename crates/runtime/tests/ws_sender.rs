@@ -0,0 +1,203 @@
+// tests/ws_sender.rs
+//
+// Bounded outbound queues and their overflow policies.
+
+use axum::Router;
+use axum::extract::WebSocketUpgrade;
+use axum::response::Response;
+use axum::routing::get;
+use futures_util::StreamExt;
+use runtime::{OverflowPolicy, WsEvent, WsOptions, WsSendError, ws_with};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+// ── Helpers ─────────────────────────────────────────────────
+
+/// Bursts five events into a two-frame queue without yielding to the writer,
+/// then reports which `try_send` calls were accepted.
+fn burst(upgrade: WebSocketUpgrade, policy: OverflowPolicy) -> Response {
+    let options = WsOptions::default().queue(2, policy);
+    ws_with(upgrade, options, |mut stream| async move {
+        let sender = stream.sender();
+        let results: Vec<&str> = (0..5)
+            .map(
+                |n| match sender.try_send(&WsEvent::custom("n", serde_json::json!(n))) {
+                    Ok(()) => "ok",
+                    Err(WsSendError::Full) => "full",
+                    Err(WsSendError::Closed) => "closed",
+                    Err(WsSendError::Encode(_)) => "encode",
+                },
+            )
+            .collect();
+        let _ = stream
+            .send(WsEvent::custom("results", serde_json::json!(results)))
+            .await;
+        stream.close().await;
+    })
+}
+
+/// Fills a three-frame `ByPriority` queue with HTML, a patch and a
+/// navigation, then reports which `try_send` calls were accepted.
+fn mixed_burst(upgrade: WebSocketUpgrade) -> Response {
+    let options = WsOptions::default().queue(3, OverflowPolicy::ByPriority);
+    ws_with(upgrade, options, |mut stream| async move {
+        let sender = stream.sender();
+        let events = [
+            WsEvent::html("h1", "#a"),
+            WsEvent::html("h2", "#a"),
+            WsEvent::custom("n", serde_json::json!(0)),
+            WsEvent::navigate("/n"),
+            WsEvent::html("h3", "#a"),
+        ];
+        let results: Vec<bool> = events.iter().map(|e| sender.try_send(e).is_ok()).collect();
+        let _ = stream
+            .send(WsEvent::custom("results", serde_json::json!(results)))
+            .await;
+        stream.close().await;
+    })
+}
+
+/// Queues ten events, closes, and reports how many frames were still queued
+/// once `close` returned.
+fn flush(upgrade: WebSocketUpgrade, report: mpsc::UnboundedSender<usize>) -> Response {
+    ws_with(upgrade, WsOptions::default(), |stream| async move {
+        let sender = stream.sender();
+        for n in 0..10 {
+            let _ = sender.try_send(&WsEvent::custom("n", serde_json::json!(n)));
+        }
+        stream.close().await;
+        let _ = report.send(sender.queued());
+    })
+}
+
+async fn serve() -> String {
+    serve_reporting(mpsc::unbounded_channel().0).await
+}
+
+async fn serve_reporting(report: mpsc::UnboundedSender<usize>) -> String {
+    let app = Router::new()
+        .route(
+            "/flush",
+            get(move |u| {
+                let report = report.clone();
+                async move { flush(u, report) }
+            }),
+        )
+        .route(
+            "/oldest",
+            get(|u| async move { burst(u, OverflowPolicy::DropOldest) }),
+        )
+        .route(
+            "/newest",
+            get(|u| async move { burst(u, OverflowPolicy::DropNewest) }),
+        )
+        .route(
+            "/disconnect",
+            get(|u| async move { burst(u, OverflowPolicy::Disconnect) }),
+        )
+        .route("/priority", get(|u| async move { mixed_burst(u) }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("ws://{addr}")
+}
+
+/// Collects every frame until the server closes the connection.
+async fn frames(url: &str) -> (Vec<serde_json::Value>, Option<u16>) {
+    let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    let mut events = Vec::new();
+    loop {
+        let next = tokio::time::timeout(Duration::from_secs(2), client.next())
+            .await
+            .expect("timed out waiting for frame");
+        match next {
+            Some(Ok(Message::Text(text))) => events.push(serde_json::from_str(&text).unwrap()),
+            Some(Ok(Message::Close(frame))) => {
+                return (events, frame.map(|f| u16::from(f.code)));
+            }
+            Some(Ok(_)) => continue,
+            _ => return (events, None),
+        }
+    }
+}
+
+fn numbers(events: &[serde_json::Value]) -> Vec<i64> {
+    events
+        .iter()
+        .filter(|e| e["event"] == "n")
+        .map(|e| e["data"].as_i64().unwrap())
+        .collect()
+}
+
+// ════════════════════════════════════════════════════════════
+// Overflow policies
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn drop_oldest_keeps_latest_frames() {
+    let url = serve().await;
+    let (events, _) = frames(&format!("{url}/oldest")).await;
+
+    assert_eq!(numbers(&events), vec![3, 4]);
+    assert_eq!(
+        events.last().unwrap()["data"],
+        serde_json::json!(["ok", "ok", "ok", "ok", "ok"])
+    );
+}
+
+#[tokio::test]
+async fn drop_newest_rejects_overflow() {
+    let url = serve().await;
+    let (events, _) = frames(&format!("{url}/newest")).await;
+
+    assert_eq!(numbers(&events), vec![0, 1]);
+    assert_eq!(
+        events.last().unwrap()["data"],
+        serde_json::json!(["ok", "ok", "full", "full", "full"])
+    );
+}
+
+#[tokio::test]
+async fn disconnect_closes_slow_client() {
+    let url = serve().await;
+    let (events, code) = frames(&format!("{url}/disconnect")).await;
+
+    assert!(events.is_empty());
+    assert_eq!(code, Some(1013));
+}
+
+#[tokio::test]
+async fn by_priority_drains_urgent_frames_first_and_sheds_html() {
+    let url = serve().await;
+    let (events, code) = frames(&format!("{url}/priority")).await;
+
+    let (results, rest): (Vec<_>, Vec<_>) =
+        events.into_iter().partition(|e| e["event"] == "results");
+    assert_eq!(
+        results[0]["data"],
+        serde_json::json!([true, true, true, true, false])
+    );
+    let order: Vec<&str> = rest.iter().map(|e| e["type"].as_str().unwrap()).collect();
+    assert_eq!(order, ["navigate", "custom", "html"]);
+    assert_eq!(rest[2]["markup"], "h2");
+    assert_ne!(code, Some(1013));
+}
+
+// ════════════════════════════════════════════════════════════
+// Closing
+// ════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn close_returns_once_queued_frames_are_written() {
+    let (report, mut queued) = mpsc::unbounded_channel();
+    let url = serve_reporting(report).await;
+    let (events, code) = frames(&format!("{url}/flush")).await;
+
+    assert_eq!(numbers(&events), (0..10).collect::<Vec<_>>());
+    assert_eq!(code, None);
+    let left = tokio::time::timeout(Duration::from_secs(2), queued.recv())
+        .await
+        .expect("close never returned");
+    assert_eq!(left, Some(0));
+}
@@ -2,26 +2,65 @@
 //! This crate is the required entrypoint for convention-based `web` apps.
 
 // ── Response builders ────────────────────────────────────────
+pub use runtime::response::config::ResponseConfig;
+pub use runtime::response::error::PilcrowError;
+pub use runtime::response::json_patch::JsonPatchOp;
 pub use runtime::response::response::{
-    ErrorResponse, JsonResponse, NavigateResponse, ResponseExt, ToastLevel,
+    AdaptedResponse, DownloadResponse, EmptyResponse, ErrorResponse, IntoPilcrow,
+    JsonPatchResponse, JsonResponse, NavigateResponse, ResponseExt, SwapMode, Toast, ToastAction,
+    ToastLevel, ToastTransport,
+};
+#[cfg(feature = "csv")]
+pub use runtime::response::response::{CsvResponse, csv};
+#[cfg(feature = "xml")]
+pub use runtime::response::response::{XmlResponse, xml};
+pub use runtime::response::response::{
+    accepted, download, download_stream, fragment, json, json_patch, navigate, no_content,
+    not_modified, status,
 };
-pub use runtime::response::response::{json, navigate, status};
 
 // ── Request handling ─────────────────────────────────────────
-pub use runtime::{RequestMode, SilcrowRequest};
+pub use runtime::response::headers;
+pub use runtime::{
+    Feature, FieldError, Flash, FlashLayer, FormatParam, IfNoneMatch, InvalidForm, LimitedPayload,
+    Locale, PROTOCOL_VERSION, Payload, PendingToasts, RenderMode, RequestMode, SilcrowForm,
+    SilcrowRequest,
+};
+#[cfg(feature = "validation")]
+pub use runtime::{Validated, ValidationRejection};
 
 // ── Status & response primitives ─────────────────────────────
+pub use runtime::Cookie;
 pub use runtime::Response;
 pub use runtime::StatusCode;
 
 // ── SSE ──────────────────────────────────────────────────────
+#[cfg(feature = "compression")]
+pub use runtime::compress_sse;
 pub use runtime::{
-    EmitError, PilcrowStreamExt, SilcrowEvent, SseEmitter, SseRoute, interval, sse_raw, sse_stream,
-    watch,
+    EmitError, Lagged, LastEventId, PilcrowStreamExt, SilcrowEvent, SseBroadcaster, SseChannel,
+    SseConnection, SseEmitter, SseEventIds, SseLagPolicy, SseOptions, SseReplay, SseRoute,
+    SseSender, interval, sse_channel, sse_guarded, sse_interval, sse_patch_stream, sse_raw,
+    sse_stream, sse_with, watch,
 };
 
 // ── WebSocket ────────────────────────────────────────────────
-pub use runtime::{WsEvent, WsRoute, WsStream};
+pub use runtime::{
+    ConnectionId, OverflowPolicy, RateLimit, RateLimitAction, WsChannel, WsContext, WsDirection,
+    WsEvent, WsEventRouter, WsFormat, WsHub, WsInterceptor, WsMetrics, WsMetricsSnapshot, WsMux,
+    WsOptions, WsRecvError, WsRoute, WsSendError, WsSender, WsStream, WsUpgrade, ws_with,
+};
+
+// ── Live connection limits ───────────────────────────────────
+pub use runtime::live::{
+    ConnectionLimitExceeded, ConnectionLimiter, ConnectionPermit, LimitPolicy,
+};
+
+#[cfg(feature = "redis")]
+pub use runtime::live::{RedisEnvelope, RedisHub};
+
+// ── Live route registry ──────────────────────────────────────
+pub use runtime::live::{LiveEvent, PilcrowLive};
 
 // ── Generated routes ─────────────────────────────────────────
 pub use runtime::{
@@ -32,6 +71,9 @@ pub use runtime::{
 // ── Assets ───────────────────────────────────────────────────
 pub use runtime::assets;
 
+// ── Test helpers ─────────────────────────────────────────────
+pub use runtime::test;
+
 // ── Domain primitives (from pilcrow-core) ────────────────────
 pub use pilcrow_core::{
     ApiEnvelope, AppError, AppResult, BackendConfig, Meta, PilcrowConfig, WebConfig,